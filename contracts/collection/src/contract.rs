@@ -2,19 +2,68 @@
 
 use crate::{Collection, CollectionArgs, CollectionClient, CollectionTrait, errors, events};
 use soroban_sdk::{
-    Address, BytesN, Env, String, Vec, contractimpl, contracttype, panic_with_error,
+    Address, Bytes, BytesN, Env, IntoVal, String, Symbol, TryFromVal, Val, Vec, contractimpl,
+    contracttype, panic_with_error, token, vec, xdr::ToXdr,
 };
 
 #[contracttype]
 pub enum DataKey {
     Admin,
+    PendingAdmin,
+    DeployCount,
+    AllowedWasms,
+    CreationFee,
+    Paused,
+    Stats,
+    MaxCollections,
 }
 
 #[contracttype]
 pub enum CollectionKey {
-    Collections,                // vec contract ID
-    Collectibles(Address, u32), // (contract ID; Token ID) - Owner
-    OwnerCollectibles(Address), // Owner - (contract ID; Token ID)
+    Collections,                        // vec contract ID
+    Collectibles(Address, u32),         // (contract ID; Token ID) - Owner
+    OwnerCollectibles(Address),         // Owner - (contract ID; Token ID)
+    Registered(Address),                // contract ID - whether it is in Collections
+    Info(Address),                      // contract ID - CollectionInfo
+    Archived(Address),                  // contract ID - whether the factory stops minting on it
+    CollectionToken(Address, u32),      // (contract ID; index) - Token ID
+    CollectionTokenIndex(Address, u32), // (contract ID; Token ID) - index
+    CollectionTokenCount(Address),      // contract ID - number of indexed tokens
+}
+
+/// Most collectibles `collection_tokens` looks at in one call, each costs two
+/// storage reads
+pub const MAX_COLLECTION_TOKENS_PAGE: u32 = 32;
+
+/// Collection details cached by the registry
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CollectionInfo {
+    pub name: String,
+    pub symbol: String,
+    pub uri: String,
+    pub max_tokens: u32,
+    /// Ledger of the registration, 0 for collections registered before the cache
+    pub created_ledger: u32,
+}
+
+/// Registry totals, kept up to date by the registry writes
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FactoryStats {
+    pub collection_count: u32,
+    pub total_collectibles: u32,
+    /// Addresses holding at least one collectible
+    pub total_owners: u32,
+}
+
+/// Fee paid by non-admin callers of `create_collection`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct CreationFee {
+    pub asset: Address,
+    pub amount: i128,
+    pub treasury: Address,
 }
 
 #[contractimpl]
@@ -24,34 +73,150 @@ impl CollectionTrait for Collection {
     }
 
     fn upgrade(e: &Env, wasm_hash: BytesN<32>) {
-        let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
+        let admin = Self::get_admin(e);
         admin.require_auth();
 
         e.deployer().update_current_contract_wasm(wasm_hash.clone());
     }
 
+    fn get_admin(e: &Env) -> Address {
+        e.storage()
+            .instance()
+            .get(&DataKey::Admin)
+            .unwrap_or_else(|| panic_with_error!(e, errors::CollectionError::NotAuthorized))
+    }
+
+    fn get_pending_admin(e: &Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::PendingAdmin)
+    }
+
+    fn transfer_admin(e: &Env, new_admin: Address) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage()
+            .instance()
+            .set(&DataKey::PendingAdmin, &new_admin);
+
+        events::AdminTransferStarted { admin, new_admin }.publish(e);
+    }
+
+    fn accept_admin(e: &Env) {
+        let new_admin = Self::get_pending_admin(e)
+            .unwrap_or_else(|| panic_with_error!(e, errors::CollectionError::NoPendingAdmin));
+        new_admin.require_auth();
+
+        let previous_admin = Self::get_admin(e);
+        e.storage().instance().set(&DataKey::Admin, &new_admin);
+        e.storage().instance().remove(&DataKey::PendingAdmin);
+
+        events::AdminTransferred {
+            previous_admin,
+            new_admin,
+        }
+        .publish(e);
+    }
+
+    fn pause(e: &Env) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage().instance().set(&DataKey::Paused, &true);
+
+        events::Paused { admin }.publish(e);
+    }
+
+    fn unpause(e: &Env) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage().instance().set(&DataKey::Paused, &false);
+
+        events::Unpaused { admin }.publish(e);
+    }
+
+    fn is_paused(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
+    }
+
+    fn supports(e: &Env, interface: Symbol) -> bool {
+        ["registry", "upgradeable"]
+            .iter()
+            .any(|name| Symbol::new(e, name) == interface)
+    }
+
     fn create_collection(
         e: &Env,
+        creator: Address,
         wasm_hash: BytesN<32>,
         name: String,
         symbol: String,
         uri: String,
         max_tokens: u32,
+        soulbound: bool,
+        provenance_limit: u32,
+        first_token_id: u32,
+        salt: Option<BytesN<32>>,
     ) -> Address {
-        let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
-        admin.require_auth();
+        creator.require_auth();
+
+        if Self::is_paused(e) {
+            panic_with_error!(&e, &errors::CollectionError::FactoryPaused);
+        }
+        when_below_max_collections(e);
+
+        // Anyone may create a collection for a fee, the admin for free
+        let mut fee_paid = 0;
+        if creator != Self::get_admin(e) {
+            let Some(fee) = Self::creation_fee(e).filter(|fee| fee.amount > 0) else {
+                panic_with_error!(&e, &errors::CollectionError::NotAuthorized);
+            };
+            token::Client::new(e, &fee.asset).transfer(&creator, &fee.treasury, &fee.amount);
+            fee_paid = fee.amount;
+        }
 
-        let salt: BytesN<32> = e.crypto().sha256(&symbol.to_bytes()).into();
+        // An empty list allows any code
+        let allowed_wasms = Self::allowed_wasms(e);
+        if !allowed_wasms.is_empty() && !allowed_wasms.contains(&wasm_hash) {
+            panic_with_error!(&e, &errors::CollectionError::WasmNotAllowed);
+        }
+
+        // Without a salt, the deploy count lets collections share a symbol
+        let salt = salt.unwrap_or_else(|| {
+            let deploy_count: u32 = e
+                .storage()
+                .instance()
+                .get(&DataKey::DeployCount)
+                .unwrap_or(0);
+            e.storage()
+                .instance()
+                .set(&DataKey::DeployCount, &(deploy_count + 1));
+            let mut preimage = symbol.to_bytes();
+            preimage.append(&deploy_count.to_xdr(e));
+            e.crypto().sha256(&preimage).into()
+        });
+        if Self::predict_collection_address(e, salt.clone())
+            .executable()
+            .is_some()
+        {
+            panic_with_error!(&e, &errors::CollectionError::DuplicateCollection);
+        }
         let deployer = e.deployer().with_current_contract(salt);
         let contract_address = deployer.deploy_v2(
             wasm_hash,
             (
-                admin,
+                creator.clone(),
                 e.current_contract_address(),
-                name,
+                name.clone(),
                 symbol.clone(),
-                uri,
+                uri.clone(),
                 max_tokens,
+                soulbound,
+                provenance_limit,
+                first_token_id,
             ),
         );
 
@@ -61,74 +226,390 @@ impl CollectionTrait for Collection {
             .get(&CollectionKey::Collections)
             .unwrap_or(Vec::new(e));
         collections.push_back(contract_address.clone());
-        e.storage()
-            .instance()
-            .set(&CollectionKey::Collections, &collections);
+        set_collections(e, &collections);
+        set_registered(e, &contract_address);
+        set_info(
+            e,
+            &contract_address,
+            &CollectionInfo {
+                name,
+                symbol: symbol.clone(),
+                uri,
+                max_tokens,
+                created_ledger: e.ledger().sequence(),
+            },
+        );
 
         events::CreateCollection {
             symbol,
             contract_address: contract_address.clone(),
+            creator,
+            fee: fee_paid,
         }
         .publish(e);
 
         contract_address
     }
 
+    fn mint_in_collection(
+        e: &Env,
+        collection: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32 {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        when_active(e, &collection);
+
+        // the factory admin mints as the collection admin
+        let token_id: u32 = e.invoke_contract(
+            &collection,
+            &Symbol::new(e, "mint"),
+            vec![
+                e,
+                admin.into_val(e),
+                message.into_val(e),
+                signature.into_val(e),
+                recovery_id.into_val(e),
+                public_key.into_val(e),
+                nonce.into_val(e),
+                expiration_ledger.into_val(e),
+            ],
+        );
+        assign(e, &collection, &admin, token_id);
+
+        token_id
+    }
+
+    fn set_creation_fee(e: &Env, asset: Address, amount: i128, treasury: Address) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if amount < 0 {
+            panic_with_error!(e, errors::CollectionError::InvalidFee);
+        }
+
+        e.storage().instance().set(
+            &DataKey::CreationFee,
+            &CreationFee {
+                asset,
+                amount,
+                treasury,
+            },
+        );
+    }
+
+    fn creation_fee(e: &Env) -> Option<CreationFee> {
+        e.storage().instance().get(&DataKey::CreationFee)
+    }
+
+    fn upgrade_collection(e: &Env, collection: Address, wasm_hash: BytesN<32>) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if !is_registered(e, &collection) {
+            panic_with_error!(&e, &errors::CollectionError::NotRegistered);
+        }
+        if !upgrade_child(e, collection, wasm_hash) {
+            panic_with_error!(&e, &errors::CollectionError::NotAuthorized);
+        }
+    }
+
+    fn upgrade_all_collections(e: &Env, wasm_hash: BytesN<32>, start: u32, limit: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let collections = Self::collections(e);
+        if start >= collections.len() {
+            panic_with_error!(&e, &errors::CollectionError::IndexOutOfBounds);
+        }
+        let end = start.saturating_add(limit).min(collections.len());
+        for index in start..end {
+            let collection = collections.get_unchecked(index);
+            // one collection refusing the upgrade does not hold back the others
+            if !upgrade_child(e, collection.clone(), wasm_hash.clone()) {
+                events::CollectionUpgradeSkipped {
+                    collection,
+                    wasm_hash: wasm_hash.clone(),
+                }
+                .publish(e);
+            }
+        }
+    }
+
+    fn allow_wasm(e: &Env, wasm_hash: BytesN<32>) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let mut allowed_wasms = Self::allowed_wasms(e);
+        if allowed_wasms.contains(&wasm_hash) {
+            return;
+        }
+        allowed_wasms.push_back(wasm_hash.clone());
+        e.storage()
+            .instance()
+            .set(&DataKey::AllowedWasms, &allowed_wasms);
+
+        events::WasmAllowed { wasm_hash }.publish(e);
+    }
+
+    fn revoke_wasm(e: &Env, wasm_hash: BytesN<32>) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let mut allowed_wasms = Self::allowed_wasms(e);
+        let Some(index) = allowed_wasms.first_index_of(&wasm_hash) else {
+            return;
+        };
+        allowed_wasms.remove(index);
+        e.storage()
+            .instance()
+            .set(&DataKey::AllowedWasms, &allowed_wasms);
+
+        events::WasmRevoked { wasm_hash }.publish(e);
+    }
+
+    fn allowed_wasms(e: &Env) -> Vec<BytesN<32>> {
+        e.storage()
+            .instance()
+            .get(&DataKey::AllowedWasms)
+            .unwrap_or(Vec::new(e))
+    }
+
+    fn predict_collection_address(e: &Env, salt: BytesN<32>) -> Address {
+        e.deployer().with_current_contract(salt).deployed_address()
+    }
+
+    fn register_collection(e: &Env, collection: Address) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if Self::is_paused(e) {
+            panic_with_error!(&e, &errors::CollectionError::FactoryPaused);
+        }
+        if is_registered(e, &collection) {
+            panic_with_error!(&e, &errors::CollectionError::DuplicateCollection);
+        }
+        when_below_max_collections(e);
+        // Only NFT contracts answer these
+        let mut info = fetch_info(e, &collection);
+        info.created_ledger = e.ledger().sequence();
+
+        let mut collections = Self::collections(e);
+        collections.push_back(collection.clone());
+        set_collections(e, &collections);
+        set_registered(e, &collection);
+        set_info(e, &collection, &info);
+
+        events::CollectionRegistered { collection }.publish(e);
+    }
+
+    fn deregister_collection(e: &Env, collection: Address) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let mut collections = Self::collections(e);
+        let Some(index) = collections.first_index_of(collection.clone()) else {
+            panic_with_error!(&e, &errors::CollectionError::NonExistentCollection);
+        };
+        collections.remove(index);
+        set_collections(e, &collections);
+        clear_registration(e, collection);
+    }
+
+    fn archive_collection(e: &Env, collection: Address) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if !is_registered(e, &collection) {
+            panic_with_error!(&e, &errors::CollectionError::NotRegistered);
+        }
+        e.storage()
+            .persistent()
+            .set(&CollectionKey::Archived(collection.clone()), &true);
+
+        events::CollectionArchived { collection }.publish(e);
+    }
+
+    fn is_archived(e: &Env, collection: Address) -> bool {
+        e.storage()
+            .persistent()
+            .has(&CollectionKey::Archived(collection))
+    }
+
     fn assign_collectible(e: &Env, collection: Address, to: Address, token_id: u32) {
         // must be call from within the collection contract itself
         collection.require_auth();
 
-        if !Self::collections(e).contains(collection.clone()) {
-            panic_with_error!(&e, &errors::CollectionError::NonExistentCollection);
+        assign(e, &collection, &to, token_id);
+    }
+
+    fn admin_assign_collectible(e: &Env, collection: Address, to: Address, token_id: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        when_active(e, &collection);
+
+        assign(e, &collection, &to, token_id);
+    }
+
+    fn unassign_collectible(e: &Env, collection: Address, token_id: u32) {
+        // must be call from within the collection contract itself
+        collection.require_auth();
+
+        let key = CollectionKey::Collectibles(collection.clone(), token_id);
+        let Some(owner) = e.storage().persistent().get::<_, Address>(&key) else {
+            panic_with_error!(&e, &errors::CollectionError::CollectibleNotFound);
+        };
+        unassign(e, collection, token_id, owner);
+    }
+
+    fn sync_collectible(e: &Env, collection: Address, token_id: u32) {
+        if !is_registered(e, &collection) {
+            panic_with_error!(&e, &errors::CollectionError::NotRegistered);
         }
+        sync(e, &collection, token_id);
+    }
 
-        let collectible = (collection.clone(), token_id);
+    fn sync_collectibles(e: &Env, collection: Address, token_ids: Vec<u32>) {
+        if !is_registered(e, &collection) {
+            panic_with_error!(&e, &errors::CollectionError::NotRegistered);
+        }
+        for token_id in token_ids {
+            sync(e, &collection, token_id);
+        }
+    }
 
-        let owner_address: Option<Address> = e
-            .storage()
+    fn collectibles(e: &Env, from: Address) -> Vec<(Address, u32)> {
+        e.storage()
             .persistent()
-            .get(&CollectionKey::Collectibles(collection.clone(), token_id));
+            .get(&CollectionKey::OwnerCollectibles(from.clone()))
+            .unwrap_or(Vec::new(e))
+    }
+
+    fn collectibles_page(e: &Env, from: Address, start: u32, limit: u32) -> Vec<(Address, u32)> {
+        let collectibles = Self::collectibles(e, from);
+        if start >= collectibles.len() {
+            return Vec::new(e);
+        }
+        let end = start.saturating_add(limit).min(collectibles.len());
+        collectibles.slice(start..end)
+    }
+
+    fn collectibles_in(e: &Env, from: Address, collection: Address) -> Vec<u32> {
+        let mut token_ids = Vec::new(e);
+        for (collectible_collection, token_id) in Self::collectibles(e, from) {
+            if collectible_collection == collection {
+                token_ids.push_back(token_id);
+            }
+        }
+        token_ids
+    }
+
+    fn collectible_count(e: &Env, from: Address) -> u32 {
+        Self::collectibles(e, from).len()
+    }
+
+    fn balance_all(e: &Env, owner: Address) -> u32 {
+        Self::collectible_count(e, owner)
+    }
+
+    fn balances_by_collection(e: &Env, owner: Address) -> Vec<(Address, u32)> {
+        let mut balances: Vec<(Address, u32)> = Vec::new(e);
+        for (collection, _) in Self::collectibles(e, owner) {
+            match balances
+                .iter()
+                .position(|(address, _)| address == collection)
+            {
+                Some(index) => {
+                    let index = index as u32;
+                    let (address, count) = balances.get_unchecked(index);
+                    balances.set(index, (address, count + 1));
+                }
+                None => balances.push_back((collection, 1)),
+            }
+        }
+        balances
+    }
+
+    fn owner_of_collectible(e: &Env, collection: Address, token_id: u32) -> Option<Address> {
+        e.storage()
+            .persistent()
+            .get(&CollectionKey::Collectibles(collection, token_id))
+    }
+
+    fn has_collectible(e: &Env, owner: Address, collection: Address, token_id: u32) -> bool {
+        Self::owner_of_collectible(e, collection, token_id) == Some(owner)
+    }
 
-        // transferring the collectible by removing from previous owner if any
-        if let Some(owner_address) = owner_address {
-            let mut owner_collectibles: Vec<(Address, u32)> = e
+    fn collection_tokens(
+        e: &Env,
+        collection: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<(u32, Address)> {
+        if limit > MAX_COLLECTION_TOKENS_PAGE {
+            panic_with_error!(&e, &errors::CollectionError::InvalidLimit);
+        }
+        let mut tokens = Vec::new(e);
+        let end = start
+            .saturating_add(limit)
+            .min(Self::collection_token_count(e, collection.clone()));
+        for index in start..end {
+            let token_id: u32 = e
                 .storage()
                 .persistent()
-                .get(&CollectionKey::OwnerCollectibles(owner_address.clone()))
-                .unwrap_or(Vec::new(e));
-            let idx_collectible = owner_collectibles
-                .first_index_of(collectible.clone())
-                .unwrap();
-            owner_collectibles.remove(idx_collectible);
-            e.storage().persistent().set(
-                &CollectionKey::OwnerCollectibles(owner_address.clone()),
-                &owner_collectibles,
-            );
+                .get(&CollectionKey::CollectionToken(collection.clone(), index))
+                .unwrap_or_else(|| {
+                    panic_with_error!(&e, &errors::CollectionError::IndexOutOfBounds)
+                });
+            if let Some(owner) = Self::owner_of_collectible(e, collection.clone(), token_id) {
+                tokens.push_back((token_id, owner));
+            }
         }
+        tokens
+    }
 
-        let mut owner_collectibles: Vec<(Address, u32)> = e
-            .storage()
+    fn collection_token_count(e: &Env, collection: Address) -> u32 {
+        e.storage()
             .persistent()
-            .get(&CollectionKey::OwnerCollectibles(to.clone()))
-            .unwrap_or(Vec::new(e));
-        owner_collectibles.push_back(collectible);
-        e.storage().persistent().set(
-            &CollectionKey::OwnerCollectibles(to.clone()),
-            &owner_collectibles,
-        );
+            .get(&CollectionKey::CollectionTokenCount(collection))
+            .unwrap_or(0)
+    }
 
-        // set new owner
-        e.storage().persistent().set(
-            &CollectionKey::Collectibles(collection.clone(), token_id),
-            &to.clone(),
-        );
+    fn set_max_collections(e: &Env, max: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage().instance().set(&DataKey::MaxCollections, &max);
     }
 
-    fn collectibles(e: &Env, from: Address) -> Vec<(Address, u32)> {
+    fn max_collections(e: &Env) -> u32 {
         e.storage()
-            .persistent()
-            .get(&CollectionKey::OwnerCollectibles(from.clone()))
-            .unwrap_or(Vec::new(e))
+            .instance()
+            .get(&DataKey::MaxCollections)
+            .unwrap_or(0)
+    }
+
+    fn stats(e: &Env) -> FactoryStats {
+        // instances older than the counters only know their collections
+        e.storage()
+            .instance()
+            .get(&DataKey::Stats)
+            .unwrap_or_else(|| FactoryStats {
+                collection_count: Self::collections(e).len(),
+                total_collectibles: 0,
+                total_owners: 0,
+            })
+    }
+
+    fn collection_count(e: &Env) -> u32 {
+        Self::stats(e).collection_count
     }
 
     fn collections(e: &Env) -> Vec<Address> {
@@ -137,4 +618,459 @@ impl CollectionTrait for Collection {
             .get(&CollectionKey::Collections)
             .unwrap_or(Vec::new(e))
     }
+
+    fn collection_info(e: &Env, collection: Address) -> CollectionInfo {
+        if !is_registered(e, &collection) {
+            panic_with_error!(&e, &errors::CollectionError::NotRegistered);
+        }
+        e.storage()
+            .persistent()
+            .get(&CollectionKey::Info(collection.clone()))
+            .unwrap_or_else(|| fetch_info(e, &collection))
+    }
+
+    fn collections_page(e: &Env, start: u32, limit: u32) -> Vec<Address> {
+        let collections = Self::collections(e);
+        if start >= collections.len() {
+            return Vec::new(e);
+        }
+        let end = start.saturating_add(limit).min(collections.len());
+        collections.slice(start..end)
+    }
+
+    fn collections_detailed(e: &Env, start: u32, limit: u32) -> Vec<(Address, CollectionInfo)> {
+        let collections = Self::collections(e);
+        let mut detailed = Vec::new(e);
+        let end = start.saturating_add(limit).min(collections.len());
+        for index in start..end {
+            let collection = collections.get_unchecked(index);
+            detailed.push_back((collection.clone(), Self::collection_info(e, collection)));
+        }
+        detailed
+    }
+
+    fn extend_owner_ttl(e: &Env, owner: Address, extend_to: u32) {
+        let extend_to = extend_to.min(e.storage().max_ttl());
+        let key = CollectionKey::OwnerCollectibles(owner.clone());
+        if !e.storage().persistent().has(&key) {
+            return;
+        }
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, extend_to, extend_to);
+        for (collection, token_id) in Self::collectibles(e, owner) {
+            e.storage().persistent().extend_ttl(
+                &CollectionKey::Collectibles(collection.clone(), token_id),
+                extend_to,
+                extend_to,
+            );
+            // the collection index entries of the collectible
+            let index_key = CollectionKey::CollectionTokenIndex(collection.clone(), token_id);
+            let index: Option<u32> = e.storage().persistent().get(&index_key);
+            if let Some(index) = index {
+                for key in [index_key, CollectionKey::CollectionToken(collection, index)] {
+                    e.storage()
+                        .persistent()
+                        .extend_ttl(&key, extend_to, extend_to);
+                }
+            }
+        }
+    }
+
+    fn extend_collection_ttl(e: &Env, collection: Address, extend_to: u32) {
+        let extend_to = extend_to.min(e.storage().max_ttl());
+        for key in [
+            CollectionKey::Registered(collection.clone()),
+            CollectionKey::Info(collection.clone()),
+            CollectionKey::CollectionTokenCount(collection),
+        ] {
+            if e.storage().persistent().has(&key) {
+                e.storage()
+                    .persistent()
+                    .extend_ttl(&key, extend_to, extend_to);
+            }
+        }
+    }
+
+    fn migrate(e: &Env, owners: Vec<Address>) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        // Early releases kept the registry in the instance
+        for owner in owners {
+            let key = CollectionKey::OwnerCollectibles(owner.clone());
+            let Some(collectibles) = e.storage().instance().get::<_, Vec<(Address, u32)>>(&key)
+            else {
+                continue;
+            };
+            // Collectibles assigned since the upgrade are already persistent
+            let mut merged = Self::collectibles(e, owner.clone());
+            let had_collectibles = !merged.is_empty();
+            let mut stats = Self::stats(e);
+            for (collection, token_id) in collectibles.iter() {
+                let collectible_key = CollectionKey::Collectibles(collection.clone(), token_id);
+                e.storage().instance().remove(&collectible_key);
+                match e.storage().persistent().get::<_, Address>(&collectible_key) {
+                    Some(current_owner) if current_owner == owner => {}
+                    // Assigned again since, the admin has to sort it out
+                    Some(_) => panic_with_error!(&e, &errors::CollectionError::AlreadyAssigned),
+                    None => {
+                        e.storage().persistent().set(&collectible_key, &owner);
+                        add_collection_token(e, &collection, token_id);
+                        stats.total_collectibles += 1;
+                    }
+                }
+                if !merged.contains(&(collection.clone(), token_id)) {
+                    merged.push_back((collection, token_id));
+                }
+            }
+            e.storage().instance().remove(&key);
+            e.storage().persistent().set(&key, &merged);
+
+            if !had_collectibles && !merged.is_empty() {
+                stats.total_owners += 1;
+            }
+            set_stats(e, &stats);
+        }
+    }
+
+    fn repair_collections(e: &Env, collections: Vec<Address>) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let max = Self::max_collections(e);
+        if max != 0 && collections.len() > max {
+            panic_with_error!(&e, &errors::CollectionError::TooManyCollections);
+        }
+        let mut repaired: Vec<Address> = Vec::new(e);
+        for collection in collections.iter() {
+            if repaired.contains(&collection) {
+                panic_with_error!(&e, &errors::CollectionError::DuplicateCollection);
+            }
+            repaired.push_back(collection);
+        }
+
+        // Older releases stored a single address instead of the list
+        let previous: Vec<Address> = match e
+            .storage()
+            .instance()
+            .get::<_, Val>(&CollectionKey::Collections)
+        {
+            Some(value) => Vec::try_from_val(e, &value)
+                .unwrap_or_else(|_| vec![e, Address::try_from_val(e, &value).unwrap()]),
+            None => Vec::new(e),
+        };
+
+        // Lost addresses can be recovered from the `create_collection` events
+        set_collections(e, &collections);
+        for collection in previous {
+            if !collections.contains(&collection) {
+                clear_registration(e, collection);
+            }
+        }
+        for collection in collections {
+            set_registered(e, &collection);
+            let info_key = CollectionKey::Info(collection.clone());
+            if !e.storage().persistent().has(&info_key) {
+                set_info(e, &collection, &fetch_info(e, &collection));
+            }
+        }
+    }
+}
+
+// membership of the registry without scanning it, collections registered
+// before the flag existed get it on first use
+fn is_registered(e: &Env, collection: &Address) -> bool {
+    let key = CollectionKey::Registered(collection.clone());
+    if e.storage().persistent().has(&key) {
+        return true;
+    }
+    if Collection::collections(e).contains(collection) {
+        set_registered(e, collection);
+        return true;
+    }
+    false
+}
+
+// upgrade `collection` to `wasm_hash`, false if it refused, the factory admin
+// is also the admin of the collections it deployed
+fn upgrade_child(e: &Env, collection: Address, wasm_hash: BytesN<32>) -> bool {
+    // e.g. the collection has another admin or needs a multisig proposal
+    let result = e.try_invoke_contract::<(), soroban_sdk::Error>(
+        &collection,
+        &Symbol::new(e, "upgrade"),
+        vec![e, wasm_hash.to_val()],
+    );
+    if !matches!(result, Ok(Ok(()))) {
+        return false;
+    }
+
+    events::CollectionUpgraded {
+        collection,
+        wasm_hash,
+    }
+    .publish(e);
+    true
+}
+
+fn set_info(e: &Env, collection: &Address, info: &CollectionInfo) {
+    e.storage()
+        .persistent()
+        .set(&CollectionKey::Info(collection.clone()), info);
+}
+
+// read the details of a collection from the contract itself
+fn fetch_info(e: &Env, collection: &Address) -> CollectionInfo {
+    CollectionInfo {
+        name: call_collection(e, collection, "name"),
+        symbol: call_collection(e, collection, "symbol"),
+        // Collections deployed before the factory answer neither
+        uri: try_call_collection(e, collection, "base_uri")
+            .unwrap_or_else(|| String::from_str(e, "")),
+        max_tokens: try_call_collection(e, collection, "max_tokens").unwrap_or(0),
+        created_ledger: 0,
+    }
+}
+
+fn call_collection<T: TryFromVal<Env, Val>>(e: &Env, collection: &Address, function: &str) -> T {
+    try_call_collection(e, collection, function)
+        .unwrap_or_else(|| panic_with_error!(&e, &errors::CollectionError::InvalidCollection))
+}
+
+fn try_call_collection<T: TryFromVal<Env, Val>>(
+    e: &Env,
+    collection: &Address,
+    function: &str,
+) -> Option<T> {
+    match e.try_invoke_contract::<T, soroban_sdk::Error>(
+        collection,
+        &Symbol::new(e, function),
+        Vec::new(e),
+    ) {
+        Ok(Ok(value)) => Some(value),
+        _ => None,
+    }
+}
+
+// forget `collection` was registered, its collectibles are kept
+fn clear_registration(e: &Env, collection: Address) {
+    e.storage()
+        .persistent()
+        .remove(&CollectionKey::Registered(collection.clone()));
+    e.storage()
+        .persistent()
+        .remove(&CollectionKey::Info(collection.clone()));
+    e.storage()
+        .persistent()
+        .remove(&CollectionKey::Archived(collection.clone()));
+
+    events::CollectionDeregistered { collection }.publish(e);
+}
+
+fn set_registered(e: &Env, collection: &Address) {
+    e.storage()
+        .persistent()
+        .set(&CollectionKey::Registered(collection.clone()), &true);
+}
+
+// drop `collectible` from the list of `owner`
+fn remove_owner_collectible(
+    e: &Env,
+    stats: &mut FactoryStats,
+    owner: &Address,
+    collectible: &(Address, u32),
+) {
+    let key = CollectionKey::OwnerCollectibles(owner.clone());
+    let mut owner_collectibles: Vec<(Address, u32)> =
+        e.storage().persistent().get(&key).unwrap_or(Vec::new(e));
+    let Some(idx_collectible) = owner_collectibles.first_index_of(collectible.clone()) else {
+        panic_with_error!(e, errors::CollectionError::CollectibleNotFound);
+    };
+    owner_collectibles.remove(idx_collectible);
+    e.storage().persistent().set(&key, &owner_collectibles);
+    if owner_collectibles.is_empty() {
+        stats.total_owners = stats.total_owners.saturating_sub(1);
+    }
+}
+
+fn when_active(e: &Env, collection: &Address) {
+    if Collection::is_paused(e) {
+        panic_with_error!(e, errors::CollectionError::FactoryPaused);
+    }
+    if !is_registered(e, collection) {
+        panic_with_error!(e, errors::CollectionError::NotRegistered);
+    }
+    if Collection::is_archived(e, collection.clone()) {
+        panic_with_error!(e, errors::CollectionError::CollectionArchived);
+    }
+}
+
+fn when_below_max_collections(e: &Env) {
+    let max = Collection::max_collections(e);
+    if max != 0 && Collection::collection_count(e) >= max {
+        panic_with_error!(e, errors::CollectionError::TooManyCollections);
+    }
+}
+
+fn set_collections(e: &Env, collections: &Vec<Address>) {
+    e.storage()
+        .instance()
+        .set(&CollectionKey::Collections, collections);
+
+    let mut stats = Collection::stats(e);
+    stats.collection_count = collections.len();
+    set_stats(e, &stats);
+}
+
+fn set_stats(e: &Env, stats: &FactoryStats) {
+    e.storage().instance().set(&DataKey::Stats, stats);
+}
+
+// append `token_id` to the index of `collection`
+fn add_collection_token(e: &Env, collection: &Address, token_id: u32) {
+    let count = Collection::collection_token_count(e, collection.clone());
+    e.storage().persistent().set(
+        &CollectionKey::CollectionToken(collection.clone(), count),
+        &token_id,
+    );
+    e.storage().persistent().set(
+        &CollectionKey::CollectionTokenIndex(collection.clone(), token_id),
+        &count,
+    );
+    e.storage().persistent().set(
+        &CollectionKey::CollectionTokenCount(collection.clone()),
+        &(count + 1),
+    );
+}
+
+// swap-remove `token_id` from the index of `collection`
+fn remove_collection_token(e: &Env, collection: &Address, token_id: u32) {
+    let index_key = CollectionKey::CollectionTokenIndex(collection.clone(), token_id);
+    // collectibles assigned before the index existed are not in it
+    let Some(index) = e.storage().persistent().get::<_, u32>(&index_key) else {
+        return;
+    };
+    let count = Collection::collection_token_count(e, collection.clone());
+    if index >= count {
+        panic_with_error!(&e, &errors::CollectionError::IndexOutOfBounds);
+    }
+    let last_index = count - 1;
+
+    if index != last_index {
+        let last_token_id: u32 = e
+            .storage()
+            .persistent()
+            .get(&CollectionKey::CollectionToken(
+                collection.clone(),
+                last_index,
+            ))
+            .unwrap_or_else(|| panic_with_error!(&e, &errors::CollectionError::IndexOutOfBounds));
+        e.storage().persistent().set(
+            &CollectionKey::CollectionToken(collection.clone(), index),
+            &last_token_id,
+        );
+        e.storage().persistent().set(
+            &CollectionKey::CollectionTokenIndex(collection.clone(), last_token_id),
+            &index,
+        );
+    }
+
+    e.storage().persistent().remove(&index_key);
+    e.storage()
+        .persistent()
+        .remove(&CollectionKey::CollectionToken(
+            collection.clone(),
+            last_index,
+        ));
+    e.storage().persistent().set(
+        &CollectionKey::CollectionTokenCount(collection.clone()),
+        &last_index,
+    );
+}
+
+fn assign(e: &Env, collection: &Address, to: &Address, token_id: u32) {
+    if !is_registered(e, collection) {
+        panic_with_error!(&e, &errors::CollectionError::NotRegistered);
+    }
+
+    let collectible = (collection.clone(), token_id);
+
+    let owner_address: Option<Address> = e
+        .storage()
+        .persistent()
+        .get(&CollectionKey::Collectibles(collection.clone(), token_id));
+
+    // already held by `to`, keep its place in the list
+    if owner_address.as_ref() == Some(to) {
+        return;
+    }
+
+    let mut stats = Collection::stats(e);
+
+    // transferring the collectible by removing from previous owner if any
+    if let Some(owner_address) = owner_address {
+        remove_owner_collectible(e, &mut stats, &owner_address, &collectible);
+    } else {
+        add_collection_token(e, collection, token_id);
+        stats.total_collectibles += 1;
+    }
+
+    let mut owner_collectibles: Vec<(Address, u32)> = e
+        .storage()
+        .persistent()
+        .get(&CollectionKey::OwnerCollectibles(to.clone()))
+        .unwrap_or(Vec::new(e));
+    if owner_collectibles.is_empty() {
+        stats.total_owners += 1;
+    }
+    owner_collectibles.push_back(collectible);
+    set_stats(e, &stats);
+    e.storage().persistent().set(
+        &CollectionKey::OwnerCollectibles(to.clone()),
+        &owner_collectibles,
+    );
+
+    // set new owner
+    e.storage().persistent().set(
+        &CollectionKey::Collectibles(collection.clone(), token_id),
+        to,
+    );
+}
+
+fn unassign(e: &Env, collection: Address, token_id: u32, owner: Address) {
+    let mut stats = Collection::stats(e);
+    remove_owner_collectible(e, &mut stats, &owner, &(collection.clone(), token_id));
+    stats.total_collectibles = stats.total_collectibles.saturating_sub(1);
+    set_stats(e, &stats);
+    remove_collection_token(e, &collection, token_id);
+    e.storage()
+        .persistent()
+        .remove(&CollectionKey::Collectibles(collection.clone(), token_id));
+
+    events::CollectibleUnassigned {
+        collection,
+        token_id,
+        owner,
+    }
+    .publish(e);
+}
+
+// align the registry with `owner_of` of the collection, which panics for
+// unclaimed and missing tokens
+fn sync(e: &Env, collection: &Address, token_id: u32) {
+    let owner = e.try_invoke_contract::<Address, soroban_sdk::Error>(
+        collection,
+        &Symbol::new(e, "owner_of"),
+        vec![e, token_id.into()],
+    );
+    match owner {
+        Ok(Ok(owner)) => assign(e, collection, &owner, token_id),
+        _ => {
+            let registered_owner =
+                Collection::owner_of_collectible(e, collection.clone(), token_id);
+            if let Some(registered_owner) = registered_owner {
+                unassign(e, collection.clone(), token_id, registered_owner);
+            }
+        }
+    }
 }
@@ -6,4 +6,32 @@ use soroban_sdk::contracterror;
 pub enum CollectionError {
     /// Indicates a non-existent collection address.
     NonExistentCollection = 300,
+    /// Indicates a collectible which is not in the registry.
+    CollectibleNotFound = 301,
+    /// Indicates a collection was already deployed with the salt.
+    DuplicateCollection = 302,
+    /// Indicates an address which is not an NFT contract.
+    InvalidCollection = 303,
+    /// Indicates a collection which is not in the registry.
+    NotRegistered = 304,
+    /// Indicates no admin transfer was started.
+    NoPendingAdmin = 305,
+    /// Indicates the factory may not act on a collection, or has no admin.
+    NotAuthorized = 306,
+    /// Indicates a collectible already held by another owner.
+    AlreadyAssigned = 307,
+    /// Indicates a start index past the end of the collections list.
+    IndexOutOfBounds = 308,
+    /// Indicates a wasm hash which is not on the allow list.
+    WasmNotAllowed = 309,
+    /// Indicates a negative creation fee.
+    InvalidFee = 310,
+    /// Indicates the factory is paused.
+    FactoryPaused = 311,
+    /// Indicates an archived collection.
+    CollectionArchived = 312,
+    /// Indicates the maximum number of collections was reached.
+    TooManyCollections = 313,
+    /// Indicates a page limit above the maximum page size.
+    InvalidLimit = 314,
 }
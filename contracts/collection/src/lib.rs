@@ -1,12 +1,15 @@
 #![no_std]
 #![allow(dead_code)]
 
-use soroban_sdk::{Address, BytesN, Env, String, Vec, contract, contractmeta};
+use soroban_sdk::{Address, Bytes, BytesN, Env, String, Symbol, Vec, contract, contractmeta};
 
 contractmeta!(key = "Description", val = "ChimpDAO Collection");
 
+#[allow(clippy::too_many_arguments)]
 mod contract;
 
+pub use contract::{CollectionInfo, CreationFee, FactoryStats, MAX_COLLECTION_TOKENS_PAGE};
+
 mod errors;
 mod events;
 #[cfg(test)]
@@ -20,18 +23,231 @@ pub trait CollectionTrait {
 
     fn upgrade(e: &Env, wasm_hash: BytesN<32>);
 
+    fn get_admin(e: &Env) -> Address;
+
+    fn get_pending_admin(e: &Env) -> Option<Address>;
+
+    /// Start handing the factory over to `new_admin`, who must call
+    /// `accept_admin`. Collections keep the admin they were deployed with.
+    fn transfer_admin(e: &Env, new_admin: Address);
+
+    /// Complete a transfer started with `transfer_admin`, panics with
+    /// `NoPendingAdmin` if there is none.
+    fn accept_admin(e: &Env);
+
+    /// Stop `create_collection`, `register_collection`, `mint_in_collection`
+    /// and `admin_assign_collectible`, panicking with `FactoryPaused`.
+    /// Registered collections keep reporting claims, transfers and clawbacks.
+    fn pause(e: &Env);
+
+    /// Resume `create_collection`, `register_collection`, `mint_in_collection`
+    /// and `admin_assign_collectible`.
+    fn unpause(e: &Env);
+
+    fn is_paused(e: &Env) -> bool;
+
+    /// Returns whether the contract exposes `interface`: `registry` for the
+    /// collectibles index and `upgradeable`.
+    fn supports(e: &Env, interface: Symbol) -> bool;
+
+    /// Deploy an NFT contract registered with this factory. Without `salt`,
+    /// one is derived from `symbol` and the number of such deployments,
+    /// panics with `DuplicateCollection` if `salt` was already used.
+    /// `creator` becomes the collection admin, and unless it is the factory
+    /// admin pays the creation fee to the treasury, panicking with
+    /// `NotAuthorized` if there is none.
+    #[allow(clippy::too_many_arguments)]
     fn create_collection(
         e: &Env,
+        creator: Address,
         wasm_hash: BytesN<32>,
         name: String,
         symbol: String,
         uri: String,
         max_tokens: u32,
+        soulbound: bool,
+        provenance_limit: u32,
+        first_token_id: u32,
+        salt: Option<BytesN<32>>,
     ) -> Address;
 
+    /// Mint on a registered `collection` as the factory admin, which must be
+    /// its admin or a minter, and record the unclaimed token under the admin.
+    /// Arguments are those of the collection's `mint`, panics with
+    /// `NotRegistered` for unknown collections, `FactoryPaused` while the
+    /// factory is paused and `CollectionArchived` for archived collections.
+    #[allow(clippy::too_many_arguments)]
+    fn mint_in_collection(
+        e: &Env,
+        collection: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32;
+
+    /// Let anyone create collections for `amount` of `asset`, paid to
+    /// `treasury`. `0` makes creation admin-only again, a negative amount
+    /// panics with `InvalidFee`.
+    fn set_creation_fee(e: &Env, asset: Address, amount: i128, treasury: Address);
+
+    /// Returns the creation fee, if one was ever set.
+    fn creation_fee(e: &Env) -> Option<CreationFee>;
+
+    /// Upgrade a registered collection to `wasm_hash`, through its `upgrade`
+    /// which the factory admin must be allowed to call, panics with
+    /// `NotAuthorized` otherwise.
+    fn upgrade_collection(e: &Env, collection: Address, wasm_hash: BytesN<32>);
+
+    /// Upgrade up to `limit` collections to `wasm_hash`, starting at index
+    /// `start` of `collections`, to stay within the budget on large registries.
+    /// Collections refusing the upgrade are skipped with a
+    /// `collection_upgrade_skipped` event. Panics with `IndexOutOfBounds` if
+    /// `start` is past the end.
+    fn upgrade_all_collections(e: &Env, wasm_hash: BytesN<32>, start: u32, limit: u32);
+
+    /// Let `create_collection` deploy `wasm_hash`. Once a hash is allowed,
+    /// only allowed hashes can be deployed, panicking with `WasmNotAllowed`.
+    fn allow_wasm(e: &Env, wasm_hash: BytesN<32>);
+
+    /// Remove `wasm_hash` from the allowed hashes, revoking the last one lifts
+    /// the restriction.
+    fn revoke_wasm(e: &Env, wasm_hash: BytesN<32>);
+
+    /// Hashes `create_collection` may deploy, empty if any code is allowed.
+    fn allowed_wasms(e: &Env) -> Vec<BytesN<32>>;
+
+    /// Address `create_collection` deploys to with `salt`.
+    fn predict_collection_address(e: &Env, salt: BytesN<32>) -> Address;
+
+    /// Add an NFT contract deployed outside the factory to the registry.
+    /// Its `base_uri` and `max_tokens` are cached when it answers them, as
+    /// empty and 0 otherwise. Panics with `InvalidCollection` if it does not
+    /// answer `name` and `symbol`, with `DuplicateCollection` if it is already registered and
+    /// with `FactoryPaused` while the factory is paused.
+    fn register_collection(e: &Env, collection: Address);
+
+    /// Remove a collection from the registry, its collectibles stay listed.
+    fn deregister_collection(e: &Env, collection: Address);
+
+    /// Stop `mint_in_collection` and `admin_assign_collectible` on a
+    /// registered collection, panicking with `CollectionArchived`. The collection keeps reporting claims,
+    /// transfers and clawbacks and its collectibles stay readable.
+    fn archive_collection(e: &Env, collection: Address);
+
+    fn is_archived(e: &Env, collection: Address) -> bool;
+
+    /// Record `to` as the owner of a collectible, called by `collection`.
+    /// Panics with `NotRegistered` unless `collection` is in the registry.
     fn assign_collectible(e: &Env, collection: Address, to: Address, token_id: u32);
 
+    /// Record `to` as the owner of a collectible as the factory admin, e.g. to
+    /// fix the registry. Panics with `NotRegistered` for unknown collections,
+    /// `FactoryPaused` while the factory is paused and `CollectionArchived`
+    /// for archived collections.
+    fn admin_assign_collectible(e: &Env, collection: Address, to: Address, token_id: u32);
+
+    /// Remove a collectible from the registry, e.g. once its token is gone.
+    /// Requires the auth of `collection`, panics with `CollectibleNotFound`
+    /// if it was never assigned.
+    fn unassign_collectible(e: &Env, collection: Address, token_id: u32);
+
+    /// Align the registry with `owner_of` of a registered `collection`, e.g.
+    /// after transfers it did not report. Unclaimed or missing tokens are
+    /// unassigned. Anyone can call it.
+    fn sync_collectible(e: &Env, collection: Address, token_id: u32);
+
+    /// `sync_collectible` for each of `token_ids`, to backfill the registry.
+    fn sync_collectibles(e: &Env, collection: Address, token_ids: Vec<u32>);
+
     fn collectibles(e: &Env, from: Address) -> Vec<(Address, u32)>;
 
+    /// Up to `limit` collectibles of `from`, starting at index `start`.
+    fn collectibles_page(e: &Env, from: Address, start: u32, limit: u32) -> Vec<(Address, u32)>;
+
+    /// Token ids of `from` in `collection`.
+    fn collectibles_in(e: &Env, from: Address, collection: Address) -> Vec<u32>;
+
+    /// Number of collectibles of `from`.
+    fn collectible_count(e: &Env, from: Address) -> u32;
+
+    /// Number of collectibles of `owner` across all collections.
+    fn balance_all(e: &Env, owner: Address) -> u32;
+
+    /// Number of collectibles of `owner` per collection, in order of the
+    /// first collectible of each collection.
+    fn balances_by_collection(e: &Env, owner: Address) -> Vec<(Address, u32)>;
+
+    /// Registered owner of a collectible, `None` if it is not assigned.
+    fn owner_of_collectible(e: &Env, collection: Address, token_id: u32) -> Option<Address>;
+
+    /// Whether `owner` holds the collectible.
+    fn has_collectible(e: &Env, owner: Address, collection: Address, token_id: u32) -> bool;
+
+    /// Up to `limit` collectibles of `collection` with their owner, starting
+    /// at index `start`. Unassigning a collectible moves the last one into its
+    /// place. Panics with `InvalidLimit` if `limit` is above
+    /// [`MAX_COLLECTION_TOKENS_PAGE`].
+    fn collection_tokens(
+        e: &Env,
+        collection: Address,
+        start: u32,
+        limit: u32,
+    ) -> Vec<(u32, Address)>;
+
+    /// Number of assigned collectibles of `collection`.
+    fn collection_token_count(e: &Env, collection: Address) -> u32;
+
+    /// Cap the number of registered collections, `create_collection` and
+    /// `register_collection` panic with `TooManyCollections` once it is
+    /// reached. `0` means unlimited.
+    fn set_max_collections(e: &Env, max: u32);
+
+    /// Maximum number of registered collections, `0` if unlimited.
+    fn max_collections(e: &Env) -> u32;
+
+    /// Number of collections, collectibles and owners in the registry.
+    /// Collectibles registered before the counters existed are not included.
+    fn stats(e: &Env) -> FactoryStats;
+
+    /// Number of registered collections.
+    fn collection_count(e: &Env) -> u32;
+
     fn collections(e: &Env) -> Vec<Address>;
+
+    /// Up to `limit` registered collections, starting at index `start`.
+    fn collections_page(e: &Env, start: u32, limit: u32) -> Vec<Address>;
+
+    /// Cached details of a registered collection, panics with `NotRegistered`
+    /// otherwise.
+    fn collection_info(e: &Env, collection: Address) -> CollectionInfo;
+
+    /// Up to `limit` registered collections with their details, starting at
+    /// index `start` of `collections`.
+    fn collections_detailed(e: &Env, start: u32, limit: u32) -> Vec<(Address, CollectionInfo)>;
+
+    /// Extend the TTL of the collectibles list of `owner` and of the owner
+    /// entries of those collectibles, capped at the network's maximum. Anyone
+    /// can call this.
+    fn extend_owner_ttl(e: &Env, owner: Address, extend_to: u32);
+
+    /// Extend the TTL of the registration and cached details of `collection`,
+    /// capped at the network's maximum. Anyone can call this.
+    fn extend_collection_ttl(e: &Env, collection: Address, extend_to: u32);
+
+    /// Move the collectibles of `owners` from instance to persistent storage,
+    /// where releases older than the persistent registry kept them, merging
+    /// them with the collectibles assigned since. Owners without instance
+    /// entries are skipped. Admin only, panics with `AlreadyAssigned` if a
+    /// collectible was assigned to another owner since.
+    fn migrate(e: &Env, owners: Vec<Address>);
+
+    /// Overwrite the collections list, for instances where an older release
+    /// stored a single address instead. Admin only. Collections of the
+    /// previous list which are left out are deregistered, missing details are
+    /// fetched from the collections. Panics with `DuplicateCollection` if an
+    /// address is listed twice and with `TooManyCollections` above the cap.
+    fn repair_collections(e: &Env, collections: Vec<Address>);
 }
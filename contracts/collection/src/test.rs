@@ -1,7 +1,17 @@
-use soroban_sdk::{Address, Env, String, Vec, testutils::Address as _, vec};
+use k256::ecdsa::SigningKey;
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env, Event, IntoVal, String, Symbol, Vec,
+    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke, storage::Persistent as _},
+    token, vec,
+};
 
-use crate::{Collection, CollectionClient, errors};
+use crate::contract::CollectionKey;
+use crate::{
+    Collection, CollectionClient, CollectionInfo, CreationFee, FactoryStats,
+    MAX_COLLECTION_TOKENS_PAGE, errors, events,
+};
 
+#[allow(clippy::too_many_arguments)]
 mod nfc_nft_contract {
     soroban_sdk::contractimport!(file = "../nfc_nft.wasm");
 }
@@ -21,13 +31,49 @@ fn test_create_collection() {
 
     let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
 
-    let _collection_address = client.create_collection(
+    let collection_address = client.create_collection(
+        &admin,
         &wasm,
         &String::from_str(&e, "TestNFT"),
         &String::from_str(&e, "TNFT"),
         &String::from_str(&e, "ipfs://abcd"),
         &10u32,
+        &false,
+        &0u32,
+        &1u32,
+        &None,
     );
+
+    let nfc_nft_client = nfc_nft_contract::Client::new(&e, &collection_address);
+    assert_eq!(nfc_nft_client.first_token_id(), 1);
+    assert_eq!(nfc_nft_client.next_token_id(), 1);
+}
+
+#[test]
+fn test_create_soulbound_collection() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &true,
+        &0u32,
+        &0u32,
+        &None,
+    );
+
+    let nfc_nft_client = nfc_nft_contract::Client::new(&e, &collection_address);
+    assert!(nfc_nft_client.is_soulbound());
 }
 
 #[test]
@@ -41,19 +87,29 @@ fn test_assign_collectible() {
     let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
 
     let collection_a_address = client.create_collection(
+        &admin,
         &wasm,
         &String::from_str(&e, "TestNFTA"),
         &String::from_str(&e, "TNFTA"),
         &String::from_str(&e, "ipfs://abcd"),
         &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
     );
 
     let collection_b_address = client.create_collection(
+        &admin,
         &wasm,
         &String::from_str(&e, "TestNFTB"),
         &String::from_str(&e, "TNFTB"),
         &String::from_str(&e, "ipfs://abcd"),
         &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
     );
 
     let mando = Address::generate(&e);
@@ -118,11 +174,16 @@ fn test_failed_assign_collectible() {
     let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
 
     let _collection_a_address = client.create_collection(
+        &admin,
         &wasm,
         &String::from_str(&e, "TestNFTA"),
         &String::from_str(&e, "TNFTA"),
         &String::from_str(&e, "ipfs://abcd"),
         &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
     );
 
     let mando = Address::generate(&e);
@@ -135,5 +196,1950 @@ fn test_failed_assign_collectible() {
         .try_assign_collectible(&collection_b_address, &mando, &1u32)
         .unwrap_err()
         .unwrap();
-    assert_eq!(err, errors::CollectionError::NonExistentCollection.into());
+    assert_eq!(err, errors::CollectionError::NotRegistered.into());
+}
+
+#[test]
+fn test_nft_contract_syncs_collectibles() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+    let nfc_nft_client = nfc_nft_contract::Client::new(&e, &collection_address);
+    assert_eq!(nfc_nft_client.registry(), client.address);
+
+    // Software chip signing the digest the NFT contract expects
+    let chip = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+    let public_key = BytesN::from_array(
+        &e,
+        chip.verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .try_into()
+            .unwrap(),
+    );
+    let token_id = nfc_nft_client.admin_mint(&public_key);
+
+    let message = Bytes::from_slice(&e, b"claim");
+    // 1 is the claim action of the NFT contract
+    let message_hash = nfc_nft_client.message_hash(&claimant, &message, &1u32, &1u32, &0u32);
+    let (signature, recovery_id) = chip
+        .sign_prehash_recoverable(&message_hash.to_array())
+        .unwrap();
+    let signature_bytes: [u8; 64] = signature.to_bytes().into();
+    nfc_nft_client.claim(
+        &claimant,
+        &message,
+        &BytesN::from_array(&e, &signature_bytes),
+        &(recovery_id.to_byte() as u32),
+        &public_key.into(),
+        &1u32,
+        &0u32,
+    );
+    assert_eq!(
+        client.collectibles(&claimant),
+        vec![&e, (collection_address.clone(), token_id)]
+    );
+
+    nfc_nft_client.set_chipless_transfer_enabled(&true);
+    nfc_nft_client.transfer_owner(&claimant, &recipient, &token_id);
+    assert_eq!(client.collectibles(&claimant), Vec::new(&e));
+    assert_eq!(
+        client.collectibles(&recipient),
+        vec![&e, (collection_address.clone(), token_id)]
+    );
+}
+
+#[test]
+fn test_supports() {
+    let e = Env::default();
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    assert!(client.supports(&Symbol::new(&e, "registry")));
+    assert!(client.supports(&Symbol::new(&e, "upgradeable")));
+    assert!(!client.supports(&Symbol::new(&e, "enumerable")));
+}
+
+#[test]
+fn test_collections() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    assert_eq!(client.collections(), Vec::new(&e));
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let create = |name: &str| {
+        client.create_collection(
+            &admin,
+            &wasm,
+            &String::from_str(&e, name),
+            &String::from_str(&e, name),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &None,
+        )
+    };
+    let collection_a_address = create("TNFTA");
+    let collection_b_address = create("TNFTB");
+    assert_eq!(
+        client.collections(),
+        vec![
+            &e,
+            collection_a_address.clone(),
+            collection_b_address.clone()
+        ]
+    );
+
+    // An instance holding a single address instead of the list
+    e.as_contract(&client.address, || {
+        e.storage()
+            .instance()
+            .set(&CollectionKey::Collections, &collection_b_address);
+    });
+    // The details of a collection registered before the cache are fetched
+    e.as_contract(&client.address, || {
+        e.storage()
+            .persistent()
+            .remove(&CollectionKey::Info(collection_a_address.clone()));
+    });
+    client.repair_collections(&vec![
+        &e,
+        collection_a_address.clone(),
+        collection_b_address.clone(),
+    ]);
+    assert_eq!(
+        client.collections(),
+        vec![
+            &e,
+            collection_a_address.clone(),
+            collection_b_address.clone()
+        ]
+    );
+    e.as_contract(&client.address, || {
+        let info: CollectionInfo = e
+            .storage()
+            .persistent()
+            .get(&CollectionKey::Info(collection_a_address.clone()))
+            .unwrap();
+        assert_eq!(info.symbol, String::from_str(&e, "TNFTA"));
+        assert_eq!(info.created_ledger, 0);
+    });
+
+    // A left out collection is deregistered
+    client.repair_collections(&vec![&e, collection_a_address.clone()]);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::CollectionDeregistered {
+            collection: collection_b_address.clone(),
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert_eq!(client.collections(), vec![&e, collection_a_address.clone()]);
+    assert_eq!(client.collection_count(), 1);
+    let err = client
+        .try_collection_info(&collection_b_address)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotRegistered.into());
+    let err = client
+        .try_assign_collectible(&collection_b_address, &admin, &1u32)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotRegistered.into());
+
+    // The list is validated
+    let err = client
+        .try_repair_collections(&vec![
+            &e,
+            collection_a_address.clone(),
+            collection_a_address.clone(),
+        ])
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::DuplicateCollection.into());
+    client.set_max_collections(&1);
+    let err = client
+        .try_repair_collections(&vec![&e, collection_a_address, collection_b_address])
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::TooManyCollections.into());
+}
+
+#[test]
+fn test_reassign_collectible() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+
+    let mando = Address::generate(&e);
+    let grogu = Address::generate(&e);
+
+    // First assignment of the collectible ever
+    client.assign_collectible(&collection_address, &mando, &1u32);
+    client.assign_collectible(&collection_address, &mando, &2u32);
+
+    // Assigning to the current owner neither fails nor reorders
+    client.assign_collectible(&collection_address, &mando, &1u32);
+    assert_eq!(
+        client.collectibles(&mando),
+        vec![
+            &e,
+            (collection_address.clone(), 1u32),
+            (collection_address.clone(), 2u32)
+        ]
+    );
+
+    client.assign_collectible(&collection_address, &grogu, &1u32);
+    assert_eq!(
+        client.collectibles(&mando),
+        vec![&e, (collection_address.clone(), 2u32)]
+    );
+    assert_eq!(
+        client.collectibles(&grogu),
+        vec![&e, (collection_address.clone(), 1u32)]
+    );
+}
+
+#[test]
+fn test_reassign_collectible_prunes_previous_owner() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+
+    let owner_a = Address::generate(&e);
+    let owner_b = Address::generate(&e);
+    client.assign_collectible(&collection_address, &owner_a, &1u32);
+    client.assign_collectible(&collection_address, &owner_b, &1u32);
+
+    assert_eq!(client.collectibles(&owner_a), Vec::new(&e));
+    assert_eq!(
+        client.collectibles(&owner_b),
+        vec![&e, (collection_address.clone(), 1u32)]
+    );
+}
+
+#[test]
+fn test_unassign_collectible() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+
+    let mando = Address::generate(&e);
+    client.assign_collectible(&collection_address, &mando, &1u32);
+    client.unassign_collectible(&collection_address, &1u32);
+    assert_eq!(
+        e.events()
+            .all()
+            .filter_by_contract(&client.address)
+            .events()
+            .last()
+            .unwrap(),
+        &events::CollectibleUnassigned {
+            collection: collection_address.clone(),
+            token_id: 1,
+            owner: mando.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert_eq!(client.collectibles(&mando), Vec::new(&e));
+
+    let err = client
+        .try_unassign_collectible(&collection_address, &1u32)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::CollectibleNotFound.into());
+
+    // The collectible can be assigned again
+    client.assign_collectible(&collection_address, &mando, &1u32);
+    assert_eq!(
+        client.collectibles(&mando),
+        vec![&e, (collection_address, 1u32)]
+    );
+}
+
+#[test]
+fn test_collectibles_page() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let create = |name: &str| {
+        client.create_collection(
+            &admin,
+            &wasm,
+            &String::from_str(&e, name),
+            &String::from_str(&e, name),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &None,
+        )
+    };
+    let collection_a_address = create("TNFTA");
+    let collection_b_address = create("TNFTB");
+
+    let mando = Address::generate(&e);
+    client.assign_collectible(&collection_a_address, &mando, &1u32);
+    client.assign_collectible(&collection_b_address, &mando, &1u32);
+    client.assign_collectible(&collection_a_address, &mando, &2u32);
+    client.assign_collectible(&collection_b_address, &mando, &2u32);
+    client.assign_collectible(&collection_a_address, &mando, &3u32);
+
+    assert_eq!(client.collectible_count(&mando), 5);
+    assert_eq!(client.collectible_count(&admin), 0);
+
+    assert_eq!(
+        client.collectibles_page(&mando, &0, &2),
+        vec![
+            &e,
+            (collection_a_address.clone(), 1u32),
+            (collection_b_address.clone(), 1u32)
+        ]
+    );
+    assert_eq!(
+        client.collectibles_page(&mando, &4, &2),
+        vec![&e, (collection_a_address.clone(), 3u32)]
+    );
+    assert_eq!(client.collectibles_page(&mando, &5, &2), Vec::new(&e));
+    assert_eq!(client.collectibles_page(&mando, &0, &0), Vec::new(&e));
+
+    assert_eq!(
+        client.collectibles_in(&mando, &collection_a_address),
+        vec![&e, 1u32, 2u32, 3u32]
+    );
+    assert_eq!(
+        client.collectibles_in(&mando, &collection_b_address),
+        vec![&e, 1u32, 2u32]
+    );
+    assert_eq!(
+        client.collectibles_in(&admin, &collection_a_address),
+        Vec::new(&e)
+    );
+}
+
+#[test]
+fn test_owner_of_collectible() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+
+    let mando = Address::generate(&e);
+    let grogu = Address::generate(&e);
+    client.assign_collectible(&collection_address, &mando, &1u32);
+
+    assert_eq!(
+        client.owner_of_collectible(&collection_address, &1u32),
+        Some(mando.clone())
+    );
+    assert!(client.has_collectible(&mando, &collection_address, &1u32));
+
+    assert_eq!(
+        client.owner_of_collectible(&collection_address, &2u32),
+        None
+    );
+    assert_eq!(client.owner_of_collectible(&grogu, &1u32), None);
+    assert!(!client.has_collectible(&grogu, &collection_address, &1u32));
+    assert!(!client.has_collectible(&mando, &collection_address, &2u32));
+}
+
+#[test]
+fn test_create_collection_salt() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let create = |salt: Option<BytesN<32>>| {
+        client.try_create_collection(
+            &admin,
+            &wasm,
+            &String::from_str(&e, "TestNFT"),
+            &String::from_str(&e, "TNFT"),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &salt,
+        )
+    };
+
+    // The same symbol twice
+    let first = create(None).unwrap().unwrap();
+    let second = create(None).unwrap().unwrap();
+    assert_ne!(first, second);
+
+    let salt = BytesN::from_array(&e, &[7u8; 32]);
+    let predicted = client.predict_collection_address(&salt);
+    let third = create(Some(salt.clone())).unwrap().unwrap();
+    assert_eq!(third, predicted);
+    assert_eq!(client.collections(), vec![&e, first, second, third]);
+
+    let err = create(Some(salt)).unwrap_err().unwrap();
+    assert_eq!(err, errors::CollectionError::DuplicateCollection.into());
+}
+
+#[test]
+fn test_register_v1_collection() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    // The first release has neither `base_uri` nor `max_tokens`
+    let collection_address = e.register(
+        include_bytes!("../../nfc-nft_v1.0.0.wasm").as_slice(),
+        (
+            &admin,
+            &client.address,
+            &String::from_str(&e, "TestNFT"),
+            &String::from_str(&e, "TNFT"),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+        ),
+    );
+
+    client.register_collection(&collection_address);
+    assert_eq!(client.collections(), vec![&e, collection_address.clone()]);
+    assert_eq!(
+        client.collection_info(&collection_address),
+        CollectionInfo {
+            name: String::from_str(&e, "TestNFT"),
+            symbol: String::from_str(&e, "TNFT"),
+            uri: String::from_str(&e, ""),
+            max_tokens: 0,
+            created_ledger: e.ledger().sequence(),
+        }
+    );
+}
+
+#[test]
+fn test_register_collection() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    // Deployed before the factory existed
+    let collection_address = e.register(
+        nfc_nft_contract::WASM,
+        (
+            &admin,
+            &client.address,
+            &String::from_str(&e, "TestNFT"),
+            &String::from_str(&e, "TNFT"),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+        ),
+    );
+    let mando = Address::generate(&e);
+
+    client.register_collection(&collection_address);
+    let last_event = || {
+        e.events()
+            .all()
+            .filter_by_contract(&client.address)
+            .events()
+            .last()
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(
+        last_event(),
+        events::CollectionRegistered {
+            collection: collection_address.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert_eq!(client.collections(), vec![&e, collection_address.clone()]);
+    client.assign_collectible(&collection_address, &mando, &1u32);
+
+    let err = client
+        .try_register_collection(&collection_address)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::DuplicateCollection.into());
+    let err = client
+        .try_register_collection(&Address::generate(&e))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::InvalidCollection.into());
+
+    client.deregister_collection(&collection_address);
+    assert_eq!(
+        last_event(),
+        events::CollectionDeregistered {
+            collection: collection_address.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert_eq!(client.collections(), Vec::new(&e));
+    let err = client
+        .try_assign_collectible(&collection_address, &mando, &2u32)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotRegistered.into());
+    let err = client
+        .try_deregister_collection(&collection_address)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NonExistentCollection.into());
+}
+
+#[test]
+fn test_assign_collectible_requires_registration() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+    let mando = Address::generate(&e);
+
+    // A contract authorizing itself is not enough
+    let rogue = e.register(
+        nfc_nft_contract::WASM,
+        (
+            &admin,
+            &client.address,
+            &String::from_str(&e, "Rogue"),
+            &String::from_str(&e, "RGE"),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+        ),
+    );
+    let err = client
+        .try_assign_collectible(&rogue, &mando, &1u32)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotRegistered.into());
+    assert_eq!(client.collectibles(&mando), Vec::new(&e));
+
+    client.assign_collectible(&collection_address, &mando, &1u32);
+    assert_eq!(
+        client.collectibles(&mando),
+        vec![&e, (collection_address.clone(), 1u32)]
+    );
+
+    // Listed before the registration flag existed
+    let legacy = Address::generate(&e);
+    e.as_contract(&client.address, || {
+        e.storage().instance().set(
+            &CollectionKey::Collections,
+            &vec![&e, collection_address.clone(), legacy.clone()],
+        );
+    });
+    client.assign_collectible(&legacy, &mando, &1u32);
+    assert!(client.has_collectible(&mando, &legacy, &1u32));
+}
+
+#[test]
+fn test_collection_info() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+    let info = CollectionInfo {
+        name: String::from_str(&e, "TestNFT"),
+        symbol: String::from_str(&e, "TNFT"),
+        uri: String::from_str(&e, "ipfs://abcd"),
+        max_tokens: 10,
+        created_ledger: 100,
+    };
+    assert_eq!(client.collection_info(&collection_address), info);
+
+    // Fetched from the contract when registered
+    e.ledger().set_sequence_number(200);
+    let external_address = e.register(
+        nfc_nft_contract::WASM,
+        (
+            &admin,
+            &client.address,
+            &String::from_str(&e, "External"),
+            &String::from_str(&e, "EXT"),
+            &String::from_str(&e, "ipfs://ef"),
+            &5u32,
+            &false,
+            &0u32,
+            &0u32,
+        ),
+    );
+    client.register_collection(&external_address);
+    let external_info = CollectionInfo {
+        name: String::from_str(&e, "External"),
+        symbol: String::from_str(&e, "EXT"),
+        uri: String::from_str(&e, "ipfs://ef"),
+        max_tokens: 5,
+        created_ledger: 200,
+    };
+
+    assert_eq!(
+        client.collections_detailed(&0, &10),
+        vec![
+            &e,
+            (collection_address.clone(), info),
+            (external_address.clone(), external_info.clone())
+        ]
+    );
+    assert_eq!(
+        client.collections_detailed(&1, &1),
+        vec![&e, (external_address, external_info)]
+    );
+    assert_eq!(client.collections_detailed(&2, &1), Vec::new(&e));
+
+    let err = client
+        .try_collection_info(&Address::generate(&e))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotRegistered.into());
+}
+
+#[test]
+fn test_migrate_and_extend_ttl() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+    let mando = Address::generate(&e);
+
+    // Registry entries as early releases stored them
+    let owner_key = CollectionKey::OwnerCollectibles(mando.clone());
+    let collectible_key = CollectionKey::Collectibles(collection_address.clone(), 1);
+    e.as_contract(&client.address, || {
+        e.storage()
+            .instance()
+            .set(&owner_key, &vec![&e, (collection_address.clone(), 1u32)]);
+        e.storage().instance().set(&collectible_key, &mando);
+    });
+    assert_eq!(client.collectibles(&mando), Vec::new(&e));
+
+    // Assigned after the upgrade, and a duplicate of the legacy entry
+    client.assign_collectible(&collection_address, &mando, &2u32);
+    e.as_contract(&client.address, || {
+        e.storage().instance().set(
+            &owner_key,
+            &vec![
+                &e,
+                (collection_address.clone(), 1u32),
+                (collection_address.clone(), 2u32),
+            ],
+        );
+    });
+    assert_eq!(client.stats().total_owners, 1);
+
+    client.migrate(&vec![&e, mando.clone()]);
+    assert_eq!(
+        client.collectibles(&mando),
+        vec![
+            &e,
+            (collection_address.clone(), 2u32),
+            (collection_address.clone(), 1u32)
+        ]
+    );
+    assert_eq!(client.stats().total_owners, 1);
+    assert_eq!(client.stats().total_collectibles, 2);
+    assert_eq!(client.collection_token_count(&collection_address), 2);
+
+    // Owners without legacy entries are left alone
+    client.migrate(&vec![&e, mando.clone()]);
+    assert_eq!(client.collectibles(&mando).len(), 2);
+    assert_eq!(client.stats().total_owners, 1);
+    assert_eq!(
+        client.owner_of_collectible(&collection_address, &1u32),
+        Some(mando.clone())
+    );
+    e.as_contract(&client.address, || {
+        assert!(!e.storage().instance().has(&owner_key));
+        assert!(!e.storage().instance().has(&collectible_key));
+    });
+
+    // Another owner's stale copy of the same collectible
+    let grogu = Address::generate(&e);
+    e.as_contract(&client.address, || {
+        e.storage().instance().set(
+            &CollectionKey::OwnerCollectibles(grogu.clone()),
+            &vec![&e, (collection_address.clone(), 1u32)],
+        );
+    });
+    let err = client.try_migrate(&vec![&e, grogu]).unwrap_err().unwrap();
+    assert_eq!(err, errors::CollectionError::AlreadyAssigned.into());
+
+    client.extend_owner_ttl(&mando, &100_000);
+    client.extend_collection_ttl(&collection_address, &100_000);
+    e.as_contract(&client.address, || {
+        assert_eq!(e.storage().persistent().get_ttl(&owner_key), 100_000);
+        assert_eq!(e.storage().persistent().get_ttl(&collectible_key), 100_000);
+        assert_eq!(
+            e.storage()
+                .persistent()
+                .get_ttl(&CollectionKey::Info(collection_address.clone())),
+            100_000
+        );
+    });
+}
+
+#[test]
+fn test_upgrade_collections() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let create = |name: &str| {
+        client.create_collection(
+            &admin,
+            &wasm,
+            &String::from_str(&e, name),
+            &String::from_str(&e, name),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &None,
+        )
+    };
+    let collection_a_address = create("TNFTA");
+    let collection_b_address = create("TNFTB");
+    let collection_c_address = create("TNFTC");
+    let registry = Symbol::new(&e, "registry");
+    let nfc_nft_a = nfc_nft_contract::Client::new(&e, &collection_a_address);
+    let nfc_nft_b = nfc_nft_contract::Client::new(&e, &collection_b_address);
+    let nfc_nft_c = nfc_nft_contract::Client::new(&e, &collection_c_address);
+    assert!(!nfc_nft_a.supports(&registry));
+
+    // Multisig collections only upgrade through a proposal
+    nfc_nft_c.set_admins(&vec![&e, admin.clone(), Address::generate(&e)], &2);
+
+    // Any other code tells the upgrade apart, the collection wasm answers `registry`
+    let new_wasm = e
+        .deployer()
+        .upload_contract_wasm(include_bytes!("../../collection.wasm").as_slice());
+    client.upgrade_collection(&collection_a_address, &new_wasm);
+    assert_eq!(
+        e.events()
+            .all()
+            .filter_by_contract(&client.address)
+            .events()
+            .last()
+            .unwrap(),
+        &events::CollectionUpgraded {
+            collection: collection_a_address.clone(),
+            wasm_hash: new_wasm.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert!(nfc_nft_a.supports(&registry));
+    assert!(!nfc_nft_b.supports(&registry));
+
+    // The refusing collection is skipped, the others are still upgraded
+    client.upgrade_all_collections(&new_wasm, &1, &10);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [
+            events::CollectionUpgraded {
+                collection: collection_b_address.clone(),
+                wasm_hash: new_wasm.clone(),
+            }
+            .to_xdr(&e, &client.address),
+            events::CollectionUpgradeSkipped {
+                collection: collection_c_address.clone(),
+                wasm_hash: new_wasm.clone(),
+            }
+            .to_xdr(&e, &client.address),
+        ]
+    );
+    assert!(nfc_nft_b.supports(&registry));
+    assert!(!nfc_nft_c.supports(&registry));
+    let err = client
+        .try_upgrade_collection(&collection_c_address, &new_wasm)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotAuthorized.into());
+    let err = client
+        .try_upgrade_all_collections(&new_wasm, &3, &10)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::IndexOutOfBounds.into());
+
+    let err = client
+        .try_upgrade_collection(&Address::generate(&e), &new_wasm)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotRegistered.into());
+}
+
+#[test]
+fn test_admin_transfer() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let new_admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    client.transfer_admin(&new_admin);
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+    assert_eq!(client.get_admin(), admin, "Admin changes only on accept");
+
+    client.accept_admin();
+    assert_eq!(
+        e.events()
+            .all()
+            .filter_by_contract(&client.address)
+            .events()
+            .last()
+            .unwrap(),
+        &events::AdminTransferred {
+            previous_admin: admin.clone(),
+            new_admin: new_admin.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert_eq!(client.get_admin(), new_admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    // the new admin is now in charge of admin operations
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    client.create_collection(
+        &new_admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+    assert_eq!(e.auths()[0].0, new_admin);
+
+    let err = client.try_accept_admin().unwrap_err().unwrap();
+    assert_eq!(err, errors::CollectionError::NoPendingAdmin.into());
+}
+
+#[test]
+fn test_accept_admin_unauthorized() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let new_admin = Address::generate(&e);
+    let attacker = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    client.transfer_admin(&new_admin);
+
+    let result = client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "accept_admin",
+                args: ().into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .try_accept_admin();
+    assert!(result.is_err(), "Only the pending admin can accept");
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+fn test_upgrade_collection_not_authorized() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+
+    // Upgrades of the collection now need a multisig proposal
+    let nfc_nft_client = nfc_nft_contract::Client::new(&e, &collection_address);
+    nfc_nft_client.set_admins(&vec![&e, admin.clone(), Address::generate(&e)], &2);
+
+    let err = client
+        .try_upgrade_collection(&collection_address, &wasm)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotAuthorized.into());
+}
+
+#[test]
+fn test_allowed_wasms() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let other_wasm = e
+        .deployer()
+        .upload_contract_wasm(include_bytes!("../../collection.wasm").as_slice());
+    let create = |symbol: &str| {
+        client.try_create_collection(
+            &admin,
+            &wasm,
+            &String::from_str(&e, "TestNFT"),
+            &String::from_str(&e, symbol),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &None,
+        )
+    };
+    let last_event = || {
+        e.events()
+            .all()
+            .filter_by_contract(&client.address)
+            .events()
+            .last()
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(client.allowed_wasms(), Vec::new(&e));
+
+    client.allow_wasm(&other_wasm);
+    assert_eq!(
+        last_event(),
+        events::WasmAllowed {
+            wasm_hash: other_wasm.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    let err = create("TNFTA").unwrap_err().unwrap();
+    assert_eq!(err, errors::CollectionError::WasmNotAllowed.into());
+
+    client.allow_wasm(&wasm);
+    assert_eq!(
+        client.allowed_wasms(),
+        vec![&e, other_wasm.clone(), wasm.clone()]
+    );
+    create("TNFTA").unwrap().unwrap();
+
+    client.revoke_wasm(&wasm);
+    assert_eq!(
+        last_event(),
+        events::WasmRevoked {
+            wasm_hash: wasm.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    let err = create("TNFTB").unwrap_err().unwrap();
+    assert_eq!(err, errors::CollectionError::WasmNotAllowed.into());
+
+    // An empty list lifts the restriction
+    client.revoke_wasm(&other_wasm);
+    create("TNFTB").unwrap().unwrap();
+}
+
+#[test]
+fn test_creation_fee() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let creator = Address::generate(&e);
+    let treasury = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let sac = e.register_stellar_asset_contract_v2(Address::generate(&e));
+    let asset = sac.address();
+    token::StellarAssetClient::new(&e, &asset).mint(&creator, &1_000);
+    token::StellarAssetClient::new(&e, &asset).mint(&admin, &1_000);
+    let token_client = token::TokenClient::new(&e, &asset);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let create = |creator: &Address, symbol: &str| {
+        client.try_create_collection(
+            creator,
+            &wasm,
+            &String::from_str(&e, "TestNFT"),
+            &String::from_str(&e, symbol),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &None,
+        )
+    };
+    let last_event = || {
+        e.events()
+            .all()
+            .filter_by_contract(&client.address)
+            .events()
+            .last()
+            .unwrap()
+            .clone()
+    };
+
+    // Without a fee only the admin can create collections
+    assert_eq!(client.creation_fee(), None);
+    let err = create(&creator, "TNFTA").unwrap_err().unwrap();
+    assert_eq!(err, errors::CollectionError::NotAuthorized.into());
+
+    assert_eq!(
+        client.try_set_creation_fee(&asset, &-1, &treasury),
+        Err(Ok(errors::CollectionError::InvalidFee.into()))
+    );
+    client.set_creation_fee(&asset, &100, &treasury);
+    assert_eq!(
+        client.creation_fee(),
+        Some(CreationFee {
+            asset: asset.clone(),
+            amount: 100,
+            treasury: treasury.clone(),
+        })
+    );
+
+    let collection_address = create(&creator, "TNFTA").unwrap().unwrap();
+    assert_eq!(e.auths()[0].0, creator);
+    assert_eq!(
+        last_event(),
+        events::CreateCollection {
+            symbol: String::from_str(&e, "TNFTA"),
+            contract_address: collection_address.clone(),
+            creator: creator.clone(),
+            fee: 100,
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert_eq!(token_client.balance(&creator), 900);
+    assert_eq!(token_client.balance(&treasury), 100);
+    assert_eq!(client.collections(), vec![&e, collection_address.clone()]);
+    assert_eq!(
+        nfc_nft_contract::Client::new(&e, &collection_address).get_admin(),
+        creator
+    );
+
+    // The admin is exempt
+    let collection_address = create(&admin, "TNFTB").unwrap().unwrap();
+    assert_eq!(
+        last_event(),
+        events::CreateCollection {
+            symbol: String::from_str(&e, "TNFTB"),
+            contract_address: collection_address,
+            creator: admin.clone(),
+            fee: 0,
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert_eq!(token_client.balance(&admin), 1_000);
+    assert_eq!(token_client.balance(&treasury), 100);
+
+    // Creators who cannot pay are rejected
+    let broke_creator = Address::generate(&e);
+    assert!(create(&broke_creator, "TNFTC").is_err());
+    assert_eq!(client.collections().len(), 2);
+
+    // A zero fee makes creation admin-only again
+    client.set_creation_fee(&asset, &0, &treasury);
+    let err = create(&creator, "TNFTC").unwrap_err().unwrap();
+    assert_eq!(err, errors::CollectionError::NotAuthorized.into());
+    assert_eq!(token_client.balance(&creator), 900);
+}
+
+#[test]
+fn test_balances() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let create = |symbol: &str| {
+        client.create_collection(
+            &admin,
+            &wasm,
+            &String::from_str(&e, "TestNFT"),
+            &String::from_str(&e, symbol),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &None,
+        )
+    };
+    let collection_a_address = create("TNFTA");
+    let collection_b_address = create("TNFTB");
+
+    let mando = Address::generate(&e);
+    let grogu = Address::generate(&e);
+    assert_eq!(client.balance_all(&mando), 0);
+    assert_eq!(client.balances_by_collection(&mando), Vec::new(&e));
+
+    client.assign_collectible(&collection_b_address, &mando, &1u32);
+    client.assign_collectible(&collection_a_address, &mando, &1u32);
+    client.assign_collectible(&collection_b_address, &mando, &2u32);
+    client.assign_collectible(&collection_b_address, &mando, &3u32);
+    client.assign_collectible(&collection_a_address, &grogu, &2u32);
+
+    assert_eq!(client.balance_all(&mando), 4);
+    assert_eq!(
+        client.balances_by_collection(&mando),
+        vec![
+            &e,
+            (collection_b_address.clone(), 3u32),
+            (collection_a_address.clone(), 1u32),
+        ]
+    );
+    assert_eq!(client.balance_all(&grogu), 1);
+    assert_eq!(
+        client.balances_by_collection(&grogu),
+        vec![&e, (collection_a_address.clone(), 1u32)]
+    );
+
+    // re-assignments move the balance
+    client.assign_collectible(&collection_a_address, &grogu, &1u32);
+    assert_eq!(client.balance_all(&mando), 3);
+    assert_eq!(
+        client.balances_by_collection(&mando),
+        vec![&e, (collection_b_address.clone(), 3u32)]
+    );
+    assert_eq!(
+        client.balances_by_collection(&grogu),
+        vec![&e, (collection_a_address, 2u32)]
+    );
+}
+
+#[test]
+fn test_pause_and_archive() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let create = |symbol: &str| {
+        client.try_create_collection(
+            &admin,
+            &wasm,
+            &String::from_str(&e, "TestNFT"),
+            &String::from_str(&e, symbol),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &None,
+        )
+    };
+    let last_event = || {
+        e.events()
+            .all()
+            .filter_by_contract(&client.address)
+            .events()
+            .last()
+            .unwrap()
+            .clone()
+    };
+    let collection_a_address = create("TNFTA").unwrap().unwrap();
+    let collection_b_address = create("TNFTB").unwrap().unwrap();
+
+    let mando = Address::generate(&e);
+    client.assign_collectible(&collection_a_address, &mando, &1u32);
+
+    assert!(!client.is_paused());
+    client.pause();
+    assert_eq!(
+        last_event(),
+        events::Paused {
+            admin: admin.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert!(client.is_paused());
+
+    let err = create("TNFTC").unwrap_err().unwrap();
+    assert_eq!(err, errors::CollectionError::FactoryPaused.into());
+    client.deregister_collection(&collection_b_address);
+    let err = client
+        .try_register_collection(&collection_b_address)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::FactoryPaused.into());
+    let err = client
+        .try_admin_assign_collectible(&collection_a_address, &mando, &2u32)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::FactoryPaused.into());
+
+    // registered collections keep reporting, reads keep working
+    client.assign_collectible(&collection_a_address, &mando, &2u32);
+    assert_eq!(
+        client.collectibles(&mando),
+        vec![
+            &e,
+            (collection_a_address.clone(), 1u32),
+            (collection_a_address.clone(), 2u32)
+        ]
+    );
+    assert_eq!(client.collections().len(), 1);
+
+    client.unpause();
+    assert_eq!(
+        last_event(),
+        events::Unpaused {
+            admin: admin.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert!(!client.is_paused());
+    create("TNFTC").unwrap().unwrap();
+    client.register_collection(&collection_b_address);
+
+    // archived collections keep their collectibles but take no new mints
+    assert!(!client.is_archived(&collection_a_address));
+    client.archive_collection(&collection_a_address);
+    assert_eq!(
+        last_event(),
+        events::CollectionArchived {
+            collection: collection_a_address.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert!(client.is_archived(&collection_a_address));
+
+    let err = client
+        .try_mint_in_collection(
+            &collection_a_address,
+            &Bytes::from_slice(&e, b"chip"),
+            &BytesN::from_array(&e, &[0u8; 64]),
+            &0u32,
+            &Bytes::from_slice(&e, &[4u8; 65]),
+            &1u32,
+            &0u32,
+        )
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::CollectionArchived.into());
+    let err = client
+        .try_admin_assign_collectible(&collection_a_address, &mando, &4u32)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::CollectionArchived.into());
+    client.assign_collectible(&collection_a_address, &mando, &3u32);
+    client.admin_assign_collectible(&collection_b_address, &mando, &2u32);
+
+    assert_eq!(
+        client.collectibles(&mando),
+        vec![
+            &e,
+            (collection_a_address.clone(), 1u32),
+            (collection_a_address.clone(), 2u32),
+            (collection_a_address.clone(), 3u32),
+            (collection_b_address.clone(), 2u32),
+        ]
+    );
+    assert_eq!(
+        client.owner_of_collectible(&collection_a_address, &1u32),
+        Some(mando.clone())
+    );
+    assert_eq!(
+        client.collection_info(&collection_a_address).symbol,
+        String::from_str(&e, "TNFTA")
+    );
+
+    let err = client
+        .try_archive_collection(&Address::generate(&e))
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotRegistered.into());
+
+    client.deregister_collection(&collection_a_address);
+    assert!(!client.is_archived(&collection_a_address));
+}
+
+#[test]
+fn test_collection_tokens() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let create = |symbol: &str| {
+        client.create_collection(
+            &admin,
+            &wasm,
+            &String::from_str(&e, "TestNFT"),
+            &String::from_str(&e, symbol),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &None,
+        )
+    };
+    let collection_a_address = create("TNFTA");
+    let collection_b_address = create("TNFTB");
+
+    let mando = Address::generate(&e);
+    let grogu = Address::generate(&e);
+    assert_eq!(client.collection_token_count(&collection_a_address), 0);
+    assert_eq!(
+        client.collection_tokens(&collection_a_address, &0, &10),
+        Vec::new(&e)
+    );
+
+    client.assign_collectible(&collection_a_address, &mando, &3u32);
+    client.assign_collectible(&collection_a_address, &mando, &1u32);
+    client.assign_collectible(&collection_b_address, &mando, &1u32);
+    client.assign_collectible(&collection_a_address, &grogu, &2u32);
+    assert_eq!(client.collection_token_count(&collection_a_address), 3);
+    assert_eq!(
+        client.collection_tokens(&collection_a_address, &0, &10),
+        vec![
+            &e,
+            (3u32, mando.clone()),
+            (1u32, mando.clone()),
+            (2u32, grogu.clone()),
+        ]
+    );
+    assert_eq!(
+        client.collection_tokens(&collection_b_address, &0, &10),
+        vec![&e, (1u32, mando.clone())]
+    );
+
+    // re-assignments keep a single entry, with the new owner
+    client.assign_collectible(&collection_a_address, &grogu, &1u32);
+    client.assign_collectible(&collection_a_address, &grogu, &1u32);
+    assert_eq!(client.collection_token_count(&collection_a_address), 3);
+    assert_eq!(
+        client.collection_tokens(&collection_a_address, &1, &1),
+        vec![&e, (1u32, grogu.clone())]
+    );
+    assert_eq!(
+        client.collection_tokens(&collection_a_address, &2, &10),
+        vec![&e, (2u32, grogu.clone())]
+    );
+    assert_eq!(
+        client.collection_tokens(&collection_a_address, &3, &10),
+        Vec::new(&e)
+    );
+
+    // the last collectible takes the place of an unassigned one
+    client.unassign_collectible(&collection_a_address, &3u32);
+    assert_eq!(client.collection_token_count(&collection_a_address), 2);
+    assert_eq!(
+        client.collection_tokens(&collection_a_address, &0, &10),
+        vec![&e, (2u32, grogu.clone()), (1u32, grogu.clone())]
+    );
+    client.unassign_collectible(&collection_a_address, &1u32);
+    client.assign_collectible(&collection_a_address, &mando, &3u32);
+    assert_eq!(
+        client.collection_tokens(&collection_a_address, &0, &10),
+        vec![&e, (2u32, grogu), (3u32, mando)]
+    );
+    assert_eq!(client.collection_token_count(&collection_b_address), 1);
+
+    assert_eq!(
+        client
+            .collection_tokens(&collection_a_address, &0, &MAX_COLLECTION_TOKENS_PAGE)
+            .len(),
+        2
+    );
+    assert_eq!(
+        client
+            .try_collection_tokens(&collection_a_address, &0, &(MAX_COLLECTION_TOKENS_PAGE + 1))
+            .unwrap_err()
+            .unwrap(),
+        errors::CollectionError::InvalidLimit.into()
+    );
+}
+
+#[test]
+fn test_sync_collectible() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+    let nfc_nft_client = nfc_nft_contract::Client::new(&e, &collection_address);
+
+    // A second registry the collection does not report to
+    let mirror = create_client(&e, &admin);
+    mirror.register_collection(&collection_address);
+
+    let chip = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+    let public_key = BytesN::from_array(
+        &e,
+        chip.verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .try_into()
+            .unwrap(),
+    );
+    let token_id = nfc_nft_client.admin_mint(&public_key);
+
+    // unclaimed tokens have no owner
+    mirror.sync_collectible(&collection_address, &token_id);
+    assert_eq!(mirror.collection_token_count(&collection_address), 0);
+
+    let message = Bytes::from_slice(&e, b"claim");
+    // 1 is the claim action of the NFT contract
+    let message_hash = nfc_nft_client.message_hash(&claimant, &message, &1u32, &1u32, &0u32);
+    let (signature, recovery_id) = chip
+        .sign_prehash_recoverable(&message_hash.to_array())
+        .unwrap();
+    let signature_bytes: [u8; 64] = signature.to_bytes().into();
+    nfc_nft_client.claim(
+        &claimant,
+        &message,
+        &BytesN::from_array(&e, &signature_bytes),
+        &(recovery_id.to_byte() as u32),
+        &public_key.into(),
+        &1u32,
+        &0u32,
+    );
+    assert_eq!(mirror.collectibles(&claimant), Vec::new(&e));
+
+    // anyone can sync
+    e.set_auths(&[]);
+    mirror.sync_collectible(&collection_address, &token_id);
+    assert_eq!(
+        mirror.collectibles(&claimant),
+        vec![&e, (collection_address.clone(), token_id)]
+    );
+
+    e.mock_all_auths();
+    nfc_nft_client.set_chipless_transfer_enabled(&true);
+    nfc_nft_client.transfer_owner(&claimant, &recipient, &token_id);
+    assert_eq!(
+        mirror.owner_of_collectible(&collection_address, &token_id),
+        Some(claimant.clone())
+    );
+
+    // a stale entry for a token which was never minted
+    mirror.assign_collectible(&collection_address, &claimant, &(token_id + 1));
+
+    mirror.sync_collectibles(&collection_address, &vec![&e, token_id, token_id + 1]);
+    assert_eq!(mirror.collectibles(&claimant), Vec::new(&e));
+    assert_eq!(
+        mirror.collectibles(&recipient),
+        vec![&e, (collection_address.clone(), token_id)]
+    );
+    assert_eq!(
+        mirror.collection_tokens(&collection_address, &0, &10),
+        vec![&e, (token_id, recipient.clone())]
+    );
+    // the registry the collection reports to agrees
+    assert_eq!(
+        client.collectibles(&recipient),
+        mirror.collectibles(&recipient)
+    );
+
+    let err = mirror
+        .try_sync_collectible(&Address::generate(&e), &token_id)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotRegistered.into());
+}
+
+#[test]
+fn test_mint_in_collection() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+    let nfc_nft_client = nfc_nft_contract::Client::new(&e, &collection_address);
+    assert_eq!(nfc_nft_client.next_token_id(), 0);
+
+    let chip = SigningKey::from_bytes(&[1u8; 32].into()).unwrap();
+    let public_key: BytesN<65> = BytesN::from_array(
+        &e,
+        chip.verifying_key()
+            .to_encoded_point(false)
+            .as_bytes()
+            .try_into()
+            .unwrap(),
+    );
+    let sign = |signer: &Address, nonce: u32, action: u32| {
+        let message = Bytes::from_slice(&e, b"chip");
+        let message_hash = nfc_nft_client.message_hash(signer, &message, &nonce, &action, &0u32);
+        let (signature, recovery_id) = chip
+            .sign_prehash_recoverable(&message_hash.to_array())
+            .unwrap();
+        let signature_bytes: [u8; 64] = signature.to_bytes().into();
+        (
+            message,
+            BytesN::from_array(&e, &signature_bytes),
+            recovery_id.to_byte() as u32,
+        )
+    };
+
+    // 0 is the mint action of the NFT contract
+    let (message, signature, recovery_id) = sign(&admin, 1, 0);
+    let token_id = client.mint_in_collection(
+        &collection_address,
+        &message,
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &1u32,
+        &0u32,
+    );
+    assert_eq!(e.auths()[0].0, admin);
+    assert_eq!(nfc_nft_client.next_token_id(), token_id + 1);
+    assert_eq!(
+        nfc_nft_client.token_id(&public_key.clone().into()),
+        token_id
+    );
+    assert_eq!(
+        client.owner_of_collectible(&collection_address, &token_id),
+        Some(admin.clone())
+    );
+    assert_eq!(
+        client.collectibles(&admin),
+        vec![&e, (collection_address.clone(), token_id)]
+    );
+
+    // claiming moves the collectible to the claimant
+    let (message, signature, recovery_id) = sign(&claimant, 2, 1);
+    nfc_nft_client.claim(
+        &claimant,
+        &message,
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &2u32,
+        &0u32,
+    );
+    assert_eq!(client.collectibles(&admin), Vec::new(&e));
+    assert_eq!(
+        client.collectibles(&claimant),
+        vec![&e, (collection_address.clone(), token_id)]
+    );
+
+    let err = client
+        .try_mint_in_collection(
+            &Address::generate(&e),
+            &message,
+            &signature,
+            &recovery_id,
+            &public_key.into(),
+            &3u32,
+            &0u32,
+        )
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::NotRegistered.into());
+}
+
+#[test]
+fn test_stats() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let stats = |collection_count, total_collectibles, total_owners| FactoryStats {
+        collection_count,
+        total_collectibles,
+        total_owners,
+    };
+    assert_eq!(client.stats(), stats(0, 0, 0));
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let create = |symbol: &str| {
+        client.create_collection(
+            &admin,
+            &wasm,
+            &String::from_str(&e, "TestNFT"),
+            &String::from_str(&e, symbol),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &None,
+        )
+    };
+    let collection_a_address = create("TNFTA");
+    let collection_b_address = create("TNFTB");
+    assert_eq!(client.collection_count(), 2);
+
+    let mando = Address::generate(&e);
+    let grogu = Address::generate(&e);
+    client.assign_collectible(&collection_a_address, &mando, &1u32);
+    client.assign_collectible(&collection_b_address, &mando, &1u32);
+    client.assign_collectible(&collection_a_address, &grogu, &2u32);
+    assert_eq!(client.stats(), stats(2, 3, 2));
+
+    // re-assignments move collectibles without adding any
+    client.assign_collectible(&collection_a_address, &mando, &2u32);
+    assert_eq!(client.stats(), stats(2, 3, 1));
+    client.assign_collectible(&collection_a_address, &mando, &2u32);
+    assert_eq!(client.stats(), stats(2, 3, 1));
+    client.assign_collectible(&collection_a_address, &grogu, &2u32);
+    assert_eq!(client.stats(), stats(2, 3, 2));
+
+    client.unassign_collectible(&collection_a_address, &2u32);
+    assert_eq!(client.stats(), stats(2, 2, 1));
+
+    client.deregister_collection(&collection_b_address);
+    assert_eq!(client.stats(), stats(1, 2, 1));
+    assert_eq!(client.collection_count(), 1);
+}
+
+#[test]
+fn test_max_collections() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let create = |symbol: &str| {
+        client.try_create_collection(
+            &admin,
+            &wasm,
+            &String::from_str(&e, "TestNFT"),
+            &String::from_str(&e, symbol),
+            &String::from_str(&e, "ipfs://abcd"),
+            &10u32,
+            &false,
+            &0u32,
+            &0u32,
+            &None,
+        )
+    };
+    assert_eq!(client.max_collections(), 0);
+
+    client.set_max_collections(&1);
+    assert_eq!(client.max_collections(), 1);
+    let collection_a_address = create("TNFTA").unwrap().unwrap();
+
+    let err = create("TNFTB").unwrap_err().unwrap();
+    assert_eq!(err, errors::CollectionError::TooManyCollections.into());
+
+    // collections deployed elsewhere count too
+    let other_factory = create_client(&e, &admin);
+    let other_collection = other_factory.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFTC"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+    let err = client
+        .try_register_collection(&other_collection)
+        .unwrap_err()
+        .unwrap();
+    assert_eq!(err, errors::CollectionError::TooManyCollections.into());
+
+    // zero lifts the cap
+    client.set_max_collections(&0);
+    let collection_b_address = create("TNFTB").unwrap().unwrap();
+    client.register_collection(&other_collection);
+
+    assert_eq!(
+        client.collections_page(&0, &2),
+        vec![
+            &e,
+            collection_a_address.clone(),
+            collection_b_address.clone()
+        ]
+    );
+    assert_eq!(
+        client.collections_page(&1, &10),
+        vec![&e, collection_b_address, other_collection]
+    );
+    assert_eq!(client.collections_page(&3, &10), Vec::new(&e));
+}
+
+#[test]
+fn test_nft_flows_while_paused_and_archived() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let wasm = e.deployer().upload_contract_wasm(nfc_nft_contract::WASM);
+    let collection_address = client.create_collection(
+        &admin,
+        &wasm,
+        &String::from_str(&e, "TestNFT"),
+        &String::from_str(&e, "TNFT"),
+        &String::from_str(&e, "ipfs://abcd"),
+        &10u32,
+        &false,
+        &0u32,
+        &0u32,
+        &None,
+    );
+    let nfc_nft_client = nfc_nft_contract::Client::new(&e, &collection_address);
+    nfc_nft_client.set_chipless_transfer_enabled(&true);
+
+    // claim, transfer and clawback a fresh token, checking the registry follows
+    let run_flows = |seed: u8| {
+        let claimant = Address::generate(&e);
+        let recipient = Address::generate(&e);
+        let chip = SigningKey::from_bytes(&[seed; 32].into()).unwrap();
+        let public_key: BytesN<65> = BytesN::from_array(
+            &e,
+            chip.verifying_key()
+                .to_encoded_point(false)
+                .as_bytes()
+                .try_into()
+                .unwrap(),
+        );
+        let token_id = nfc_nft_client.admin_mint(&public_key);
+
+        let message = Bytes::from_slice(&e, b"claim");
+        // 1 is the claim action of the NFT contract
+        let message_hash = nfc_nft_client.message_hash(&claimant, &message, &1u32, &1u32, &0u32);
+        let (signature, recovery_id) = chip
+            .sign_prehash_recoverable(&message_hash.to_array())
+            .unwrap();
+        let signature_bytes: [u8; 64] = signature.to_bytes().into();
+        nfc_nft_client.claim(
+            &claimant,
+            &message,
+            &BytesN::from_array(&e, &signature_bytes),
+            &(recovery_id.to_byte() as u32),
+            &public_key.into(),
+            &1u32,
+            &0u32,
+        );
+        assert_eq!(
+            client.owner_of_collectible(&collection_address, &token_id),
+            Some(claimant.clone())
+        );
+
+        nfc_nft_client.transfer_owner(&claimant, &recipient, &token_id);
+        assert_eq!(
+            client.owner_of_collectible(&collection_address, &token_id),
+            Some(recipient.clone())
+        );
+
+        nfc_nft_client.clawback(&token_id, &1u32);
+        assert_eq!(
+            client.owner_of_collectible(&collection_address, &token_id),
+            Some(admin.clone())
+        );
+    };
+
+    client.pause();
+    run_flows(1);
+    client.unpause();
+
+    client.archive_collection(&collection_address);
+    run_flows(2);
+
+    client.pause();
+    run_flows(3);
 }
@@ -5,20 +5,55 @@ use crate::{
 };
 use soroban_sdk::xdr::ToXdr;
 use soroban_sdk::{
-    Address, Bytes, BytesN, Env, String, contractimpl, contracttype, panic_with_error,
+    Address, Bytes, BytesN, Env, Executable, IntoVal, Map, String, Symbol, TryFromVal, Val, Vec,
+    contractimpl, contracttype, crypto::Hash, panic_with_error, symbol_short, token::TokenClient,
 };
 
 #[contracttype]
 pub enum DataKey {
     Admin,
+    PendingAdmin,
     CollectionContract,
     NextTokenId,
     MaxTokens,
+    FirstTokenId,
     Name,
     Symbol,
     Uri,
+    Paused,
+    MintEnabled,
+    TransferEnabled,
+    TotalSupply,
+    ClaimedCount,
+    UnclaimedCount,
+    UriFrozen,
+    AllMetadataFrozen,
+    ContractUri,
+    ClaimWindow,
+    Soulbound,
+    DefaultRoyalty,
+    RegistrationRequired,
+    StorageVersion,
+    ProvenanceLimit,
+    ChiplessTransferEnabled,
+    ClaimTransferEvents,
+    MintFee,
+    MintRateLimit,
+    MintsInLedger,
+    ReservedUpTo,
+    UriSuffix,
+    Admins,
+    AdminThreshold,
+    NextProposalId,
 }
 
+/// Version of the contract code, bumped on each release
+pub const CONTRACT_VERSION: u32 = 2;
+
+/// Version of the storage layout written by this code, instances without a
+/// stored version predate versioning and are at 0
+pub const STORAGE_VERSION: u32 = 1;
+
 #[contracttype]
 pub enum NFTStorageKey {
     ChipNonceByPublicKey(BytesN<65>),
@@ -26,8 +61,257 @@ pub enum NFTStorageKey {
     PublicKey(u32),
     TokenIdByPublicKey(BytesN<65>),
     Balance(Address),
+    Approval(u32),
+    OwnerToken(Address, u32),
+    OwnerTokenIndex(u32),
+    Metadata(u32),
+    Quarantine(u32),
+    ClaimDeadline(u32),
+    Frozen(u32),
+    Royalty(u32),
+    Minter(Address),
+    BlockedChip(BytesN<65>),
+    RegisteredChip(BytesN<65>),
+    UncompressedPublicKey(BytesN<33>),
+    Curve(u32),
+    History(u32),
+    Provenance(u32),
+    ChipUrl(u32),
+    Proposal(u32),
+    Lock(u32),
+    User(u32),
+    ChipLost(u32),
+    ChipBatch(BytesN<65>),
+    BatchToken(String, u32),
+    BatchTokenIndex(u32),
+    BatchTokenCount(String),
+    MetadataFrozen(u32),
+    Referrer(u32),
+    ReferralCount(Address),
+    BackupKey(u32),
+    ClaimedToken(u32),
+    OwnerTokenCount(Address),
+    RetiredChip(BytesN<65>),
+}
+
+#[contracttype]
+pub struct ApprovalData {
+    pub approved: Address,
+    pub live_until_ledger: u32,
+}
+
+#[contracttype]
+pub struct UserData {
+    pub user: Address,
+    pub expires_ledger: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenMetadata {
+    pub edition: u32,
+    pub batch: String,
+    pub minted_ledger: u32,
+    pub extra: Map<String, String>,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct TokenInfo {
+    pub owner: Option<Address>,
+    pub public_key: BytesN<65>,
+    pub minted_ledger: u32,
+    pub minted_timestamp: u64,
+    pub claimed_ledger: Option<u32>,
+    pub claimed_timestamp: Option<u64>,
+    pub status: TokenStatus,
+}
+
+/// Token and owner of a chip, see `resolve_chip`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChipResolution {
+    pub token_id: Option<u32>,
+    pub owner: Option<Address>,
+    pub claimed: bool,
+    pub blocked: bool,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ProvenanceEntry {
+    pub from: Address,
+    pub to: Address,
+    pub ledger: u32,
+}
+
+/// Ledger and timestamp of the mint and claim of a token
+#[contracttype]
+pub struct TokenHistory {
+    pub minted_ledger: u32,
+    pub minted_timestamp: u64,
+    pub claimed_ledger: Option<u32>,
+    pub claimed_timestamp: Option<u64>,
+}
+
+/// Structured payload signed by the chip instead of free-form message bytes,
+/// its XDR encoding takes the place of the message in the signed hash
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct SignedAction {
+    /// `mint`, `claim` or `transfer`, must match the entry point
+    pub action: Symbol,
+    /// Token the signature is for, checked by `claim_v2` and `transfer_v2` when set
+    pub token_id: Option<u32>,
+    /// Recipient of a transfer, checked by `transfer_v2` when set
+    pub to: Option<Address>,
+    /// Free text published in a `memo` event
+    pub memo: Option<String>,
+}
+
+/// Admin operations needing `admin_threshold` approvals, see `propose_action`
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum AdminAction {
+    /// Upgrade to the WASM with this hash
+    Upgrade(BytesN<32>),
+    /// Clawback of a token id, with the reason code
+    Clawback(u32, u32),
+    /// New base URI
+    SetBaseUri(String),
+    /// New admins and threshold
+    SetAdmins(Vec<Address>, u32),
+    /// Release of a quarantined token id, to this account
+    ClawbackRelease(u32, Address),
+    /// Account proposed as the new admin
+    TransferAdmin(Address),
+    /// New collection registry
+    SetRegistry(Address),
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Proposal {
+    pub action: AdminAction,
+    pub approvals: Vec<Address>,
+    /// Last ledger the proposal can be approved at
+    pub deadline: u32,
 }
 
+/// Fee charged to the minter for each chip minted
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintFee {
+    pub asset: Address,
+    pub amount: i128,
+}
+
+#[contracttype]
+pub struct RoyaltyData {
+    pub receiver: Address,
+    pub basis_points: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MintRequest {
+    pub message: Bytes,
+    pub signature: BytesN<64>,
+    pub recovery_id: u32,
+    pub public_key: BytesN<65>,
+    pub nonce: u32,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClaimRequest {
+    pub message: Bytes,
+    pub signature: BytesN<64>,
+    pub recovery_id: u32,
+    pub public_key: BytesN<65>,
+    pub nonce: u32,
+}
+
+/// Action discriminators bound into the chip signed hash, see `check_chip_signature`
+pub const ACTION_MINT: u32 = 0;
+pub const ACTION_CLAIM: u32 = 1;
+pub const ACTION_TRANSFER: u32 = 2;
+/// 3 is taken by the prize contract's redeem signatures
+pub const ACTION_ROTATE: u32 = 4;
+pub const ACTION_BACKUP: u32 = 5;
+
+/// Recovery id letting the contract find the recovery id of a secp256k1 signature
+pub const RECOVERY_ID_AUTO: u32 = u32::MAX;
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum TokenStatus {
+    NotMinted,
+    Minted,
+    Claimed,
+    Quarantined,
+}
+
+#[contracttype]
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub enum CurveType {
+    Secp256k1,
+    Secp256r1,
+    Ed25519,
+}
+
+#[contracttype]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub enum ChipKey {
+    Secp256k1(BytesN<65>),
+    Secp256r1(BytesN<65>),
+    Ed25519(BytesN<32>),
+}
+
+/// Number of ownership changes kept per token when the constructor is given 0
+pub const DEFAULT_PROVENANCE_LIMIT: u32 = 16;
+
+const DAY_IN_LEDGERS: u32 = 17280;
+
+/// TTL the entries touched by mint, claim and transfer are extended to
+const TTL_EXTEND_TO: u32 = 30 * DAY_IN_LEDGERS;
+
+/// Entries are only extended once their TTL dropped below this
+const TTL_THRESHOLD: u32 = TTL_EXTEND_TO - DAY_IN_LEDGERS;
+
+/// Number of ledgers a proposal can be approved for
+pub const PROPOSAL_LIFETIME: u32 = 7 * DAY_IN_LEDGERS;
+
+/// Token id of the `MetadataFrozen` event published by `freeze_all_metadata`
+pub const ALL_TOKENS: u32 = u32::MAX;
+
+/// Interfaces reported by `supports`
+pub const SUPPORTED_INTERFACES: [&str; 6] = [
+    "approvals",
+    "chip_auth",
+    "enumerable",
+    "metadata",
+    "royalties",
+    "upgradeable",
+];
+
+/// Most token ids `owners` looks at in one call, each costs one storage read
+pub const MAX_OWNERS_PAGE: u32 = 64;
+
+/// Most token ids `tokens`, `unclaimed_tokens`, `tokens_detailed` and
+/// `tokens_in_batch` look at in one call, each costs up to two storage reads
+pub const MAX_TOKENS_PAGE: u32 = 32;
+
+/// Longest manufacturing batch name stored by `set_chip_batch`, in bytes
+pub const MAX_BATCH_LEN: u32 = 32;
+
+/// Longest NDEF URL stored by `set_chip_url`, in bytes
+pub const MAX_CHIP_URL_LEN: u32 = 256;
+
+/// First byte of the 65 bytes form ed25519 keys are stored under: 0xED || key || zero padding.
+/// SEC1 keys never start with it.
+const ED25519_KEY_PREFIX: u8 = 0xED;
+
 #[contractimpl]
 impl NFCtoNFTTrait for NFCtoNFT {
     fn __constructor(
@@ -38,6 +322,9 @@ impl NFCtoNFTTrait for NFCtoNFT {
         symbol: String,
         uri: String,
         max_tokens: u32,
+        soulbound: bool,
+        provenance_limit: u32,
+        first_token_id: u32,
     ) {
         e.storage().instance().set(&DataKey::Admin, &admin);
 
@@ -50,324 +337,3542 @@ impl NFCtoNFTTrait for NFCtoNFT {
         e.storage().instance().set(&DataKey::Uri, &uri);
 
         e.storage().instance().set(&DataKey::MaxTokens, &max_tokens);
-        e.storage().instance().set(&DataKey::NextTokenId, &0u32);
+        e.storage()
+            .instance()
+            .set(&DataKey::FirstTokenId, &first_token_id);
+        e.storage()
+            .instance()
+            .set(&DataKey::NextTokenId, &first_token_id);
+        e.storage().instance().set(&DataKey::TotalSupply, &0u32);
+        e.storage().instance().set(&DataKey::Soulbound, &soulbound);
+        let provenance_limit = if provenance_limit == 0 {
+            DEFAULT_PROVENANCE_LIMIT
+        } else {
+            provenance_limit
+        };
+        e.storage()
+            .instance()
+            .set(&DataKey::ProvenanceLimit, &provenance_limit);
+        e.storage()
+            .instance()
+            .set(&DataKey::StorageVersion, &STORAGE_VERSION);
     }
 
     fn upgrade(e: &Env, wasm_hash: BytesN<32>) {
         let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
         admin.require_auth();
+        when_single_admin(e);
 
-        e.deployer().update_current_contract_wasm(wasm_hash.clone());
+        upgrade_wasm(e, admin, wasm_hash);
     }
 
-    fn mint(
-        e: &Env,
-        message: Bytes,
-        signature: BytesN<64>,
-        recovery_id: u32,
-        public_key: BytesN<65>,
-        nonce: u32,
-    ) -> u32 {
-        let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
+    fn set_admins(e: &Env, admins: Vec<Address>, threshold: u32) {
+        let admin = Self::get_admin(e);
         admin.require_auth();
+        when_single_admin(e);
 
-        Self::verify_chip_signature(
-            e,
-            admin.to_xdr(e),
-            message,
-            signature,
-            recovery_id,
-            public_key.clone(),
-            nonce,
-        );
+        set_admins(e, admins, threshold);
+    }
 
-        let public_key_lookup = NFTStorageKey::TokenIdByPublicKey(public_key.clone());
-        if e.storage().persistent().has(&public_key_lookup) {
-            panic_with_error!(&e, &errors::NonFungibleTokenError::TokenAlreadyMinted);
-        }
+    fn admins(e: &Env) -> Vec<Address> {
+        e.storage()
+            .instance()
+            .get(&DataKey::Admins)
+            .unwrap_or_else(|| Vec::from_array(e, [Self::get_admin(e)]))
+    }
 
-        let token_id: u32 = Self::next_token_id(e);
-        let max_tokens: u32 = e.storage().instance().get(&DataKey::MaxTokens).unwrap();
+    fn admin_threshold(e: &Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::AdminThreshold)
+            .unwrap_or(1)
+    }
 
-        if token_id >= max_tokens {
-            panic_with_error!(&e, &errors::NonFungibleTokenError::TokenIDsAreDepleted);
-        }
+    fn propose_action(e: &Env, proposer: Address, action: AdminAction) -> u32 {
+        proposer.require_auth();
+        when_in_admins(e, &proposer);
 
-        e.storage()
+        let proposal_id: u32 = e
+            .storage()
             .instance()
-            .set(&DataKey::NextTokenId, &(token_id + 1));
-        e.storage().persistent().set(&public_key_lookup, &token_id);
+            .get(&DataKey::NextProposalId)
+            .unwrap_or(0);
         e.storage()
-            .persistent()
-            .set(&NFTStorageKey::PublicKey(token_id), &public_key);
-
-        let contract_address = e.current_contract_address();
-        events::Mint {
-            to: contract_address,
-            token_id,
+            .instance()
+            .set(&DataKey::NextProposalId, &(proposal_id + 1));
+
+        let proposal = Proposal {
+            action: action.clone(),
+            approvals: Vec::from_array(e, [proposer.clone()]),
+            deadline: e.ledger().sequence().saturating_add(PROPOSAL_LIFETIME),
+        };
+
+        events::ActionProposed {
+            proposal_id,
+            proposer: proposer.clone(),
+            action,
         }
         .publish(e);
 
-        token_id
-    }
-
-    fn claim(
-        e: &Env,
-        claimant: Address,
-        message: Bytes,
-        signature: BytesN<64>,
-        recovery_id: u32,
-        public_key: BytesN<65>,
-        nonce: u32,
-    ) -> u32 {
-        claimant.require_auth();
+        approve_or_execute(e, proposal_id, proposal, proposer);
 
-        Self::verify_chip_signature(
-            e,
-            claimant.clone().to_xdr(e),
-            message,
-            signature,
-            recovery_id,
-            public_key.clone(),
-            nonce,
-        );
+        proposal_id
+    }
 
-        let token_id = Self::token_id(e, public_key.clone());
+    fn approve_action(e: &Env, approver: Address, proposal_id: u32) {
+        approver.require_auth();
+        when_in_admins(e, &approver);
 
-        if e.storage()
-            .persistent()
-            .has(&NFTStorageKey::Owner(token_id))
-        {
-            panic_with_error!(e, &errors::NonFungibleTokenError::TokenAlreadyClaimed);
+        let mut proposal = Self::proposal(e, proposal_id);
+        if e.ledger().sequence() > proposal.deadline {
+            panic_with_error!(e, errors::NonFungibleTokenError::ProposalExpired);
         }
+        if proposal.approvals.contains(&approver) {
+            panic_with_error!(e, errors::NonFungibleTokenError::AlreadyApproved);
+        }
+        proposal.approvals.push_back(approver.clone());
+
+        approve_or_execute(e, proposal_id, proposal, approver);
+    }
 
+    fn proposal(e: &Env, proposal_id: u32) -> Proposal {
         e.storage()
             .persistent()
-            .set(&NFTStorageKey::Owner(token_id), &claimant);
+            .get(&NFTStorageKey::Proposal(proposal_id))
+            .unwrap_or_else(|| {
+                panic_with_error!(e, errors::NonFungibleTokenError::NonExistentProposal)
+            })
+    }
 
-        let claimant_balance = Self::balance(e, claimant.clone());
-        e.storage().persistent().set(
-            &NFTStorageKey::Balance(claimant.clone()),
-            &(claimant_balance + 1),
-        );
+    fn migrate(e: &Env) {
+        let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
 
-        assign_collectible(e, &claimant, &token_id);
+        // No layout changes yet, future versions migrate from the stored version here
+        e.storage()
+            .instance()
+            .set(&DataKey::StorageVersion, &STORAGE_VERSION);
+    }
 
-        events::Claim { claimant, token_id }.publish(e);
+    fn version(_e: &Env) -> u32 {
+        CONTRACT_VERSION
+    }
 
-        token_id
+    fn supports(e: &Env, interface: Symbol) -> bool {
+        SUPPORTED_INTERFACES
+            .iter()
+            .any(|name| Symbol::new(e, name) == interface)
     }
 
-    #[allow(clippy::too_many_arguments)]
-    fn transfer(
-        e: &Env,
-        from: Address,
-        to: Address,
-        token_id: u32,
-        message: Bytes,
-        signature: BytesN<64>,
-        recovery_id: u32,
-        public_key: BytesN<65>,
-        nonce: u32,
-    ) {
-        from.require_auth();
+    fn storage_version(e: &Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::StorageVersion)
+            .unwrap_or(0u32)
+    }
 
-        Self::verify_chip_signature(
-            e,
-            from.clone().to_xdr(e),
-            message,
-            signature,
-            recovery_id,
-            public_key.clone(),
-            nonce,
-        );
+    fn transfer_admin(e: &Env, new_admin: Address) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+        when_single_admin(e);
 
-        // Verify the chip public_key corresponds to that specific token_id
-        let token_id_public_key: BytesN<65> = Self::public_key(e, token_id);
+        start_admin_transfer(e, new_admin);
+    }
 
-        if token_id_public_key != public_key {
-            panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidSignature);
-        }
+    fn accept_admin(e: &Env) {
+        let new_admin = Self::get_pending_admin(e)
+            .unwrap_or_else(|| panic_with_error!(e, errors::NonFungibleTokenError::NoPendingAdmin));
+        new_admin.require_auth();
 
-        let owner = Self::owner_of(e, token_id);
-        if owner != from || from == to {
-            panic_with_error!(e, &errors::NonFungibleTokenError::IncorrectOwner);
+        let previous_admin = Self::get_admin(e);
+        e.storage().instance().set(&DataKey::Admin, &new_admin);
+        e.storage().instance().remove(&DataKey::PendingAdmin);
+
+        events::AdminTransferred {
+            previous_admin,
+            new_admin,
         }
+        .publish(e);
+    }
 
-        e.storage()
-            .persistent()
-            .set(&NFTStorageKey::Owner(token_id), &to);
+    fn get_admin(e: &Env) -> Address {
+        get_instance_metadata(e, &DataKey::Admin)
+    }
+
+    fn set_registry(e: &Env, registry: Address) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+        when_single_admin(e);
 
-        let from_balance = Self::balance(e, from.clone());
-        e.storage()
-            .persistent()
-            .set(&NFTStorageKey::Balance(from.clone()), &(from_balance - 1));
-        let to_balance = Self::balance(e, to.clone());
         e.storage()
-            .persistent()
-            .set(&NFTStorageKey::Balance(to.clone()), &(to_balance + 1));
+            .instance()
+            .set(&DataKey::CollectionContract, &registry);
+    }
 
-        assign_collectible(e, &to, &token_id);
+    fn registry(e: &Env) -> Address {
+        get_instance_metadata(e, &DataKey::CollectionContract)
+    }
 
-        events::Transfer { from, to, token_id }.publish(e);
+    fn get_pending_admin(e: &Env) -> Option<Address> {
+        e.storage().instance().get(&DataKey::PendingAdmin)
     }
 
-    fn clawback(e: &Env, token_id: u32) {
-        let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
+    fn set_minter(e: &Env, minter: Address) {
+        let admin = Self::get_admin(e);
         admin.require_auth();
 
-        let from = Self::owner_of(e, token_id);
-        let to = admin.clone();
-
         e.storage()
             .persistent()
-            .set(&NFTStorageKey::Owner(token_id), &to);
+            .set(&NFTStorageKey::Minter(minter.clone()), &true);
+
+        events::MinterSet { minter }.publish(e);
+    }
+
+    fn remove_minter(e: &Env, minter: Address) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
 
-        let from_balance = Self::balance(e, from.clone());
-        e.storage()
-            .persistent()
-            .set(&NFTStorageKey::Balance(from.clone()), &(from_balance - 1));
-        let to_balance = Self::balance(e, to.clone());
         e.storage()
             .persistent()
-            .set(&NFTStorageKey::Balance(to.clone()), &(to_balance + 1));
+            .remove(&NFTStorageKey::Minter(minter.clone()));
 
-        assign_collectible(e, &to, &token_id);
+        events::MinterRemoved { minter }.publish(e);
     }
 
-    fn get_nonce(e: &Env, public_key: BytesN<65>) -> u32 {
-        let nonce_key = NFTStorageKey::ChipNonceByPublicKey(public_key);
-        e.storage().persistent().get(&nonce_key).unwrap_or(0u32) // Default to 0 if not set (first use)
+    fn is_minter(e: &Env, addr: Address) -> bool {
+        e.storage().persistent().has(&NFTStorageKey::Minter(addr))
     }
 
-    fn balance(e: &Env, owner: Address) -> u32 {
-        e.storage()
-            .persistent()
-            .get(&NFTStorageKey::Balance(owner))
-            .unwrap_or(0u32)
+    fn pause(e: &Env) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage().instance().set(&DataKey::Paused, &true);
+
+        events::Paused { admin }.publish(e);
     }
 
-    fn owner_of(e: &Env, token_id: u32) -> Address {
-        // Verify the token exists (this will panic if it doesn't)
-        Self::public_key(e, token_id);
+    fn unpause(e: &Env) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
 
-        // Token exists, now check if it has an owner
-        e.storage()
-            .persistent()
-            .get(&NFTStorageKey::Owner(token_id))
-            .unwrap_or_else(|| panic_with_error!(e, errors::NonFungibleTokenError::TokenNotClaimed))
+        e.storage().instance().set(&DataKey::Paused, &false);
+
+        events::Unpaused { admin }.publish(e);
     }
 
-    fn name(e: &Env) -> String {
-        e.storage().instance().get(&DataKey::Name).unwrap()
+    fn is_paused(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::Paused)
+            .unwrap_or(false)
     }
 
-    fn symbol(e: &Env) -> String {
-        e.storage().instance().get(&DataKey::Symbol).unwrap()
+    fn set_mint_enabled(e: &Env, enabled: bool) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage().instance().set(&DataKey::MintEnabled, &enabled);
     }
 
-    fn token_uri(e: &Env, token_id: u32) -> String {
-        // Verify token exists (this will panic if it doesn't)
-        Self::public_key(e, token_id);
+    fn set_transfer_enabled(e: &Env, enabled: bool) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
 
-        let base_uri: String = e.storage().instance().get(&DataKey::Uri).unwrap();
+        e.storage()
+            .instance()
+            .set(&DataKey::TransferEnabled, &enabled);
+    }
 
-        // Construct Uri: {base_uri}/{token_id}
-        let mut uri_bytes = Bytes::new(e);
-        uri_bytes.append(&Bytes::from(base_uri));
-        uri_bytes.append(&Bytes::from_slice(e, b"/"));
-        uri_bytes.append(&u32_to_decimal_bytes(e, token_id));
+    fn set_mint_rate_limit(e: &Env, max_per_ledger: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
 
-        String::from(uri_bytes)
+        e.storage()
+            .instance()
+            .set(&DataKey::MintRateLimit, &max_per_ledger);
     }
 
-    fn token_id(e: &Env, public_key: BytesN<65>) -> u32 {
-        let public_key_lookup = NFTStorageKey::TokenIdByPublicKey(public_key);
+    fn mint_rate_limit(e: &Env) -> u32 {
         e.storage()
-            .persistent()
-            .get::<NFTStorageKey, u32>(&public_key_lookup)
-            .unwrap_or_else(|| {
-                panic_with_error!(e, errors::NonFungibleTokenError::NonExistentToken)
-            })
+            .instance()
+            .get(&DataKey::MintRateLimit)
+            .unwrap_or(0)
     }
 
-    fn next_token_id(e: &Env) -> u32 {
-        e.storage().instance().get(&DataKey::NextTokenId).unwrap()
+    fn set_chipless_transfer_enabled(e: &Env, enabled: bool) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage()
+            .instance()
+            .set(&DataKey::ChiplessTransferEnabled, &enabled);
     }
 
-    fn public_key(e: &Env, token_id: u32) -> BytesN<65> {
+    fn set_claim_transfer_events(e: &Env, enabled: bool) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
         e.storage()
-            .persistent()
-            .get(&NFTStorageKey::PublicKey(token_id))
-            .unwrap_or_else(|| {
-                panic_with_error!(e, errors::NonFungibleTokenError::NonExistentToken)
-            })
+            .instance()
+            .set(&DataKey::ClaimTransferEvents, &enabled);
+    }
+
+    fn is_mint_enabled(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::MintEnabled)
+            .unwrap_or(true)
+    }
+
+    fn is_transfer_enabled(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::TransferEnabled)
+            .unwrap_or(true)
+    }
+
+    fn is_chipless_transfer_enabled(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::ChiplessTransferEnabled)
+            .unwrap_or(false)
+    }
+
+    fn claim_transfer_events(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::ClaimTransferEvents)
+            .unwrap_or(true)
+    }
+
+    fn set_base_uri(e: &Env, uri: String) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+        when_single_admin(e);
+
+        set_base_uri(e, uri);
+    }
+
+    fn set_uri_suffix(e: &Env, suffix: String) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if e.storage()
+            .instance()
+            .get(&DataKey::UriFrozen)
+            .unwrap_or(false)
+        {
+            panic_with_error!(e, errors::NonFungibleTokenError::MetadataFrozen);
+        }
+
+        e.storage().instance().set(&DataKey::UriSuffix, &suffix);
+    }
+
+    fn uri_suffix(e: &Env) -> String {
+        e.storage()
+            .instance()
+            .get(&DataKey::UriSuffix)
+            .unwrap_or_else(|| String::from_str(e, ""))
+    }
+
+    fn freeze_base_uri(e: &Env) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage().instance().set(&DataKey::UriFrozen, &true);
+    }
+
+    fn set_contract_uri(e: &Env, uri: String) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage().instance().set(&DataKey::ContractUri, &uri);
+
+        events::ContractUriUpdated { uri }.publish(e);
+    }
+
+    fn set_max_tokens(e: &Env, new_max: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if new_max < Self::max_tokens(e) {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidMaxTokens);
+        }
+
+        e.storage().instance().set(&DataKey::MaxTokens, &new_max);
+    }
+
+    fn set_claim_deadline(e: &Env, ledgers: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage().instance().set(&DataKey::ClaimWindow, &ledgers);
+    }
+
+    fn freeze_token(e: &Env, token_id: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if !Self::is_minted(e, token_id) {
+            panic_with_error!(e, errors::NonFungibleTokenError::NonExistentToken);
+        }
+
+        e.storage()
+            .persistent()
+            .set(&NFTStorageKey::Frozen(token_id), &true);
+    }
+
+    fn unfreeze_token(e: &Env, token_id: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage()
+            .persistent()
+            .remove(&NFTStorageKey::Frozen(token_id));
+    }
+
+    fn lock(e: &Env, owner: Address, token_id: u32, until_ledger: u32) {
+        owner.require_auth();
+
+        if Self::owner_of(e, token_id) != owner {
+            panic_with_error!(e, errors::NonFungibleTokenError::IncorrectOwner);
+        }
+        // a lock can be extended but never shortened
+        let current = Self::lock_info(e, token_id).unwrap_or(0);
+        if until_ledger <= e.ledger().sequence() || until_ledger < current {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+        }
+
+        e.storage()
+            .persistent()
+            .set(&NFTStorageKey::Lock(token_id), &until_ledger);
+
+        events::Lock {
+            owner,
+            token_id,
+            until_ledger,
+        }
+        .publish(e);
+    }
+
+    fn unlock(e: &Env, caller: Address, token_id: u32) {
+        caller.require_auth();
+
+        let key = NFTStorageKey::Lock(token_id);
+        let Some(until_ledger) = e.storage().persistent().get::<_, u32>(&key) else {
+            return;
+        };
+        let owner = Self::owner_of(e, token_id);
+        // the admin releases anytime, the owner once the deadline passed
+        if caller != Self::get_admin(e) && (caller != owner || e.ledger().sequence() < until_ledger)
+        {
+            panic_with_error!(e, errors::NonFungibleTokenError::NotAuthorized);
+        }
+
+        e.storage().persistent().remove(&key);
+
+        events::Unlock { owner, token_id }.publish(e);
+    }
+
+    fn lock_info(e: &Env, token_id: u32) -> Option<u32> {
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::Lock(token_id))
+            .filter(|until_ledger| e.ledger().sequence() < *until_ledger)
+    }
+
+    fn set_user(e: &Env, owner: Address, token_id: u32, user: Address, expires_ledger: u32) {
+        owner.require_auth();
+
+        if Self::owner_of(e, token_id) != owner {
+            panic_with_error!(e, errors::NonFungibleTokenError::IncorrectOwner);
+        }
+        if expires_ledger <= e.ledger().sequence() {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+        }
+
+        e.storage().persistent().set(
+            &NFTStorageKey::User(token_id),
+            &UserData {
+                user: user.clone(),
+                expires_ledger,
+            },
+        );
+
+        events::UserSet {
+            token_id,
+            user,
+            expires_ledger,
+        }
+        .publish(e);
+    }
+
+    fn user_of(e: &Env, token_id: u32) -> Option<Address> {
+        let user: Option<UserData> = e.storage().persistent().get(&NFTStorageKey::User(token_id));
+        user.filter(|user| e.ledger().sequence() < user.expires_ledger)
+            .map(|user| user.user)
+    }
+
+    fn block_chip(e: &Env, public_key: BytesN<65>) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage()
+            .persistent()
+            .set(&NFTStorageKey::BlockedChip(public_key), &true);
+    }
+
+    fn unblock_chip(e: &Env, public_key: BytesN<65>) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage()
+            .persistent()
+            .remove(&NFTStorageKey::BlockedChip(public_key));
+    }
+
+    fn register_chips(e: &Env, public_keys: Vec<BytesN<65>>) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        for public_key in public_keys {
+            e.storage()
+                .persistent()
+                .set(&NFTStorageKey::RegisteredChip(public_key), &true);
+        }
+    }
+
+    fn require_registration(e: &Env, enabled: bool) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage()
+            .instance()
+            .set(&DataKey::RegistrationRequired, &enabled);
+    }
+
+    fn set_default_royalty(e: &Env, receiver: Address, basis_points: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage().instance().set(
+            &DataKey::DefaultRoyalty,
+            &royalty_data(e, receiver, basis_points),
+        );
+    }
+
+    fn set_mint_fee(e: &Env, asset: Address, amount: i128) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if amount < 0 {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidFee);
+        }
+
+        e.storage()
+            .instance()
+            .set(&DataKey::MintFee, &MintFee { asset, amount });
+    }
+
+    fn mint_fee(e: &Env) -> Option<MintFee> {
+        e.storage().instance().get(&DataKey::MintFee)
+    }
+
+    fn withdraw_fees(e: &Env, asset: Address, to: Address) -> i128 {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let token = TokenClient::new(e, &asset);
+        let contract = e.current_contract_address();
+        let amount = token.balance(&contract);
+        if amount > 0 {
+            token.transfer(&contract, &to, &amount);
+            events::FeesWithdrawn { to, asset, amount }.publish(e);
+        }
+        amount
+    }
+
+    fn set_token_royalty(e: &Env, token_id: u32, receiver: Address, basis_points: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if !Self::is_minted(e, token_id) {
+            panic_with_error!(e, errors::NonFungibleTokenError::NonExistentToken);
+        }
+
+        e.storage().persistent().set(
+            &NFTStorageKey::Royalty(token_id),
+            &royalty_data(e, receiver, basis_points),
+        );
     }
 
-    fn verify_chip_signature(
+    fn mint(
         e: &Env,
-        signer: Bytes,
+        minter: Address,
         message: Bytes,
         signature: BytesN<64>,
         recovery_id: u32,
-        public_key: BytesN<65>,
+        public_key: Bytes,
         nonce: u32,
-    ) {
-        let nonce_key = NFTStorageKey::ChipNonceByPublicKey(public_key.clone());
-        let stored_nonce: u32 = e.storage().persistent().get(&nonce_key).unwrap_or(0u32);
+        expiration_ledger: u32,
+    ) -> u32 {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
 
-        // Verify nonce is monotonic increasing
-        if nonce <= stored_nonce {
-            panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidSignature);
-        }
+        require_minter(e, &minter);
 
-        // Build message hash with signer and nonce
-        let mut builder: Bytes = Bytes::new(e);
-        builder.append(&message.clone());
-        builder.append(&signer.clone());
-        builder.append(&nonce.to_xdr(e));
-        let message_hash = e.crypto().sha256(&builder);
+        mint_chip(
+            e,
+            &minter,
+            message,
+            signature,
+            recovery_id,
+            public_key,
+            nonce,
+            expiration_ledger,
+            CurveType::Secp256k1,
+        )
+    }
 
-        // Verify signature recovers to the public_key
-        let recovered = e
-            .crypto()
-            .secp256k1_recover(&message_hash, &signature, recovery_id);
-        if recovered != public_key {
-            panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidSignature);
-        }
+    fn mint_r1(
+        e: &Env,
+        minter: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<65>,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32 {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
 
-        // Update stored nonce for this public_key
-        e.storage().persistent().set(&nonce_key, &nonce);
+        require_minter(e, &minter);
+
+        mint_chip(
+            e,
+            &minter,
+            message,
+            signature,
+            0,
+            public_key.into(),
+            nonce,
+            expiration_ledger,
+            CurveType::Secp256r1,
+        )
     }
-}
 
-/// Convert an u32 to its decimal string representation as Bytes
-/// Implementation inspired by OpenZeppelin's token_id_to_string
-pub(crate) fn u32_to_decimal_bytes(e: &Env, mut value: u32) -> Bytes {
-    if value == 0 {
-        return Bytes::from_slice(e, b"0");
+    fn mint_ed25519(
+        e: &Env,
+        minter: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<32>,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32 {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        require_minter(e, &minter);
+
+        mint_chip(
+            e,
+            &minter,
+            message,
+            signature,
+            0,
+            public_key.into(),
+            nonce,
+            expiration_ledger,
+            CurveType::Ed25519,
+        )
     }
 
-    // Count digits (equivalent to log10(value) + 1 in no_std)
-    let mut temp = value;
-    let mut length = 0;
-    while temp > 0 {
-        length += 1;
-        temp /= 10;
+    fn mint_to(
+        e: &Env,
+        to: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: BytesN<65>,
+        nonce: u32,
+    ) -> u32 {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        let token_id = mint_chip(
+            e,
+            &admin,
+            message,
+            signature,
+            recovery_id,
+            public_key.into(),
+            nonce,
+            0,
+            CurveType::Secp256k1,
+        );
+        set_claimed(e, &to, token_id);
+
+        token_id
     }
 
-    // Allocate buffer with max size (20 for u32)
-    let mut buffer = [0u8; 20];
+    fn reserve_range(e: &Env, up_to: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
 
-    // Fill from right to left (most significant digit first)
-    let mut i = length;
-    while value > 0 {
-        i -= 1;
-        buffer[i] = b'0' + (value % 10) as u8;
-        value /= 10;
+        if Self::total_supply(e) != 0 {
+            panic_with_error!(e, errors::NonFungibleTokenError::MintingStarted);
+        }
+        let max_tokens: u32 = e.storage().instance().get(&DataKey::MaxTokens).unwrap();
+        let first_token_id = Self::first_token_id(e);
+        if up_to < first_token_id || up_to - first_token_id > max_tokens {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+        }
+
+        // The supply no longer follows the next id once ids are skipped
+        e.storage().instance().set(&DataKey::TotalSupply, &0u32);
+        e.storage().instance().set(&DataKey::ReservedUpTo, &up_to);
+        e.storage().instance().set(&DataKey::NextTokenId, &up_to);
     }
 
-    Bytes::from_slice(e, &buffer[..length])
+    fn reserved_range(e: &Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::ReservedUpTo)
+            .unwrap_or(0)
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn mint_reserved(
+        e: &Env,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32 {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let public_key = verify_chip(
+            e,
+            admin.to_xdr(e),
+            message,
+            signature,
+            recovery_id,
+            public_key,
+            nonce,
+            ACTION_MINT,
+            expiration_ledger,
+            CurveType::Secp256k1,
+        );
+
+        mint_token(e, public_key, CurveType::Secp256k1, Some(token_id))
+    }
+
+    fn admin_mint(e: &Env, public_key: BytesN<65>) -> u32 {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        admin_mint_key(e, public_key)
+    }
+
+    fn admin_mint_batch(e: &Env, public_keys: Vec<BytesN<65>>) -> Vec<u32> {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let mut token_ids = Vec::new(e);
+        for public_key in public_keys {
+            token_ids.push_back(admin_mint_key(e, public_key));
+        }
+        token_ids
+    }
+
+    fn batch_mint(e: &Env, minter: Address, mints: Vec<MintRequest>) -> Vec<u32> {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        require_minter(e, &minter);
+
+        let mut token_ids = Vec::new(e);
+        for request in mints {
+            token_ids.push_back(mint_chip(
+                e,
+                &minter,
+                request.message,
+                request.signature,
+                request.recovery_id,
+                request.public_key.into(),
+                request.nonce,
+                0,
+                CurveType::Secp256k1,
+            ));
+        }
+        token_ids
+    }
+
+    fn mint_with_metadata(
+        e: &Env,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: BytesN<65>,
+        nonce: u32,
+        edition: u32,
+        batch: String,
+        extra: Map<String, String>,
+    ) -> u32 {
+        let token_id = Self::mint(
+            e,
+            Self::get_admin(e),
+            message,
+            signature,
+            recovery_id,
+            public_key.into(),
+            nonce,
+            0,
+        );
+
+        e.storage().persistent().set(
+            &NFTStorageKey::Metadata(token_id),
+            &TokenMetadata {
+                edition,
+                batch,
+                minted_ledger: e.ledger().sequence(),
+                extra,
+            },
+        );
+
+        token_id
+    }
+
+    fn set_token_attribute(e: &Env, token_id: u32, key: String, value: String) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let mut metadata = Self::token_metadata(e, token_id);
+        if Self::is_metadata_frozen(e, token_id) {
+            panic_with_error!(e, errors::NonFungibleTokenError::MetadataFrozen);
+        }
+        metadata.extra.set(key, value);
+        e.storage()
+            .persistent()
+            .set(&NFTStorageKey::Metadata(token_id), &metadata);
+    }
+
+    fn freeze_token_metadata(e: &Env, token_id: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if !Self::is_minted(e, token_id) {
+            panic_with_error!(e, errors::NonFungibleTokenError::NonExistentToken);
+        }
+
+        e.storage()
+            .persistent()
+            .set(&NFTStorageKey::MetadataFrozen(token_id), &true);
+
+        events::MetadataFrozen { token_id }.publish(e);
+    }
+
+    fn freeze_all_metadata(e: &Env) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        e.storage()
+            .instance()
+            .set(&DataKey::AllMetadataFrozen, &true);
+        // token URIs derive from the base URI, so it is locked as well
+        e.storage().instance().set(&DataKey::UriFrozen, &true);
+
+        events::MetadataFrozen {
+            token_id: ALL_TOKENS,
+        }
+        .publish(e);
+    }
+
+    fn is_metadata_frozen(e: &Env, token_id: u32) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::AllMetadataFrozen)
+            .unwrap_or(false)
+            || e.storage()
+                .persistent()
+                .has(&NFTStorageKey::MetadataFrozen(token_id))
+    }
+
+    fn set_chip_url(e: &Env, caller: Address, token_id: u32, url: String) {
+        caller.require_auth();
+
+        if !Self::is_minted(e, token_id) {
+            panic_with_error!(e, errors::NonFungibleTokenError::NonExistentToken);
+        }
+        if caller != Self::get_admin(e) && Self::owner_of(e, token_id) != caller {
+            panic_with_error!(e, errors::NonFungibleTokenError::NotAuthorized);
+        }
+        if url.len() > MAX_CHIP_URL_LEN {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+        }
+
+        e.storage()
+            .persistent()
+            .set(&NFTStorageKey::ChipUrl(token_id), &url);
+
+        events::ChipUrlUpdated { token_id, url }.publish(e);
+    }
+
+    fn chip_url(e: &Env, token_id: u32) -> Option<String> {
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::ChipUrl(token_id))
+    }
+
+    fn set_chip_batch(e: &Env, public_key: BytesN<65>, batch: String) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if batch.is_empty() || batch.len() > MAX_BATCH_LEN {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+        }
+
+        // Minted chips move from the index of their previous batch
+        let token_id: Option<u32> = e
+            .storage()
+            .persistent()
+            .get(&NFTStorageKey::TokenIdByPublicKey(public_key.clone()));
+        if let Some(token_id) = token_id {
+            remove_from_batch(e, &public_key, token_id);
+        }
+        e.storage()
+            .persistent()
+            .set(&NFTStorageKey::ChipBatch(public_key.clone()), &batch);
+        if let Some(token_id) = token_id {
+            add_to_batch(e, &public_key, token_id);
+        }
+
+        events::BatchAssigned { public_key, batch }.publish(e);
+    }
+
+    fn chip_batch(e: &Env, token_id: u32) -> Option<String> {
+        let public_key: BytesN<65> = e
+            .storage()
+            .persistent()
+            .get(&NFTStorageKey::PublicKey(token_id))?;
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::ChipBatch(public_key))
+    }
+
+    fn tokens_in_batch(e: &Env, batch: String, start: u32, limit: u32) -> Vec<u32> {
+        if limit > MAX_TOKENS_PAGE {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+        }
+        let mut token_ids = Vec::new(e);
+        let end = start
+            .saturating_add(limit)
+            .min(batch_token_count(e, &batch));
+        for index in start..end {
+            let token_id: u32 = e
+                .storage()
+                .persistent()
+                .get(&NFTStorageKey::BatchToken(batch.clone(), index))
+                .unwrap_or_else(|| {
+                    panic_with_error!(e, errors::NonFungibleTokenError::IndexOutOfBounds)
+                });
+            token_ids.push_back(token_id);
+        }
+        token_ids
+    }
+
+    fn token_metadata(e: &Env, token_id: u32) -> TokenMetadata {
+        // Verify token exists (this will panic if it doesn't)
+        Self::public_key(e, token_id);
+
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::Metadata(token_id))
+            .unwrap_or_else(|| TokenMetadata {
+                edition: 0,
+                batch: String::from_str(e, ""),
+                minted_ledger: 0,
+                extra: Map::new(e),
+            })
+    }
+
+    fn claim(
+        e: &Env,
+        claimant: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32 {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        claimant.require_auth();
+
+        claim_chip(
+            e,
+            &claimant,
+            message,
+            signature,
+            recovery_id,
+            public_key,
+            nonce,
+            expiration_ledger,
+            CurveType::Secp256k1,
+        )
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn claim_with_referrer(
+        e: &Env,
+        claimant: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+        referrer: Option<Address>,
+    ) -> u32 {
+        let token_id = Self::claim(
+            e,
+            claimant.clone(),
+            message,
+            signature,
+            recovery_id,
+            public_key,
+            nonce,
+            expiration_ledger,
+        );
+
+        // Self-referrals are not attributed
+        if let Some(referrer) = referrer.filter(|referrer| *referrer != claimant) {
+            e.storage()
+                .persistent()
+                .set(&NFTStorageKey::Referrer(token_id), &referrer);
+            let count_key = NFTStorageKey::ReferralCount(referrer.clone());
+            let count = Self::referral_count(e, referrer.clone());
+            e.storage().persistent().set(&count_key, &(count + 1));
+            e.storage()
+                .persistent()
+                .extend_ttl(&count_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+            events::Referral {
+                referrer,
+                claimant,
+                token_id,
+            }
+            .publish(e);
+        }
+
+        token_id
+    }
+
+    fn referrer_of(e: &Env, token_id: u32) -> Option<Address> {
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::Referrer(token_id))
+    }
+
+    fn referral_count(e: &Env, referrer: Address) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::ReferralCount(referrer))
+            .unwrap_or(0)
+    }
+
+    fn claim_r1(
+        e: &Env,
+        claimant: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<65>,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32 {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        claimant.require_auth();
+
+        claim_chip(
+            e,
+            &claimant,
+            message,
+            signature,
+            0,
+            public_key.into(),
+            nonce,
+            expiration_ledger,
+            CurveType::Secp256r1,
+        )
+    }
+
+    fn claim_ed25519(
+        e: &Env,
+        claimant: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<32>,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32 {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        claimant.require_auth();
+
+        claim_chip(
+            e,
+            &claimant,
+            message,
+            signature,
+            0,
+            public_key.into(),
+            nonce,
+            expiration_ledger,
+            CurveType::Ed25519,
+        )
+    }
+
+    fn claim_for(
+        e: &Env,
+        claimant: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: BytesN<65>,
+        nonce: u32,
+    ) -> u32 {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+
+        claim_chip(
+            e,
+            &claimant,
+            message,
+            signature,
+            recovery_id,
+            public_key.into(),
+            nonce,
+            0,
+            CurveType::Secp256k1,
+        )
+    }
+
+    fn reclaim_expired(e: &Env, token_id: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        if !Self::is_minted(e, token_id) {
+            panic_with_error!(e, errors::NonFungibleTokenError::NonExistentToken);
+        }
+        if Self::is_claimed(e, token_id) {
+            panic_with_error!(e, errors::NonFungibleTokenError::TokenAlreadyClaimed);
+        }
+        match Self::claim_deadline(e, token_id) {
+            Some(deadline) if e.ledger().sequence() > deadline => {}
+            _ => panic_with_error!(e, errors::NonFungibleTokenError::ClaimWindowOpen),
+        }
+
+        set_claimed(e, &admin, token_id);
+    }
+
+    fn batch_claim(e: &Env, claimant: Address, claims: Vec<ClaimRequest>) -> Vec<u32> {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::MintEnabled);
+
+        claimant.require_auth();
+
+        let mut token_ids = Vec::new(e);
+        for request in claims {
+            token_ids.push_back(claim_chip(
+                e,
+                &claimant,
+                request.message,
+                request.signature,
+                request.recovery_id,
+                request.public_key.into(),
+                request.nonce,
+                0,
+                CurveType::Secp256k1,
+            ));
+        }
+        token_ids
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn transfer(
+        e: &Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::TransferEnabled);
+        when_not_soulbound(e);
+        when_not_frozen(e, token_id);
+        when_not_locked(e, token_id);
+
+        from.require_auth();
+
+        transfer_chip(
+            e,
+            from,
+            to,
+            token_id,
+            message,
+            signature,
+            recovery_id,
+            public_key,
+            nonce,
+            expiration_ledger,
+            CurveType::Secp256k1,
+        );
+    }
+
+    fn safe_transfer(
+        e: &Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) {
+        Self::transfer(
+            e,
+            from.clone(),
+            to.clone(),
+            token_id,
+            message,
+            signature,
+            recovery_id,
+            public_key,
+            nonce,
+            expiration_ledger,
+        );
+
+        if !accepts_token(e, &from, &from, &to, token_id) {
+            panic_with_error!(e, errors::NonFungibleTokenError::UnsafeRecipient);
+        }
+    }
+
+    fn transfer_r1(
+        e: &Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<65>,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::TransferEnabled);
+        when_not_soulbound(e);
+        when_not_frozen(e, token_id);
+        when_not_locked(e, token_id);
+
+        from.require_auth();
+
+        transfer_chip(
+            e,
+            from,
+            to,
+            token_id,
+            message,
+            signature,
+            0,
+            public_key.into(),
+            nonce,
+            expiration_ledger,
+            CurveType::Secp256r1,
+        );
+    }
+
+    fn transfer_ed25519(
+        e: &Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<32>,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::TransferEnabled);
+        when_not_soulbound(e);
+        when_not_frozen(e, token_id);
+        when_not_locked(e, token_id);
+
+        from.require_auth();
+
+        transfer_chip(
+            e,
+            from,
+            to,
+            token_id,
+            message,
+            signature,
+            0,
+            public_key.into(),
+            nonce,
+            expiration_ledger,
+            CurveType::Ed25519,
+        );
+    }
+
+    fn transfer_owner(e: &Env, from: Address, to: Address, token_id: u32) {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::TransferEnabled);
+        when_not_soulbound(e);
+        when_not_frozen(e, token_id);
+        when_not_locked(e, token_id);
+        if !Self::is_chipless_transfer_enabled(e) {
+            panic_with_error!(e, errors::NonFungibleTokenError::OperationDisabled);
+        }
+
+        from.require_auth();
+
+        let (_, owner) = token_owner(e, token_id);
+        if owner != from || from == to {
+            panic_with_error!(e, &errors::NonFungibleTokenError::IncorrectOwner);
+        }
+
+        update_owner(e, &from, &to, token_id);
+
+        events::Transfer { from, to, token_id }.publish(e);
+    }
+
+    fn admin_transfer(e: &Env, token_id: u32, to: Address, owner_consent: bool) {
+        when_not_paused(e);
+        if !owner_consent {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+        }
+
+        let (_, from) = token_owner(e, token_id);
+        if from == to {
+            panic_with_error!(e, &errors::NonFungibleTokenError::IncorrectOwner);
+        }
+        Self::get_admin(e).require_auth();
+        from.require_auth();
+        // The owner of a quarantined token is the admin, the admins release it
+        if e.storage()
+            .persistent()
+            .has(&NFTStorageKey::Quarantine(token_id))
+        {
+            when_single_admin(e);
+        }
+
+        update_owner(e, &from, &to, token_id);
+        let lost_key = NFTStorageKey::ChipLost(token_id);
+        e.storage().persistent().set(&lost_key, &true);
+        e.storage()
+            .persistent()
+            .extend_ttl(&lost_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+        events::ForcedTransfer { from, to, token_id }.publish(e);
+    }
+
+    fn is_chip_lost(e: &Env, token_id: u32) -> bool {
+        e.storage()
+            .persistent()
+            .has(&NFTStorageKey::ChipLost(token_id))
+    }
+
+    fn mint_v2(
+        e: &Env,
+        minter: Address,
+        payload: SignedAction,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32 {
+        let message = signed_action_message(e, &payload, symbol_short!("mint"));
+        let token_id = Self::mint(
+            e,
+            minter,
+            message,
+            signature,
+            recovery_id,
+            public_key,
+            nonce,
+            expiration_ledger,
+        );
+        check_signed_token_id(e, &payload, token_id);
+        publish_memo(e, payload, token_id);
+        token_id
+    }
+
+    fn claim_v2(
+        e: &Env,
+        claimant: Address,
+        payload: SignedAction,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32 {
+        let message = signed_action_message(e, &payload, symbol_short!("claim"));
+        let token_id = Self::claim(
+            e,
+            claimant,
+            message,
+            signature,
+            recovery_id,
+            public_key,
+            nonce,
+            expiration_ledger,
+        );
+        check_signed_token_id(e, &payload, token_id);
+        publish_memo(e, payload, token_id);
+        token_id
+    }
+
+    fn transfer_v2(
+        e: &Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        payload: SignedAction,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) {
+        let message = signed_action_message(e, &payload, symbol_short!("transfer"));
+        if payload
+            .to
+            .as_ref()
+            .is_some_and(|signed_to| *signed_to != to)
+        {
+            panic_with_error!(e, errors::NonFungibleTokenError::PayloadMismatch);
+        }
+        check_signed_token_id(e, &payload, token_id);
+        Self::transfer(
+            e,
+            from,
+            to,
+            token_id,
+            message,
+            signature,
+            recovery_id,
+            public_key,
+            nonce,
+            expiration_ledger,
+        );
+        publish_memo(e, payload, token_id);
+    }
+
+    fn transfer_from(
+        e: &Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: BytesN<65>,
+        nonce: u32,
+    ) {
+        when_not_paused(e);
+        when_enabled(e, &DataKey::TransferEnabled);
+        when_not_soulbound(e);
+        when_not_frozen(e, token_id);
+        when_not_locked(e, token_id);
+
+        spender.require_auth();
+
+        verify_chip(
+            e,
+            spender.clone().to_xdr(e),
+            message,
+            signature,
+            recovery_id,
+            public_key.clone().into(),
+            nonce,
+            ACTION_TRANSFER,
+            0,
+            CurveType::Secp256k1,
+        );
+
+        // Verify the chip public_key corresponds to that specific token_id
+        let token_id_public_key: BytesN<65> = Self::public_key(e, token_id);
+
+        if token_id_public_key != public_key {
+            panic_with_error!(&e, &errors::NonFungibleTokenError::SignerMismatch);
+        }
+
+        let owner = claimed_owner(e, token_id);
+        if owner != from || from == to {
+            panic_with_error!(e, &errors::NonFungibleTokenError::IncorrectOwner);
+        }
+
+        if spender != owner && Self::get_approved(e, token_id) != Some(spender) {
+            panic_with_error!(e, &errors::NonFungibleTokenError::InsufficientApproval);
+        }
+
+        update_owner(e, &from, &to, token_id);
+
+        events::Transfer { from, to, token_id }.publish(e);
+    }
+
+    fn approve(
+        e: &Env,
+        approver: Address,
+        approved: Address,
+        token_id: u32,
+        live_until_ledger: u32,
+    ) {
+        approver.require_auth();
+
+        let owner = Self::owner_of(e, token_id);
+        if owner != approver {
+            panic_with_error!(e, &errors::NonFungibleTokenError::NotAuthorized);
+        }
+
+        let approval_key = NFTStorageKey::Approval(token_id);
+        if live_until_ledger == 0 {
+            e.storage().persistent().remove(&approval_key);
+        } else {
+            let current_ledger = e.ledger().sequence();
+            if live_until_ledger < current_ledger {
+                panic_with_error!(e, &errors::NonFungibleTokenError::InvalidLiveUntilLedger);
+            }
+
+            e.storage().persistent().set(
+                &approval_key,
+                &ApprovalData {
+                    approved: approved.clone(),
+                    live_until_ledger,
+                },
+            );
+        }
+
+        events::Approve {
+            approver,
+            token_id,
+            approved,
+            live_until_ledger,
+        }
+        .publish(e);
+    }
+
+    fn get_approved(e: &Env, token_id: u32) -> Option<Address> {
+        let approval: Option<ApprovalData> = e
+            .storage()
+            .persistent()
+            .get(&NFTStorageKey::Approval(token_id));
+
+        // Expired approvals are ignored
+        approval
+            .filter(|approval| approval.live_until_ledger >= e.ledger().sequence())
+            .map(|approval| approval.approved)
+    }
+
+    fn clawback(e: &Env, token_id: u32, reason: u32) {
+        let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        when_single_admin(e);
+
+        clawback_token(e, token_id, reason);
+    }
+
+    fn clawback_release(e: &Env, token_id: u32, to: Address) {
+        let admin: Address = e.storage().instance().get(&DataKey::Admin).unwrap();
+        admin.require_auth();
+        when_single_admin(e);
+
+        release_token(e, token_id, to);
+    }
+
+    #[allow(clippy::too_many_arguments)]
+    fn rotate_chip(
+        e: &Env,
+        token_id: u32,
+        new_public_key: BytesN<65>,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        nonce: u32,
+    ) {
+        let owner = Self::owner_of(e, token_id);
+        owner.require_auth();
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let old_public_key = Self::public_key(e, token_id);
+
+        let new_lookup = NFTStorageKey::TokenIdByPublicKey(new_public_key.clone());
+        if e.storage().persistent().has(&new_lookup) {
+            panic_with_error!(e, errors::NonFungibleTokenError::TokenAlreadyMinted);
+        }
+        when_not_retired(e, &new_public_key);
+
+        // The new chip proves possession by signing over the owner
+        verify_chip(
+            e,
+            owner.to_xdr(e),
+            message,
+            signature,
+            recovery_id,
+            new_public_key.clone().into(),
+            nonce,
+            ACTION_ROTATE,
+            0,
+            CurveType::Secp256k1,
+        );
+
+        // The old nonce is kept so signatures of the old chip cannot be replayed,
+        // and the old chip is retired so it cannot be minted as a new token
+        remove_from_batch(e, &old_public_key, token_id);
+        retire_chip(e, &old_public_key, token_id);
+        e.storage()
+            .persistent()
+            .remove(&NFTStorageKey::TokenIdByPublicKey(old_public_key.clone()));
+        if old_public_key.get(0) != Some(ED25519_KEY_PREFIX) {
+            e.storage()
+                .persistent()
+                .remove(&NFTStorageKey::UncompressedPublicKey(compress_public_key(
+                    e,
+                    &old_public_key,
+                )));
+        }
+
+        e.storage().persistent().set(&new_lookup, &token_id);
+        e.storage()
+            .persistent()
+            .set(&NFTStorageKey::PublicKey(token_id), &new_public_key);
+        e.storage()
+            .persistent()
+            .set(&NFTStorageKey::Curve(token_id), &CurveType::Secp256k1);
+        e.storage()
+            .persistent()
+            .remove(&NFTStorageKey::ChipLost(token_id));
+        add_to_batch(e, &new_public_key, token_id);
+
+        extend_token_entries(e, token_id, &new_public_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+        events::ChipRotated {
+            token_id,
+            old_public_key,
+            new_public_key,
+        }
+        .publish(e);
+    }
+
+    fn add_backup_key(
+        e: &Env,
+        token_id: u32,
+        backup_key: BytesN<65>,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        nonce: u32,
+    ) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        // Verify token exists (this will panic if it doesn't)
+        let public_key = Self::public_key(e, token_id);
+        if Self::backup_key(e, token_id).is_some() {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+        }
+        let lookup = NFTStorageKey::TokenIdByPublicKey(backup_key.clone());
+        if e.storage().persistent().has(&lookup) {
+            panic_with_error!(e, errors::NonFungibleTokenError::TokenAlreadyMinted);
+        }
+        when_not_retired(e, &backup_key);
+
+        // The backup chip proves possession by signing over the admin
+        verify_chip(
+            e,
+            admin.to_xdr(e),
+            message,
+            signature,
+            recovery_id,
+            backup_key.clone().into(),
+            nonce,
+            ACTION_BACKUP,
+            0,
+            CurveType::Secp256k1,
+        );
+
+        e.storage().persistent().set(&lookup, &token_id);
+        e.storage()
+            .persistent()
+            .set(&NFTStorageKey::BackupKey(token_id), &backup_key);
+        extend_token_entries(e, token_id, &public_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+        events::BackupKeyAdded {
+            token_id,
+            backup_key,
+        }
+        .publish(e);
+    }
+
+    fn remove_backup_key(e: &Env, token_id: u32) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let Some(backup_key) = Self::backup_key(e, token_id) else {
+            return;
+        };
+        // The nonce is kept so signatures of the backup chip cannot be replayed,
+        // and the chip is retired so it cannot be minted as a new token
+        retire_chip(e, &backup_key, token_id);
+        e.storage()
+            .persistent()
+            .remove(&NFTStorageKey::TokenIdByPublicKey(backup_key.clone()));
+        e.storage()
+            .persistent()
+            .remove(&NFTStorageKey::UncompressedPublicKey(compress_public_key(
+                e,
+                &backup_key,
+            )));
+        e.storage()
+            .persistent()
+            .remove(&NFTStorageKey::BackupKey(token_id));
+
+        events::BackupKeyRemoved {
+            token_id,
+            backup_key,
+        }
+        .publish(e);
+    }
+
+    fn backup_key(e: &Env, token_id: u32) -> Option<BytesN<65>> {
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::BackupKey(token_id))
+    }
+
+    fn extend_token_ttl(e: &Env, token_id: u32, extend_to: u32) {
+        let extend_to = extend_to.min(e.storage().max_ttl());
+        let public_key = Self::public_key(e, token_id);
+        extend_token_entries(e, token_id, &public_key, extend_to, extend_to);
+    }
+
+    fn extend_instance_ttl(e: &Env, extend_to: u32) {
+        let extend_to = extend_to.min(e.storage().max_ttl());
+        e.storage().instance().extend_ttl(extend_to, extend_to);
+    }
+
+    fn reset_nonce(e: &Env, public_key: BytesN<65>, new_nonce: u32, force: bool) {
+        let admin = Self::get_admin(e);
+        admin.require_auth();
+
+        let old_nonce = Self::get_nonce(e, public_key.clone().into());
+        if new_nonce < old_nonce && !force {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidNonce);
+        }
+
+        e.storage().persistent().set(
+            &NFTStorageKey::ChipNonceByPublicKey(public_key.clone()),
+            &new_nonce,
+        );
+
+        events::NonceReset {
+            public_key,
+            old_nonce,
+            new_nonce,
+        }
+        .publish(e);
+    }
+
+    fn get_nonce(e: &Env, public_key: Bytes) -> u32 {
+        let Some(public_key) = stored_public_key(e, public_key) else {
+            return 0u32;
+        };
+        let nonce_key = NFTStorageKey::ChipNonceByPublicKey(public_key);
+        e.storage().persistent().get(&nonce_key).unwrap_or(0u32) // Default to 0 if not set (first use)
+    }
+
+    fn get_nonces(e: &Env, public_keys: Vec<BytesN<65>>) -> Vec<u32> {
+        let mut nonces = Vec::new(e);
+        for public_key in public_keys {
+            nonces.push_back(
+                e.storage()
+                    .persistent()
+                    .get(&NFTStorageKey::ChipNonceByPublicKey(public_key))
+                    .unwrap_or(0u32),
+            );
+        }
+        nonces
+    }
+
+    fn is_nonce_used(e: &Env, public_key: Bytes, nonce: u32) -> bool {
+        // nonces strictly increase, so every nonce up to the last one is spent
+        nonce <= Self::get_nonce(e, public_key)
+    }
+
+    fn balance(e: &Env, owner: Address) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::Balance(owner))
+            .unwrap_or(0u32)
+    }
+
+    fn tokens_of_owner(e: &Env, owner: Address) -> Vec<u32> {
+        let mut tokens = Vec::new(e);
+        for index in 0..owner_token_count(e, &owner) {
+            tokens.push_back(Self::token_of_owner_by_index(e, owner.clone(), index));
+        }
+        tokens
+    }
+
+    fn token_of_owner_by_index(e: &Env, owner: Address, index: u32) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::OwnerToken(owner, index))
+            .unwrap_or_else(|| {
+                panic_with_error!(e, errors::NonFungibleTokenError::IndexOutOfBounds)
+            })
+    }
+
+    fn enumerate_tokens(e: &Env, token_ids: Vec<u32>) {
+        for token_id in token_ids {
+            let owner: Option<Address> = e
+                .storage()
+                .persistent()
+                .get(&NFTStorageKey::Owner(token_id));
+            if let Some(owner) = owner
+                && !e
+                    .storage()
+                    .persistent()
+                    .has(&NFTStorageKey::OwnerTokenIndex(token_id))
+            {
+                add_to_owner_enumeration(e, &owner, token_id);
+            }
+        }
+    }
+
+    fn tokens(e: &Env, start: u32, limit: u32) -> Vec<u32> {
+        let mut tokens = Vec::new(e);
+        for (token_id, _) in Self::tokens_detailed(e, start, limit) {
+            tokens.push_back(token_id);
+        }
+        tokens
+    }
+
+    fn unclaimed_tokens(e: &Env, start: u32, limit: u32) -> Vec<u32> {
+        let mut tokens = Vec::new(e);
+        for (token_id, owner) in Self::tokens_detailed(e, start, limit) {
+            if owner.is_none() {
+                tokens.push_back(token_id);
+            }
+        }
+        tokens
+    }
+
+    fn owners(e: &Env, start_token: u32, limit: u32) -> Vec<(u32, Address)> {
+        if limit > MAX_OWNERS_PAGE {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+        }
+
+        let mut owners = Vec::new(e);
+        let end = start_token
+            .saturating_add(limit)
+            .min(Self::next_token_id(e));
+        for token_id in start_token..end {
+            // Unclaimed and missing ids have no owner entry
+            let owner: Option<Address> = e
+                .storage()
+                .persistent()
+                .get(&NFTStorageKey::Owner(token_id));
+            if let Some(owner) = owner {
+                owners.push_back((token_id, owner));
+            }
+        }
+        owners
+    }
+
+    fn tokens_detailed(e: &Env, start: u32, limit: u32) -> Vec<(u32, Option<Address>)> {
+        if limit > MAX_TOKENS_PAGE {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+        }
+        let mut tokens = Vec::new(e);
+        let end = start.saturating_add(limit).min(Self::next_token_id(e));
+        for token_id in start..end {
+            // Skip ids which do not hold a token
+            if !e
+                .storage()
+                .persistent()
+                .has(&NFTStorageKey::PublicKey(token_id))
+            {
+                continue;
+            }
+            let owner: Option<Address> = e
+                .storage()
+                .persistent()
+                .get(&NFTStorageKey::Owner(token_id));
+            tokens.push_back((token_id, owner));
+        }
+        tokens
+    }
+
+    fn owner_of(e: &Env, token_id: u32) -> Address {
+        let (_, owner) = token_owner(e, token_id);
+        owner
+    }
+
+    fn owners_of(e: &Env, token_ids: Vec<u32>) -> Vec<Option<Address>> {
+        let mut owners = Vec::new(e);
+        for token_id in token_ids {
+            owners.push_back(
+                e.storage()
+                    .persistent()
+                    .get(&NFTStorageKey::Owner(token_id)),
+            );
+        }
+        owners
+    }
+
+    fn balances_of(e: &Env, owners: Vec<Address>) -> Vec<u32> {
+        let mut balances = Vec::new(e);
+        for owner in owners {
+            balances.push_back(Self::balance(e, owner));
+        }
+        balances
+    }
+
+    fn is_minted(e: &Env, token_id: u32) -> bool {
+        e.storage()
+            .persistent()
+            .has(&NFTStorageKey::PublicKey(token_id))
+    }
+
+    fn is_claimed(e: &Env, token_id: u32) -> bool {
+        e.storage()
+            .persistent()
+            .has(&NFTStorageKey::Owner(token_id))
+    }
+
+    fn token_info(e: &Env, token_id: u32) -> TokenInfo {
+        let public_key = Self::public_key(e, token_id);
+        // Tokens minted before the history was recorded report ledger 0
+        let history = e
+            .storage()
+            .persistent()
+            .get(&NFTStorageKey::History(token_id))
+            .unwrap_or(TokenHistory {
+                minted_ledger: 0,
+                minted_timestamp: 0,
+                claimed_ledger: None,
+                claimed_timestamp: None,
+            });
+
+        TokenInfo {
+            owner: e
+                .storage()
+                .persistent()
+                .get(&NFTStorageKey::Owner(token_id)),
+            public_key,
+            minted_ledger: history.minted_ledger,
+            minted_timestamp: history.minted_timestamp,
+            claimed_ledger: history.claimed_ledger,
+            claimed_timestamp: history.claimed_timestamp,
+            status: Self::token_status(e, token_id),
+        }
+    }
+
+    fn token_status(e: &Env, token_id: u32) -> TokenStatus {
+        if !Self::is_minted(e, token_id) {
+            TokenStatus::NotMinted
+        } else if !Self::is_claimed(e, token_id) {
+            TokenStatus::Minted
+        } else if e
+            .storage()
+            .persistent()
+            .has(&NFTStorageKey::Quarantine(token_id))
+        {
+            TokenStatus::Quarantined
+        } else {
+            TokenStatus::Claimed
+        }
+    }
+
+    fn claim_deadline(e: &Env, token_id: u32) -> Option<u32> {
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::ClaimDeadline(token_id))
+    }
+
+    fn is_chip_blocked(e: &Env, public_key: BytesN<65>) -> bool {
+        e.storage()
+            .persistent()
+            .has(&NFTStorageKey::BlockedChip(public_key))
+    }
+
+    fn resolve_chip(e: &Env, public_key: BytesN<65>) -> ChipResolution {
+        let token_id: Option<u32> = e
+            .storage()
+            .persistent()
+            .get(&NFTStorageKey::TokenIdByPublicKey(public_key.clone()));
+        let owner: Option<Address> = token_id.and_then(|token_id| {
+            e.storage()
+                .persistent()
+                .get(&NFTStorageKey::Owner(token_id))
+        });
+
+        ChipResolution {
+            token_id,
+            claimed: owner.is_some(),
+            owner,
+            blocked: Self::is_chip_blocked(e, public_key),
+        }
+    }
+
+    fn is_chip_registered(e: &Env, public_key: BytesN<65>) -> bool {
+        e.storage()
+            .persistent()
+            .has(&NFTStorageKey::RegisteredChip(public_key))
+    }
+
+    fn provenance(e: &Env, token_id: u32) -> Vec<ProvenanceEntry> {
+        // Verify token exists (this will panic if it doesn't)
+        Self::public_key(e, token_id);
+
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::Provenance(token_id))
+            .unwrap_or(Vec::new(e))
+    }
+
+    fn is_soulbound(e: &Env) -> bool {
+        e.storage()
+            .instance()
+            .get(&DataKey::Soulbound)
+            .unwrap_or(false)
+    }
+
+    fn is_frozen(e: &Env, token_id: u32) -> bool {
+        e.storage()
+            .persistent()
+            .has(&NFTStorageKey::Frozen(token_id))
+    }
+
+    fn royalty_info(e: &Env, token_id: u32, sale_price: i128) -> (Address, i128) {
+        // Verify token exists (this will panic if it doesn't)
+        Self::public_key(e, token_id);
+
+        let royalty: Option<RoyaltyData> = e
+            .storage()
+            .persistent()
+            .get(&NFTStorageKey::Royalty(token_id))
+            .or_else(|| e.storage().instance().get(&DataKey::DefaultRoyalty));
+        let Some(royalty) = royalty else {
+            return (e.current_contract_address(), 0);
+        };
+
+        // split the multiplication so it cannot overflow
+        let basis_points = royalty.basis_points as i128;
+        let amount =
+            (sale_price / 10_000) * basis_points + (sale_price % 10_000) * basis_points / 10_000;
+
+        (royalty.receiver, amount)
+    }
+
+    fn name(e: &Env) -> String {
+        get_instance_metadata(e, &DataKey::Name)
+    }
+
+    fn symbol(e: &Env) -> String {
+        get_instance_metadata(e, &DataKey::Symbol)
+    }
+
+    fn base_uri(e: &Env) -> String {
+        get_instance_metadata(e, &DataKey::Uri)
+    }
+
+    fn contract_uri(e: &Env) -> String {
+        e.storage()
+            .instance()
+            .get(&DataKey::ContractUri)
+            .unwrap_or_else(|| String::from_str(e, ""))
+    }
+
+    fn max_tokens(e: &Env) -> u32 {
+        get_instance_metadata(e, &DataKey::MaxTokens)
+    }
+
+    fn first_token_id(e: &Env) -> u32 {
+        // Deployments predating the option started at 0
+        e.storage()
+            .instance()
+            .get(&DataKey::FirstTokenId)
+            .unwrap_or(0)
+    }
+
+    fn token_uri(e: &Env, token_id: u32) -> String {
+        // Verify token exists (this will panic if it doesn't)
+        Self::public_key(e, token_id);
+
+        let base_uri: String = e.storage().instance().get(&DataKey::Uri).unwrap();
+
+        // Construct Uri: {base_uri}/{token_id}{suffix}
+        let mut uri_bytes = Bytes::new(e);
+        uri_bytes.append(&Bytes::from(base_uri));
+        uri_bytes.append(&Bytes::from_slice(e, b"/"));
+        uri_bytes.append(&u32_to_decimal_bytes(e, token_id));
+        uri_bytes.append(&Bytes::from(Self::uri_suffix(e)));
+
+        String::from(uri_bytes)
+    }
+
+    fn token_id(e: &Env, public_key: Bytes) -> u32 {
+        let Some(public_key) = stored_public_key(e, public_key) else {
+            panic_with_error!(e, errors::NonFungibleTokenError::NonExistentToken)
+        };
+        let public_key_lookup = NFTStorageKey::TokenIdByPublicKey(public_key);
+        e.storage()
+            .persistent()
+            .get::<NFTStorageKey, u32>(&public_key_lookup)
+            .unwrap_or_else(|| {
+                panic_with_error!(e, errors::NonFungibleTokenError::NonExistentToken)
+            })
+    }
+
+    fn next_token_id(e: &Env) -> u32 {
+        e.storage().instance().get(&DataKey::NextTokenId).unwrap()
+    }
+
+    fn total_supply(e: &Env) -> u32 {
+        // Deployments predating the counter minted ids sequentially from 0
+        e.storage()
+            .instance()
+            .get(&DataKey::TotalSupply)
+            .unwrap_or_else(|| Self::next_token_id(e))
+    }
+
+    fn claimed_count(e: &Env) -> u32 {
+        e.storage()
+            .instance()
+            .get(&DataKey::ClaimedCount)
+            .unwrap_or(0u32)
+    }
+
+    fn claimed_token_by_index(e: &Env, index: u32) -> u32 {
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::ClaimedToken(index))
+            .unwrap_or_else(|| {
+                panic_with_error!(e, errors::NonFungibleTokenError::IndexOutOfBounds)
+            })
+    }
+
+    fn unclaimed_count(e: &Env) -> u32 {
+        // Deployments predating the counter never lost claimed tokens
+        e.storage()
+            .instance()
+            .get(&DataKey::UnclaimedCount)
+            .unwrap_or_else(|| Self::total_supply(e) - Self::claimed_count(e))
+    }
+
+    fn public_key(e: &Env, token_id: u32) -> BytesN<65> {
+        e.storage()
+            .persistent()
+            .get(&NFTStorageKey::PublicKey(token_id))
+            .unwrap_or_else(|| {
+                panic_with_error!(e, errors::NonFungibleTokenError::NonExistentToken)
+            })
+    }
+
+    fn curve(e: &Env, token_id: u32) -> CurveType {
+        if let Some(curve) = e
+            .storage()
+            .persistent()
+            .get(&NFTStorageKey::Curve(token_id))
+        {
+            return curve;
+        }
+        // Tokens minted before curves were stored are secp256k1
+        Self::public_key(e, token_id);
+        CurveType::Secp256k1
+    }
+
+    fn chip_key(e: &Env, token_id: u32) -> ChipKey {
+        let public_key = Self::public_key(e, token_id);
+        match Self::curve(e, token_id) {
+            CurveType::Secp256k1 => ChipKey::Secp256k1(public_key),
+            CurveType::Secp256r1 => ChipKey::Secp256r1(public_key),
+            CurveType::Ed25519 => {
+                let key: BytesN<32> = Bytes::from(public_key).slice(1..33).try_into().unwrap();
+                ChipKey::Ed25519(key)
+            }
+        }
+    }
+
+    fn check_chip_signature(
+        e: &Env,
+        signer: Bytes,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        action: u32,
+        expiration_ledger: u32,
+    ) -> bool {
+        // Malformed input is rejected beforehand, secp256k1_recover still traps
+        // on an r which is not the x coordinate of a curve point
+        if !is_sec1_public_key(&public_key) {
+            return false;
+        }
+        if recovery_id > 1 && recovery_id != RECOVERY_ID_AUTO {
+            return false;
+        }
+        if !is_low_s(&signature) || !has_valid_scalars(&signature) {
+            return false;
+        }
+        let Ok(action) = u8::try_from(action) else {
+            return false;
+        };
+        if expiration_ledger != 0 && e.ledger().sequence() > expiration_ledger {
+            return false;
+        }
+
+        let message_hash =
+            chip_message_hash(e, &signer, &message, nonce, action, expiration_ledger);
+        let Some(public_key) =
+            recover_secp256k1(e, &message_hash, &signature, recovery_id, &public_key)
+        else {
+            return false;
+        };
+
+        !Self::is_chip_blocked(e, public_key.clone())
+            && nonce == Self::get_nonce(e, public_key.into()).saturating_add(1)
+    }
+
+    fn consume_chip_signature(
+        e: &Env,
+        signer: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        action: u32,
+        expiration_ledger: u32,
+    ) {
+        signer.require_auth();
+
+        // Signatures for this contract's own actions are only used by it
+        if action > u8::MAX as u32
+            || [
+                ACTION_MINT,
+                ACTION_CLAIM,
+                ACTION_TRANSFER,
+                ACTION_ROTATE,
+                ACTION_BACKUP,
+            ]
+            .contains(&action)
+        {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidAction);
+        }
+
+        verify_chip(
+            e,
+            signer.to_xdr(e),
+            message,
+            signature,
+            recovery_id,
+            public_key,
+            nonce,
+            action,
+            expiration_ledger,
+            CurveType::Secp256k1,
+        );
+    }
+
+    fn message_hash(
+        e: &Env,
+        signer: Address,
+        message: Bytes,
+        nonce: u32,
+        action: u32,
+        expiration_ledger: u32,
+    ) -> BytesN<32> {
+        let Ok(action) = u8::try_from(action) else {
+            panic_with_error!(e, errors::NonFungibleTokenError::InvalidAction);
+        };
+        chip_message_hash(
+            e,
+            &signer.to_xdr(e),
+            &message,
+            nonce,
+            action,
+            expiration_ledger,
+        )
+        .to_bytes()
+    }
+
+    fn verify_presence(
+        e: &Env,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+    ) -> bool {
+        let Some(public_key) = e
+            .storage()
+            .persistent()
+            .get::<_, BytesN<65>>(&NFTStorageKey::PublicKey(token_id))
+        else {
+            return false;
+        };
+        // Malformed input is rejected beforehand, secp256k1_recover still traps
+        // on an r which is not the x coordinate of a curve point
+        if Self::curve(e, token_id) != CurveType::Secp256k1
+            || (recovery_id > 1 && recovery_id != RECOVERY_ID_AUTO)
+            || !is_low_s(&signature)
+            || !has_valid_scalars(&signature)
+        {
+            return false;
+        }
+
+        let message_hash = e.crypto().sha256(&message);
+        recover_secp256k1(
+            e,
+            &message_hash,
+            &signature,
+            recovery_id,
+            &public_key.into(),
+        )
+        .is_some()
+    }
+}
+
+/// Convert an u32 to its decimal string representation as Bytes
+/// Implementation inspired by OpenZeppelin's token_id_to_string
+pub(crate) fn u32_to_decimal_bytes(e: &Env, mut value: u32) -> Bytes {
+    if value == 0 {
+        return Bytes::from_slice(e, b"0");
+    }
+
+    // Count digits (equivalent to log10(value) + 1 in no_std)
+    let mut temp = value;
+    let mut length = 0;
+    while temp > 0 {
+        length += 1;
+        temp /= 10;
+    }
+
+    // Allocate buffer with max size (20 for u32)
+    let mut buffer = [0u8; 20];
+
+    // Fill from right to left (most significant digit first)
+    let mut i = length;
+    while value > 0 {
+        i -= 1;
+        buffer[i] = b'0' + (value % 10) as u8;
+        value /= 10;
+    }
+
+    Bytes::from_slice(e, &buffer[..length])
+}
+
+// verify the chip signature and nonce, returns the uncompressed public key of the chip
+#[allow(clippy::too_many_arguments)]
+fn verify_chip(
+    e: &Env,
+    signer: Bytes,
+    message: Bytes,
+    signature: BytesN<64>,
+    recovery_id: u32,
+    public_key: Bytes,
+    nonce: u32,
+    action: u32,
+    expiration_ledger: u32,
+    curve: CurveType,
+) -> BytesN<65> {
+    // secp256r1 signatures are verified against the key, which must be uncompressed
+    let valid_key = match curve {
+        CurveType::Secp256k1 => is_sec1_public_key(&public_key),
+        CurveType::Secp256r1 => public_key.len() == 65 && is_sec1_public_key(&public_key),
+        CurveType::Ed25519 => public_key.len() == 32,
+    };
+    if !valid_key {
+        panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidPublicKey);
+    }
+
+    if curve == CurveType::Secp256k1 {
+        if recovery_id > 1 && recovery_id != RECOVERY_ID_AUTO {
+            panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidRecoveryId);
+        }
+        if !is_low_s(&signature) {
+            panic_with_error!(&e, &errors::NonFungibleTokenError::MalleableSignature);
+        }
+        if !has_valid_scalars(&signature) {
+            panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidSignature);
+        }
+    }
+
+    // Only a single byte of the action is signed
+    let Ok(action) = u8::try_from(action) else {
+        panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidSignature);
+    };
+
+    if expiration_ledger != 0 && e.ledger().sequence() > expiration_ledger {
+        panic_with_error!(&e, &errors::NonFungibleTokenError::SignatureExpired);
+    }
+    let message_hash = chip_message_hash(e, &signer, &message, nonce, action, expiration_ledger);
+
+    let public_key = match curve {
+        CurveType::Secp256k1 => {
+            let Some(recovered) =
+                recover_secp256k1(e, &message_hash, &signature, recovery_id, &public_key)
+            else {
+                panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidSignature);
+            };
+            recovered
+        }
+        CurveType::Secp256r1 => {
+            // Traps if the signature is not valid for the public_key
+            let public_key: BytesN<65> = BytesN::try_from(public_key).unwrap();
+            e.crypto()
+                .secp256r1_verify(&public_key, &message_hash, &signature);
+            public_key
+        }
+        CurveType::Ed25519 => {
+            // Traps if the signature is not valid for the public_key
+            let public_key: BytesN<32> = BytesN::try_from(public_key).unwrap();
+            e.crypto()
+                .ed25519_verify(&public_key, &message_hash.to_bytes().into(), &signature);
+            ed25519_storage_key(e, &public_key)
+        }
+    };
+
+    if NFCtoNFT::is_chip_blocked(e, public_key.clone()) {
+        panic_with_error!(&e, &errors::NonFungibleTokenError::ChipBlocked);
+    }
+
+    let nonce_key = NFTStorageKey::ChipNonceByPublicKey(public_key.clone());
+    let stored_nonce: u32 = e.storage().persistent().get(&nonce_key).unwrap_or(0u32);
+
+    // Verify nonce is the next one, signatures cannot be used out of order
+    let expected_nonce = stored_nonce.saturating_add(1);
+    if nonce != expected_nonce {
+        if nonce <= stored_nonce {
+            panic_with_error!(&e, &errors::NonFungibleTokenError::NonceAlreadyUsed);
+        }
+        panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidNonce);
+    }
+
+    // Update stored nonce for this public_key
+    e.storage().persistent().set(&nonce_key, &nonce);
+    e.storage()
+        .persistent()
+        .extend_ttl(&nonce_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+    // Remember the uncompressed key so lookups by compressed key work
+    if curve != CurveType::Ed25519 {
+        let uncompressed_key =
+            NFTStorageKey::UncompressedPublicKey(compress_public_key(e, &public_key));
+        if !e.storage().persistent().has(&uncompressed_key) {
+            e.storage().persistent().set(&uncompressed_key, &public_key);
+        }
+        e.storage()
+            .persistent()
+            .extend_ttl(&uncompressed_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    public_key
+}
+
+// hash signed by the chip: message || signer || nonce || action || contract ||
+// network_id [|| expiration_ledger], the expiration is only hashed when not 0
+fn chip_message_hash(
+    e: &Env,
+    signer: &Bytes,
+    message: &Bytes,
+    nonce: u32,
+    action: u8,
+    expiration_ledger: u32,
+) -> Hash<32> {
+    let mut builder: Bytes = Bytes::new(e);
+    builder.append(message);
+    builder.append(signer);
+    builder.append(&nonce.to_xdr(e));
+    builder.push_back(action);
+    builder.append(&e.current_contract_address().to_xdr(e));
+    builder.append(&e.ledger().network_id().into());
+    if expiration_ledger != 0 {
+        builder.append(&expiration_ledger.to_xdr(e));
+    }
+    e.crypto().sha256(&builder)
+}
+
+// uncompressed key recovered from a secp256k1 signature if it matches the 33 or 65 bytes
+// `public_key`, compressed keys are compared against the compressed recovered key
+fn recover_secp256k1(
+    e: &Env,
+    message_hash: &Hash<32>,
+    signature: &BytesN<64>,
+    recovery_id: u32,
+    public_key: &Bytes,
+) -> Option<BytesN<65>> {
+    let recover = |recovery_id: u32| {
+        let recovered = e
+            .crypto()
+            .secp256k1_recover(message_hash, signature, recovery_id);
+        let matches = if public_key.len() == 33 {
+            *public_key == Bytes::from(compress_public_key(e, &recovered))
+        } else {
+            *public_key == Bytes::from(recovered.clone())
+        };
+        matches.then_some(recovered)
+    };
+    // Both parities of R recover a key from a valid signature, ids 2 and 3
+    // need an x coordinate above the curve order which practically never happens
+    // and are rejected as client bugs
+    if recovery_id == RECOVERY_ID_AUTO {
+        recover(0).or_else(|| recover(1))
+    } else {
+        recover(recovery_id)
+    }
+}
+
+// 65 bytes uncompressed (0x04) or 33 bytes compressed (0x02, 0x03) SEC1 encoding
+fn is_sec1_public_key(public_key: &Bytes) -> bool {
+    matches!(
+        (public_key.len(), public_key.get(0)),
+        (65, Some(0x04)) | (33, Some(0x02 | 0x03))
+    )
+}
+
+// whether S is in the lower half of the secp256k1 order, the only form the host
+// accepts so that a signature has a single valid encoding
+fn is_low_s(signature: &BytesN<64>) -> bool {
+    const HALF_ORDER: [u8; 32] = [
+        0x7F, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFF, 0x5D, 0x57, 0x6E, 0x73, 0x57, 0xA4, 0x50, 0x1D, 0xDF, 0xE9, 0x2F, 0x46, 0x68, 0x1B,
+        0x20, 0xA0,
+    ];
+    // Big endian, so comparing the bytes compares the numbers
+    signature.to_array()[32..] <= HALF_ORDER[..]
+}
+
+// r and s of a secp256k1 signature are both in [1, n - 1]
+fn has_valid_scalars(signature: &BytesN<64>) -> bool {
+    const ORDER: [u8; 32] = [
+        0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF,
+        0xFE, 0xBA, 0xAE, 0xDC, 0xE6, 0xAF, 0x48, 0xA0, 0x3B, 0xBF, 0xD2, 0x5E, 0x8C, 0xD0, 0x36,
+        0x41, 0x41,
+    ];
+    let bytes = signature.to_array();
+    let (r, s) = bytes.split_at(32);
+    [r, s]
+        .iter()
+        .all(|scalar| scalar.iter().any(|byte| *byte != 0) && scalar[..] < ORDER[..])
+}
+
+// SEC1 compressed form of an uncompressed public key: parity of y || x
+fn compress_public_key(e: &Env, public_key: &BytesN<65>) -> BytesN<33> {
+    let bytes = public_key.to_array();
+    let mut compressed = [0u8; 33];
+    compressed[0] = 0x02 | (bytes[64] & 1);
+    compressed[1..].copy_from_slice(&bytes[1..33]);
+    BytesN::from_array(e, &compressed)
+}
+
+// 65 bytes form an ed25519 key is stored under
+fn ed25519_storage_key(e: &Env, public_key: &BytesN<32>) -> BytesN<65> {
+    let mut key = [0u8; 65];
+    key[0] = ED25519_KEY_PREFIX;
+    key[1..33].copy_from_slice(&public_key.to_array());
+    BytesN::from_array(e, &key)
+}
+
+// stored public key of a 32 (ed25519), 33 or 65 bytes key, None if a compressed key was never seen
+fn stored_public_key(e: &Env, public_key: Bytes) -> Option<BytesN<65>> {
+    match public_key.len() {
+        65 => BytesN::try_from(public_key).ok(),
+        32 => Some(ed25519_storage_key(
+            e,
+            &BytesN::try_from(public_key).unwrap(),
+        )),
+        33 => {
+            let compressed: BytesN<33> = BytesN::try_from(public_key).unwrap();
+            e.storage()
+                .persistent()
+                .get(&NFTStorageKey::UncompressedPublicKey(compressed))
+        }
+        _ => panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidPublicKey),
+    }
+}
+
+// verify the chip signature made over `minter` and register the chip as a new token
+fn mint_chip(
+    e: &Env,
+    minter: &Address,
+    message: Bytes,
+    signature: BytesN<64>,
+    recovery_id: u32,
+    public_key: Bytes,
+    nonce: u32,
+    expiration_ledger: u32,
+    curve: CurveType,
+) -> u32 {
+    let public_key = verify_chip(
+        e,
+        minter.clone().to_xdr(e),
+        message,
+        signature,
+        recovery_id,
+        public_key,
+        nonce,
+        ACTION_MINT,
+        expiration_ledger,
+        curve,
+    );
+
+    // Only charged once the chip is verified, failed mints are free
+    if let Some(fee) = NFCtoNFT::mint_fee(e)
+        && fee.amount > 0
+    {
+        TokenClient::new(e, &fee.asset).transfer(minter, e.current_contract_address(), &fee.amount);
+    }
+
+    mint_token(e, public_key, curve, None)
+}
+
+// register `public_key` as a new unclaimed token, with the next public id or the
+// given reserved id
+fn mint_token(e: &Env, public_key: BytesN<65>, curve: CurveType, reserved_id: Option<u32>) -> u32 {
+    count_mint(e);
+
+    let registration_required: bool = e
+        .storage()
+        .instance()
+        .get(&DataKey::RegistrationRequired)
+        .unwrap_or(false);
+    if registration_required && !NFCtoNFT::is_chip_registered(e, public_key.clone()) {
+        panic_with_error!(&e, &errors::NonFungibleTokenError::ChipNotRegistered);
+    }
+
+    let public_key_lookup = NFTStorageKey::TokenIdByPublicKey(public_key.clone());
+    if e.storage().persistent().has(&public_key_lookup) {
+        panic_with_error!(&e, &errors::NonFungibleTokenError::TokenAlreadyMinted);
+    }
+    when_not_retired(e, &public_key);
+
+    // `max_tokens` caps the number of tokens, whatever the first id
+    let total_supply = NFCtoNFT::total_supply(e);
+    let max_tokens: u32 = e.storage().instance().get(&DataKey::MaxTokens).unwrap();
+    if total_supply >= max_tokens {
+        panic_with_error!(&e, &errors::NonFungibleTokenError::TokenIDsAreDepleted);
+    }
+
+    // Counted before allocating, older deployments derive the supply from the next id
+    e.storage().instance().set(
+        &DataKey::UnclaimedCount,
+        &(NFCtoNFT::unclaimed_count(e) + 1),
+    );
+    e.storage()
+        .instance()
+        .set(&DataKey::TotalSupply, &(total_supply + 1));
+    let token_id = match reserved_id {
+        Some(token_id) => {
+            if token_id < NFCtoNFT::first_token_id(e) || token_id >= NFCtoNFT::reserved_range(e) {
+                panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidInput);
+            }
+            if NFCtoNFT::is_minted(e, token_id) {
+                panic_with_error!(&e, &errors::NonFungibleTokenError::TokenAlreadyMinted);
+            }
+            token_id
+        }
+        None => {
+            let token_id: u32 = NFCtoNFT::next_token_id(e);
+            e.storage()
+                .instance()
+                .set(&DataKey::NextTokenId, &(token_id + 1));
+            token_id
+        }
+    };
+
+    e.storage().persistent().set(&public_key_lookup, &token_id);
+    e.storage()
+        .persistent()
+        .set(&NFTStorageKey::PublicKey(token_id), &public_key);
+    add_to_batch(e, &public_key, token_id);
+    e.storage()
+        .persistent()
+        .set(&NFTStorageKey::Curve(token_id), &curve);
+    e.storage().persistent().set(
+        &NFTStorageKey::History(token_id),
+        &TokenHistory {
+            minted_ledger: e.ledger().sequence(),
+            minted_timestamp: e.ledger().timestamp(),
+            claimed_ledger: None,
+            claimed_timestamp: None,
+        },
+    );
+
+    let mut keys = Vec::from_array(
+        e,
+        [
+            public_key_lookup,
+            NFTStorageKey::PublicKey(token_id),
+            NFTStorageKey::Curve(token_id),
+            NFTStorageKey::History(token_id),
+        ],
+    );
+
+    let claim_window: u32 = e
+        .storage()
+        .instance()
+        .get(&DataKey::ClaimWindow)
+        .unwrap_or(0);
+    if claim_window > 0 {
+        let deadline_key = NFTStorageKey::ClaimDeadline(token_id);
+        e.storage().persistent().set(
+            &deadline_key,
+            &e.ledger().sequence().saturating_add(claim_window),
+        );
+        keys.push_back(deadline_key);
+    }
+
+    extend_entries(e, keys);
+    e.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+
+    let contract_address = e.current_contract_address();
+    events::Mint {
+        to: contract_address,
+        token_id,
+        public_key,
+    }
+    .publish(e);
+
+    token_id
+}
+
+// index `token_id` under the manufacturing batch of its chip, if any
+fn add_to_batch(e: &Env, public_key: &BytesN<65>, token_id: u32) {
+    let Some(batch) = e
+        .storage()
+        .persistent()
+        .get::<_, String>(&NFTStorageKey::ChipBatch(public_key.clone()))
+    else {
+        return;
+    };
+    let count = batch_token_count(e, &batch);
+    let entry_key = NFTStorageKey::BatchToken(batch.clone(), count);
+    let index_key = NFTStorageKey::BatchTokenIndex(token_id);
+    let count_key = NFTStorageKey::BatchTokenCount(batch);
+    e.storage().persistent().set(&entry_key, &token_id);
+    e.storage().persistent().set(&index_key, &count);
+    e.storage().persistent().set(&count_key, &(count + 1));
+    extend_entries(e, Vec::from_array(e, [entry_key, index_key, count_key]));
+}
+
+fn remove_from_batch(e: &Env, public_key: &BytesN<65>, token_id: u32) {
+    let Some(batch) = e
+        .storage()
+        .persistent()
+        .get::<_, String>(&NFTStorageKey::ChipBatch(public_key.clone()))
+    else {
+        return;
+    };
+    let index_key = NFTStorageKey::BatchTokenIndex(token_id);
+    let Some(index) = e.storage().persistent().get::<_, u32>(&index_key) else {
+        return;
+    };
+    let count = batch_token_count(e, &batch);
+    if index >= count {
+        panic_with_error!(e, errors::NonFungibleTokenError::IndexOutOfBounds);
+    }
+    let last_index = count - 1;
+
+    if index != last_index {
+        let last_token_id: u32 = e
+            .storage()
+            .persistent()
+            .get(&NFTStorageKey::BatchToken(batch.clone(), last_index))
+            .unwrap_or_else(|| {
+                panic_with_error!(e, errors::NonFungibleTokenError::IndexOutOfBounds)
+            });
+        let entry_key = NFTStorageKey::BatchToken(batch.clone(), index);
+        let moved_index_key = NFTStorageKey::BatchTokenIndex(last_token_id);
+        e.storage().persistent().set(&entry_key, &last_token_id);
+        e.storage().persistent().set(&moved_index_key, &index);
+        extend_entries(e, Vec::from_array(e, [entry_key, moved_index_key]));
+    }
+
+    e.storage().persistent().remove(&index_key);
+    e.storage()
+        .persistent()
+        .remove(&NFTStorageKey::BatchToken(batch.clone(), last_index));
+    e.storage()
+        .persistent()
+        .set(&NFTStorageKey::BatchTokenCount(batch), &last_index);
+}
+
+// number of tokens in the index of `batch`
+fn batch_token_count(e: &Env, batch: &String) -> u32 {
+    e.storage()
+        .persistent()
+        .get(&NFTStorageKey::BatchTokenCount(batch.clone()))
+        .unwrap_or(0)
+}
+
+// count a mint in the current ledger against the rate limit
+fn count_mint(e: &Env) {
+    let limit = NFCtoNFT::mint_rate_limit(e);
+    if limit == 0 {
+        return;
+    }
+    let ledger = e.ledger().sequence();
+    let (last_ledger, count): (u32, u32) = e
+        .storage()
+        .instance()
+        .get(&DataKey::MintsInLedger)
+        .unwrap_or((ledger, 0));
+    // The count restarts with each ledger
+    let count = if last_ledger == ledger { count } else { 0 };
+    if count >= limit {
+        panic_with_error!(e, errors::NonFungibleTokenError::RateLimitExceeded);
+    }
+    e.storage()
+        .instance()
+        .set(&DataKey::MintsInLedger, &(ledger, count + 1));
+}
+
+// XDR encoding of a payload signed for `action`, the message of the signed hash
+fn signed_action_message(e: &Env, payload: &SignedAction, action: Symbol) -> Bytes {
+    if payload.action != action {
+        panic_with_error!(e, errors::NonFungibleTokenError::PayloadMismatch);
+    }
+    payload.clone().to_xdr(e)
+}
+
+fn check_signed_token_id(e: &Env, payload: &SignedAction, token_id: u32) {
+    if payload
+        .token_id
+        .is_some_and(|signed_id| signed_id != token_id)
+    {
+        panic_with_error!(e, errors::NonFungibleTokenError::PayloadMismatch);
+    }
+}
+
+fn publish_memo(e: &Env, payload: SignedAction, token_id: u32) {
+    if let Some(memo) = payload.memo {
+        events::Memo { token_id, memo }.publish(e);
+    }
+}
+
+// mint a token for a provisioned secp256k1 key without a chip signature
+fn admin_mint_key(e: &Env, public_key: BytesN<65>) -> u32 {
+    if public_key.get(0) != Some(0x04) {
+        panic_with_error!(&e, &errors::NonFungibleTokenError::InvalidPublicKey);
+    }
+    mint_token(e, public_key, CurveType::Secp256k1, None)
+}
+
+// verify the chip signature made over `claimant` and give them the chip's token
+fn claim_chip(
+    e: &Env,
+    claimant: &Address,
+    message: Bytes,
+    signature: BytesN<64>,
+    recovery_id: u32,
+    public_key: Bytes,
+    nonce: u32,
+    expiration_ledger: u32,
+    curve: CurveType,
+) -> u32 {
+    let public_key = verify_chip(
+        e,
+        claimant.clone().to_xdr(e),
+        message,
+        signature,
+        recovery_id,
+        public_key,
+        nonce,
+        ACTION_CLAIM,
+        expiration_ledger,
+        curve,
+    );
+
+    let token_id = NFCtoNFT::token_id(e, public_key.into());
+    if NFCtoNFT::curve(e, token_id) != curve {
+        panic_with_error!(e, &errors::NonFungibleTokenError::SignerMismatch);
+    }
+
+    if e.storage()
+        .persistent()
+        .has(&NFTStorageKey::Owner(token_id))
+    {
+        panic_with_error!(e, &errors::NonFungibleTokenError::TokenAlreadyClaimed);
+    }
+
+    if let Some(deadline) = NFCtoNFT::claim_deadline(e, token_id)
+        && e.ledger().sequence() > deadline
+    {
+        panic_with_error!(e, &errors::NonFungibleTokenError::ClaimWindowExpired);
+    }
+
+    set_claimed(e, claimant, token_id);
+
+    token_id
+}
+
+// verify the chip signature made over `from` and move its token to `to`
+#[allow(clippy::too_many_arguments)]
+fn transfer_chip(
+    e: &Env,
+    from: Address,
+    to: Address,
+    token_id: u32,
+    message: Bytes,
+    signature: BytesN<64>,
+    recovery_id: u32,
+    public_key: Bytes,
+    nonce: u32,
+    expiration_ledger: u32,
+    curve: CurveType,
+) {
+    let public_key = verify_chip(
+        e,
+        from.clone().to_xdr(e),
+        message,
+        signature,
+        recovery_id,
+        public_key,
+        nonce,
+        ACTION_TRANSFER,
+        expiration_ledger,
+        curve,
+    );
+
+    // Verify the chip public_key corresponds to that specific token_id, backup chips
+    // are secp256k1
+    let token_id_public_key: BytesN<65> = NFCtoNFT::public_key(e, token_id);
+    let is_primary = token_id_public_key == public_key && NFCtoNFT::curve(e, token_id) == curve;
+    if !is_primary
+        && (curve != CurveType::Secp256k1 || NFCtoNFT::backup_key(e, token_id) != Some(public_key))
+    {
+        panic_with_error!(&e, &errors::NonFungibleTokenError::SignerMismatch);
+    }
+
+    let owner = claimed_owner(e, token_id);
+    if owner != from || from == to {
+        panic_with_error!(e, &errors::NonFungibleTokenError::IncorrectOwner);
+    }
+
+    update_owner(e, &from, &to, token_id);
+
+    events::Transfer { from, to, token_id }.publish(e);
+}
+
+// give an unclaimed token its first owner
+fn set_claimed(e: &Env, claimant: &Address, token_id: u32) {
+    let public_key = NFCtoNFT::public_key(e, token_id);
+    e.storage()
+        .persistent()
+        .set(&NFTStorageKey::Owner(token_id), claimant);
+
+    let claimant_balance = NFCtoNFT::balance(e, claimant.clone());
+    add_to_owner_enumeration(e, claimant, token_id);
+    e.storage().persistent().set(
+        &NFTStorageKey::Balance(claimant.clone()),
+        &(claimant_balance + 1),
+    );
+    // Append-only, clawed back tokens stay claimed
+    let claimed_count = NFCtoNFT::claimed_count(e);
+    e.storage()
+        .persistent()
+        .set(&NFTStorageKey::ClaimedToken(claimed_count), &token_id);
+    e.storage()
+        .instance()
+        .set(&DataKey::ClaimedCount, &(claimed_count + 1));
+    e.storage().instance().set(
+        &DataKey::UnclaimedCount,
+        &(NFCtoNFT::unclaimed_count(e) - 1),
+    );
+
+    record_provenance(e, &e.current_contract_address(), claimant, token_id);
+
+    let history_key = NFTStorageKey::History(token_id);
+    if let Some(mut history) = e
+        .storage()
+        .persistent()
+        .get::<NFTStorageKey, TokenHistory>(&history_key)
+    {
+        history.claimed_ledger = Some(e.ledger().sequence());
+        history.claimed_timestamp = Some(e.ledger().timestamp());
+        e.storage().persistent().set(&history_key, &history);
+        e.storage()
+            .persistent()
+            .extend_ttl(&history_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+
+    assign_collectible(e, claimant, &token_id);
+
+    extend_entries(
+        e,
+        Vec::from_array(
+            e,
+            [
+                NFTStorageKey::PublicKey(token_id),
+                NFTStorageKey::TokenIdByPublicKey(public_key.clone()),
+                NFTStorageKey::Owner(token_id),
+                NFTStorageKey::Provenance(token_id),
+                NFTStorageKey::Balance(claimant.clone()),
+                NFTStorageKey::ClaimedToken(claimed_count),
+            ],
+        ),
+    );
+    e.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+
+    events::Claim {
+        claimant: claimant.clone(),
+        token_id,
+        public_key,
+    }
+    .publish(e);
+    if NFCtoNFT::claim_transfer_events(e) {
+        events::Transfer {
+            from: e.current_contract_address(),
+            to: claimant.clone(),
+            token_id,
+        }
+        .publish(e);
+    }
+}
+
+fn royalty_data(e: &Env, receiver: Address, basis_points: u32) -> RoyaltyData {
+    if basis_points > 10_000 {
+        panic_with_error!(e, errors::NonFungibleTokenError::InvalidRoyalty);
+    }
+
+    RoyaltyData {
+        receiver,
+        basis_points,
+    }
+}
+
+// the admin and registered minters can mint
+fn require_minter(e: &Env, minter: &Address) {
+    if *minter != NFCtoNFT::get_admin(e) && !NFCtoNFT::is_minter(e, minter.clone()) {
+        panic_with_error!(e, errors::NonFungibleTokenError::NotMinter);
+    }
+    minter.require_auth();
+}
+
+fn when_not_paused(e: &Env) {
+    if NFCtoNFT::is_paused(e) {
+        panic_with_error!(e, errors::NonFungibleTokenError::ContractPaused);
+    }
+}
+
+// remember that `public_key` was unbound from `token_id`
+fn retire_chip(e: &Env, public_key: &BytesN<65>, token_id: u32) {
+    let key = NFTStorageKey::RetiredChip(public_key.clone());
+    e.storage().persistent().set(&key, &token_id);
+    e.storage()
+        .persistent()
+        .extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+fn when_not_retired(e: &Env, public_key: &BytesN<65>) {
+    if e.storage()
+        .persistent()
+        .has(&NFTStorageKey::RetiredChip(public_key.clone()))
+    {
+        panic_with_error!(e, errors::NonFungibleTokenError::ChipRetired);
+    }
+}
+
+fn when_not_soulbound(e: &Env) {
+    if NFCtoNFT::is_soulbound(e) {
+        panic_with_error!(e, errors::NonFungibleTokenError::SoulboundCollection);
+    }
+}
+
+fn when_not_frozen(e: &Env, token_id: u32) {
+    if NFCtoNFT::is_frozen(e, token_id) {
+        panic_with_error!(e, errors::NonFungibleTokenError::TokenFrozen);
+    }
+}
+
+fn when_not_locked(e: &Env, token_id: u32) {
+    if NFCtoNFT::lock_info(e, token_id).is_some() {
+        panic_with_error!(e, errors::NonFungibleTokenError::TokenLocked);
+    }
+}
+
+// destructive operations go through proposals once several admins must agree
+fn when_single_admin(e: &Env) {
+    if NFCtoNFT::admin_threshold(e) > 1 {
+        panic_with_error!(e, errors::NonFungibleTokenError::ProposalRequired);
+    }
+}
+
+fn when_in_admins(e: &Env, account: &Address) {
+    if !NFCtoNFT::admins(e).contains(account) {
+        panic_with_error!(e, errors::NonFungibleTokenError::NotAuthorized);
+    }
+}
+
+// store the proposal, or execute and drop it once it has enough approvals
+fn approve_or_execute(e: &Env, proposal_id: u32, proposal: Proposal, approver: Address) {
+    let key = NFTStorageKey::Proposal(proposal_id);
+    if proposal.approvals.len() < NFCtoNFT::admin_threshold(e) {
+        e.storage().persistent().set(&key, &proposal);
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, PROPOSAL_LIFETIME, PROPOSAL_LIFETIME);
+        events::ActionApproved {
+            proposal_id,
+            approver,
+        }
+        .publish(e);
+        return;
+    }
+
+    e.storage().persistent().remove(&key);
+    match proposal.action {
+        AdminAction::Upgrade(wasm_hash) => upgrade_wasm(e, approver, wasm_hash),
+        AdminAction::Clawback(token_id, reason) => clawback_token(e, token_id, reason),
+        AdminAction::SetBaseUri(uri) => set_base_uri(e, uri),
+        AdminAction::SetAdmins(admins, threshold) => set_admins(e, admins, threshold),
+        AdminAction::ClawbackRelease(token_id, to) => release_token(e, token_id, to),
+        AdminAction::TransferAdmin(new_admin) => start_admin_transfer(e, new_admin),
+        AdminAction::SetRegistry(registry) => e
+            .storage()
+            .instance()
+            .set(&DataKey::CollectionContract, &registry),
+    }
+    events::ActionExecuted { proposal_id }.publish(e);
+}
+
+fn set_admins(e: &Env, admins: Vec<Address>, threshold: u32) {
+    if threshold == 0 || threshold > admins.len() {
+        panic_with_error!(e, errors::NonFungibleTokenError::InvalidInput);
+    }
+
+    e.storage().instance().set(&DataKey::Admins, &admins);
+    e.storage()
+        .instance()
+        .set(&DataKey::AdminThreshold, &threshold);
+}
+
+fn upgrade_wasm(e: &Env, admin: Address, wasm_hash: BytesN<32>) {
+    e.deployer().update_current_contract_wasm(wasm_hash.clone());
+
+    events::Upgrade { admin, wasm_hash }.publish(e);
+}
+
+// send `token_id` to the admin and quarantine it
+fn clawback_token(e: &Env, token_id: u32, reason: u32) {
+    let (_, from) = token_owner(e, token_id);
+    let to = NFCtoNFT::get_admin(e);
+
+    // Remember who the token was taken from, until it moves again, a second
+    // clawback keeps the owner of the first
+    let quarantine_key = NFTStorageKey::Quarantine(token_id);
+    let previous_owner: Address = e
+        .storage()
+        .persistent()
+        .get(&quarantine_key)
+        .unwrap_or_else(|| from.clone());
+
+    update_owner(e, &from, &to, token_id);
+
+    e.storage()
+        .persistent()
+        .set(&quarantine_key, &previous_owner);
+    e.storage()
+        .persistent()
+        .extend_ttl(&quarantine_key, TTL_THRESHOLD, TTL_EXTEND_TO);
+
+    events::Clawback {
+        from: from.clone(),
+        token_id,
+        reason,
+    }
+    .publish(e);
+    if NFCtoNFT::claim_transfer_events(e) {
+        events::Transfer { from, to, token_id }.publish(e);
+    }
+}
+
+// send a quarantined `token_id` to `to`, while the admin still holds it
+fn release_token(e: &Env, token_id: u32, to: Address) {
+    let (_, from) = token_owner(e, token_id);
+    if from != NFCtoNFT::get_admin(e)
+        || !e
+            .storage()
+            .persistent()
+            .has(&NFTStorageKey::Quarantine(token_id))
+    {
+        panic_with_error!(e, errors::NonFungibleTokenError::TokenNotQuarantined);
+    }
+
+    update_owner(e, &from, &to, token_id);
+
+    events::ClawbackRelease { to, token_id }.publish(e);
+}
+
+fn start_admin_transfer(e: &Env, new_admin: Address) {
+    e.storage()
+        .instance()
+        .set(&DataKey::PendingAdmin, &new_admin);
+
+    events::AdminTransferStarted {
+        admin: NFCtoNFT::get_admin(e),
+        new_admin,
+    }
+    .publish(e);
+}
+
+fn set_base_uri(e: &Env, uri: String) {
+    if e.storage()
+        .instance()
+        .get(&DataKey::UriFrozen)
+        .unwrap_or(false)
+    {
+        panic_with_error!(e, errors::NonFungibleTokenError::MetadataFrozen);
+    }
+
+    let old_uri = NFCtoNFT::base_uri(e);
+    e.storage().instance().set(&DataKey::Uri, &uri);
+
+    events::BaseUriUpdated {
+        old_uri,
+        new_uri: uri,
+    }
+    .publish(e);
+}
+
+fn when_enabled(e: &Env, flag: &DataKey) {
+    if !e.storage().instance().get(flag).unwrap_or(true) {
+        panic_with_error!(e, errors::NonFungibleTokenError::OperationDisabled);
+    }
+}
+
+// read a value set in the constructor
+fn get_instance_metadata<V: TryFromVal<Env, Val>>(e: &Env, key: &DataKey) -> V {
+    e.storage()
+        .instance()
+        .get(key)
+        .unwrap_or_else(|| panic_with_error!(e, errors::NonFungibleTokenError::UnsetMetadata))
+}
+
+// the chip key and owner of `token_id`, each read once
+fn token_owner(e: &Env, token_id: u32) -> (BytesN<65>, Address) {
+    // Verify the token exists (this will panic if it doesn't)
+    let public_key = NFCtoNFT::public_key(e, token_id);
+    (public_key, claimed_owner(e, token_id))
+}
+
+// the owner of an existing token
+fn claimed_owner(e: &Env, token_id: u32) -> Address {
+    e.storage()
+        .persistent()
+        .get(&NFTStorageKey::Owner(token_id))
+        .unwrap_or_else(|| panic_with_error!(e, errors::NonFungibleTokenError::TokenNotClaimed))
+}
+
+// move ownership and balances, dropping any pending approval, the caller has
+// checked that the token exists
+fn update_owner(e: &Env, from: &Address, to: &Address, token_id: u32) {
+    e.storage()
+        .persistent()
+        .set(&NFTStorageKey::Owner(token_id), to);
+
+    let from_balance = NFCtoNFT::balance(e, from.clone());
+    remove_from_owner_enumeration(e, from, token_id);
+    e.storage()
+        .persistent()
+        .set(&NFTStorageKey::Balance(from.clone()), &(from_balance - 1));
+    let to_balance = NFCtoNFT::balance(e, to.clone());
+    add_to_owner_enumeration(e, to, token_id);
+    e.storage()
+        .persistent()
+        .set(&NFTStorageKey::Balance(to.clone()), &(to_balance + 1));
+
+    e.storage()
+        .persistent()
+        .remove(&NFTStorageKey::Approval(token_id));
+    e.storage()
+        .persistent()
+        .remove(&NFTStorageKey::User(token_id));
+    e.storage()
+        .persistent()
+        .remove(&NFTStorageKey::Quarantine(token_id));
+    // only a clawback moves a locked token, the lock does not follow it
+    e.storage()
+        .persistent()
+        .remove(&NFTStorageKey::Lock(token_id));
+
+    record_provenance(e, from, to, token_id);
+
+    assign_collectible(e, to, &token_id);
+
+    // The owner lists extend their own entries
+    extend_entries(
+        e,
+        Vec::from_array(
+            e,
+            [
+                NFTStorageKey::PublicKey(token_id),
+                NFTStorageKey::Owner(token_id),
+                NFTStorageKey::Provenance(token_id),
+                NFTStorageKey::Balance(from.clone()),
+                NFTStorageKey::Balance(to.clone()),
+            ],
+        ),
+    );
+    e.storage()
+        .instance()
+        .extend_ttl(TTL_THRESHOLD, TTL_EXTEND_TO);
+}
+
+// accounts always accept tokens, contracts only if their `on_nft_received` returns true
+fn accepts_token(e: &Env, operator: &Address, from: &Address, to: &Address, token_id: u32) -> bool {
+    match to.executable() {
+        Some(Executable::Wasm(_)) => {}
+        Some(Executable::StellarAsset) => return false,
+        Some(Executable::Account) | None => return true,
+    }
+    let result = e.try_invoke_contract::<bool, soroban_sdk::Error>(
+        to,
+        &Symbol::new(e, "on_nft_received"),
+        (operator.clone(), from.clone(), token_id).into_val(e),
+    );
+    matches!(result, Ok(Ok(true)))
+}
+
+// append an ownership change to the provenance of `token_id`, evicting the oldest
+// entries beyond the limit
+fn record_provenance(e: &Env, from: &Address, to: &Address, token_id: u32) {
+    let limit: u32 = e
+        .storage()
+        .instance()
+        .get(&DataKey::ProvenanceLimit)
+        .unwrap_or(DEFAULT_PROVENANCE_LIMIT);
+    let key = NFTStorageKey::Provenance(token_id);
+    let mut provenance: Vec<ProvenanceEntry> =
+        e.storage().persistent().get(&key).unwrap_or(Vec::new(e));
+    while provenance.len() >= limit {
+        provenance.pop_front();
+    }
+    provenance.push_back(ProvenanceEntry {
+        from: from.clone(),
+        to: to.clone(),
+        ledger: e.ledger().sequence(),
+    });
+    e.storage().persistent().set(&key, &provenance);
+}
+
+// extend `keys`, which must all exist
+fn extend_entries(e: &Env, keys: Vec<NFTStorageKey>) {
+    for key in keys {
+        e.storage()
+            .persistent()
+            .extend_ttl(&key, TTL_THRESHOLD, TTL_EXTEND_TO);
+    }
+}
+
+// extend every entry of `token_id`, its chip and its owner whose TTL is below
+// `threshold`, transfers and claims only extend the entries they touch
+fn extend_token_entries(
+    e: &Env,
+    token_id: u32,
+    public_key: &BytesN<65>,
+    threshold: u32,
+    extend_to: u32,
+) {
+    let mut keys = Vec::from_array(
+        e,
+        [
+            NFTStorageKey::PublicKey(token_id),
+            NFTStorageKey::Curve(token_id),
+            NFTStorageKey::History(token_id),
+            NFTStorageKey::Provenance(token_id),
+            NFTStorageKey::Owner(token_id),
+            NFTStorageKey::OwnerTokenIndex(token_id),
+            NFTStorageKey::Approval(token_id),
+            NFTStorageKey::Metadata(token_id),
+            NFTStorageKey::Quarantine(token_id),
+            NFTStorageKey::ClaimDeadline(token_id),
+            NFTStorageKey::Frozen(token_id),
+            NFTStorageKey::Royalty(token_id),
+            NFTStorageKey::ChipUrl(token_id),
+            NFTStorageKey::Lock(token_id),
+            NFTStorageKey::User(token_id),
+            NFTStorageKey::ChipLost(token_id),
+            NFTStorageKey::MetadataFrozen(token_id),
+            NFTStorageKey::Referrer(token_id),
+            NFTStorageKey::BackupKey(token_id),
+            NFTStorageKey::TokenIdByPublicKey(public_key.clone()),
+            NFTStorageKey::ChipNonceByPublicKey(public_key.clone()),
+            NFTStorageKey::RegisteredChip(public_key.clone()),
+            NFTStorageKey::BlockedChip(public_key.clone()),
+            NFTStorageKey::ChipBatch(public_key.clone()),
+            NFTStorageKey::BatchTokenIndex(token_id),
+        ],
+    );
+    let batch: Option<String> = e
+        .storage()
+        .persistent()
+        .get(&NFTStorageKey::ChipBatch(public_key.clone()));
+    let batch_index: Option<u32> = e
+        .storage()
+        .persistent()
+        .get(&NFTStorageKey::BatchTokenIndex(token_id));
+    if let (Some(batch), Some(index)) = (batch, batch_index) {
+        keys.push_back(NFTStorageKey::BatchToken(batch.clone(), index));
+        keys.push_back(NFTStorageKey::BatchTokenCount(batch));
+    }
+    if public_key.get(0) != Some(ED25519_KEY_PREFIX) {
+        keys.push_back(NFTStorageKey::UncompressedPublicKey(compress_public_key(
+            e, public_key,
+        )));
+    }
+    if let Some(backup_key) = NFCtoNFT::backup_key(e, token_id) {
+        keys.push_back(NFTStorageKey::TokenIdByPublicKey(backup_key.clone()));
+        keys.push_back(NFTStorageKey::ChipNonceByPublicKey(backup_key.clone()));
+        keys.push_back(NFTStorageKey::UncompressedPublicKey(compress_public_key(
+            e,
+            &backup_key,
+        )));
+    }
+    let owner: Option<Address> = e
+        .storage()
+        .persistent()
+        .get(&NFTStorageKey::Owner(token_id));
+    if let Some(owner) = owner {
+        let index: Option<u32> = e
+            .storage()
+            .persistent()
+            .get(&NFTStorageKey::OwnerTokenIndex(token_id));
+        if let Some(index) = index {
+            keys.push_back(NFTStorageKey::OwnerToken(owner.clone(), index));
+        }
+        keys.push_back(NFTStorageKey::OwnerTokenCount(owner.clone()));
+        keys.push_back(NFTStorageKey::Balance(owner));
+    }
+
+    for key in keys {
+        if e.storage().persistent().has(&key) {
+            e.storage()
+                .persistent()
+                .extend_ttl(&key, threshold, extend_to);
+        }
+    }
+}
+
+// number of tokens in the owner's token list, tokens claimed before the list
+// existed are only in it once moved or enumerated with `enumerate_tokens`
+fn owner_token_count(e: &Env, owner: &Address) -> u32 {
+    e.storage()
+        .persistent()
+        .get(&NFTStorageKey::OwnerTokenCount(owner.clone()))
+        .unwrap_or(0)
+}
+
+// append `token_id` to the owner's token list
+fn add_to_owner_enumeration(e: &Env, owner: &Address, token_id: u32) {
+    let count = owner_token_count(e, owner);
+    let entry_key = NFTStorageKey::OwnerToken(owner.clone(), count);
+    let index_key = NFTStorageKey::OwnerTokenIndex(token_id);
+    let count_key = NFTStorageKey::OwnerTokenCount(owner.clone());
+    e.storage().persistent().set(&entry_key, &token_id);
+    e.storage().persistent().set(&index_key, &count);
+    e.storage().persistent().set(&count_key, &(count + 1));
+    extend_entries(e, Vec::from_array(e, [entry_key, index_key, count_key]));
+}
+
+// swap-remove `token_id` from the owner's token list, always followed by
+// `add_to_owner_enumeration` for the new owner
+fn remove_from_owner_enumeration(e: &Env, owner: &Address, token_id: u32) {
+    // Not in the list, e.g. claimed before it existed
+    let Some(index) = e
+        .storage()
+        .persistent()
+        .get::<_, u32>(&NFTStorageKey::OwnerTokenIndex(token_id))
+    else {
+        return;
+    };
+    let count = owner_token_count(e, owner);
+    if index >= count {
+        panic_with_error!(e, errors::NonFungibleTokenError::IndexOutOfBounds);
+    }
+    let last_index = count - 1;
+
+    if index != last_index {
+        let last_token_id: u32 = e
+            .storage()
+            .persistent()
+            .get(&NFTStorageKey::OwnerToken(owner.clone(), last_index))
+            .unwrap_or_else(|| {
+                panic_with_error!(e, errors::NonFungibleTokenError::IndexOutOfBounds)
+            });
+        let entry_key = NFTStorageKey::OwnerToken(owner.clone(), index);
+        let index_key = NFTStorageKey::OwnerTokenIndex(last_token_id);
+        e.storage().persistent().set(&entry_key, &last_token_id);
+        e.storage().persistent().set(&index_key, &index);
+        extend_entries(e, Vec::from_array(e, [entry_key, index_key]));
+    }
+
+    // The index of `token_id` itself is overwritten by `add_to_owner_enumeration`,
+    // removing it first would recreate the entry with a shorter TTL
+    e.storage()
+        .persistent()
+        .remove(&NFTStorageKey::OwnerToken(owner.clone(), last_index));
+    let count_key = NFTStorageKey::OwnerTokenCount(owner.clone());
+    e.storage().persistent().set(&count_key, &last_index);
+    e.storage()
+        .persistent()
+        .extend_ttl(&count_key, TTL_THRESHOLD, TTL_EXTEND_TO);
 }
 
 // update collection
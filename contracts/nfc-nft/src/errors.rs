@@ -1,3 +1,6 @@
+//! Contract errors, clients receive them as `Error(Contract, #code)` with the
+//! codes below.
+
 use soroban_sdk::contracterror;
 
 #[contracterror]
@@ -11,12 +14,93 @@ pub enum NonFungibleTokenError {
     /// Indicates an error related to the ownership over a particular token.
     /// Used in transfers.
     IncorrectOwner = 202,
-    /// Indicates all possible `token_id`s are already in use.
+    /// Indicates `max_tokens` tokens are already minted.
     TokenIDsAreDepleted = 203,
+    /// Indicates a failure with the `spender`'s approval. Used in transfers.
+    InsufficientApproval = 204,
+    /// Indicates an invalid value for `live_until_ledger` when setting an
+    /// approval.
+    InvalidLiveUntilLedger = 205,
+    /// Indicates there is no pending admin transfer to accept.
+    NoPendingAdmin = 206,
+    /// Indicates the contract configuration was never set.
+    UnsetMetadata = 207,
+    /// Indicates the contract is paused.
+    ContractPaused = 208,
+    /// Indicates the operation was disabled by the admin.
+    OperationDisabled = 209,
     /// Indicates the token was already minted.
     TokenAlreadyMinted = 210,
     /// Indicates the token was already claimed.
     TokenAlreadyClaimed = 211,
     /// Indicates the token exists but has not been claimed yet
     TokenNotClaimed = 212,
+    /// Indicates an index is out of bounds of an enumeration.
+    IndexOutOfBounds = 213,
+    /// Indicates the metadata can no longer be changed.
+    MetadataFrozen = 214,
+    /// Indicates an invalid value for `max_tokens`.
+    InvalidMaxTokens = 215,
+    /// Indicates the claim window of the token has elapsed.
+    ClaimWindowExpired = 216,
+    /// Indicates the claim window of the token is still open.
+    ClaimWindowOpen = 217,
+    /// Indicates the token is not quarantined by a clawback.
+    TokenNotQuarantined = 218,
+    /// Indicates the token was frozen by the admin.
+    TokenFrozen = 219,
+    /// Indicates tokens of the collection cannot be transferred.
+    SoulboundCollection = 220,
+    /// Indicates an invalid royalty above 100%.
+    InvalidRoyalty = 221,
+    /// Indicates the account is not allowed to mint.
+    NotMinter = 222,
+    /// Indicates the chip was blocked by the admin.
+    ChipBlocked = 223,
+    /// Indicates the chip was not registered by the admin.
+    ChipNotRegistered = 224,
+    /// Indicates a chip nonce skipping ahead of the next one, or a nonce reset
+    /// going backwards without `force`.
+    InvalidNonce = 225,
+    /// Indicates the chip signature expired.
+    SignatureExpired = 226,
+    /// Indicates a public key that is neither 33 nor 65 bytes long.
+    InvalidPublicKey = 227,
+    /// Indicates the caller may not act on the token, e.g. approvals by a non-owner.
+    NotAuthorized = 228,
+    /// Indicates the chip nonce was already used by a previous signature.
+    NonceAlreadyUsed = 229,
+    /// Indicates a recovery id other than 0, 1 or `RECOVERY_ID_AUTO`.
+    InvalidRecoveryId = 230,
+    /// Indicates the signing chip is not the chip of the token.
+    SignerMismatch = 231,
+    /// Indicates a recipient contract did not acknowledge the token.
+    UnsafeRecipient = 232,
+    /// Indicates an action that does not fit in a byte.
+    InvalidAction = 233,
+    /// Indicates a secp256k1 signature with a high S value.
+    MalleableSignature = 234,
+    /// Indicates a negative mint fee.
+    InvalidFee = 235,
+    /// Indicates a signed payload whose action, token or recipient does not match the call.
+    PayloadMismatch = 236,
+    /// Indicates an argument outside of its allowed range, e.g. a too long URL.
+    InvalidInput = 237,
+    /// Indicates the maximum number of mints in this ledger was reached.
+    RateLimitExceeded = 238,
+    /// Indicates the operation is only possible before the first mint.
+    MintingStarted = 239,
+    /// Indicates there is no pending proposal with this id.
+    NonExistentProposal = 240,
+    /// Indicates the approval deadline of the proposal has passed.
+    ProposalExpired = 241,
+    /// Indicates the admin already approved the proposal.
+    AlreadyApproved = 242,
+    /// Indicates the operation needs several admins, see `propose_action`.
+    ProposalRequired = 243,
+    /// Indicates the token is locked, see `lock`.
+    TokenLocked = 244,
+    /// Indicates the chip was unbound with `rotate_chip` or `remove_backup_key` and
+    /// cannot get a token again.
+    ChipRetired = 245,
 }
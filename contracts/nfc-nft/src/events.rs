@@ -1,4 +1,5 @@
-use soroban_sdk::{Address, contractevent};
+use crate::AdminAction;
+use soroban_sdk::{Address, BytesN, String, contractevent};
 
 #[contractevent]
 #[derive(Clone, Debug, Eq, PartialEq)]
@@ -16,6 +17,7 @@ pub struct Mint {
     #[topic]
     pub to: Address,
     pub token_id: u32,
+    pub public_key: BytesN<65>,
 }
 
 #[contractevent]
@@ -24,4 +26,244 @@ pub struct Claim {
     #[topic]
     pub claimant: Address,
     pub token_id: u32,
+    pub public_key: BytesN<65>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Clawback {
+    #[topic]
+    pub from: Address,
+    pub token_id: u32,
+    pub reason: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ClawbackRelease {
+    #[topic]
+    pub to: Address,
+    pub token_id: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BackupKeyAdded {
+    #[topic]
+    pub token_id: u32,
+    pub backup_key: BytesN<65>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BackupKeyRemoved {
+    #[topic]
+    pub token_id: u32,
+    pub backup_key: BytesN<65>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Referral {
+    #[topic]
+    pub referrer: Address,
+    #[topic]
+    pub claimant: Address,
+    pub token_id: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MetadataFrozen {
+    pub token_id: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BatchAssigned {
+    #[topic]
+    pub public_key: BytesN<65>,
+    pub batch: String,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ForcedTransfer {
+    #[topic]
+    pub from: Address,
+    #[topic]
+    pub to: Address,
+    pub token_id: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UserSet {
+    #[topic]
+    pub token_id: u32,
+    #[topic]
+    pub user: Address,
+    pub expires_ledger: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Lock {
+    #[topic]
+    pub owner: Address,
+    pub token_id: u32,
+    pub until_ledger: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Unlock {
+    #[topic]
+    pub owner: Address,
+    pub token_id: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActionProposed {
+    #[topic]
+    pub proposal_id: u32,
+    #[topic]
+    pub proposer: Address,
+    pub action: AdminAction,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActionApproved {
+    #[topic]
+    pub proposal_id: u32,
+    #[topic]
+    pub approver: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ActionExecuted {
+    #[topic]
+    pub proposal_id: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChipUrlUpdated {
+    #[topic]
+    pub token_id: u32,
+    pub url: String,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Memo {
+    #[topic]
+    pub token_id: u32,
+    pub memo: String,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct FeesWithdrawn {
+    #[topic]
+    pub to: Address,
+    pub asset: Address,
+    pub amount: i128,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ChipRotated {
+    #[topic]
+    pub token_id: u32,
+    pub old_public_key: BytesN<65>,
+    pub new_public_key: BytesN<65>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Approve {
+    #[topic]
+    pub approver: Address,
+    #[topic]
+    pub token_id: u32,
+    pub approved: Address,
+    pub live_until_ledger: u32,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Upgrade {
+    #[topic]
+    pub admin: Address,
+    pub wasm_hash: BytesN<32>,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransferStarted {
+    #[topic]
+    pub admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct AdminTransferred {
+    #[topic]
+    pub previous_admin: Address,
+    pub new_admin: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Paused {
+    #[topic]
+    pub admin: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct Unpaused {
+    #[topic]
+    pub admin: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinterSet {
+    #[topic]
+    pub minter: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct MinterRemoved {
+    #[topic]
+    pub minter: Address,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct BaseUriUpdated {
+    pub old_uri: String,
+    pub new_uri: String,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct ContractUriUpdated {
+    pub uri: String,
+}
+
+#[contractevent]
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct NonceReset {
+    #[topic]
+    pub public_key: BytesN<65>,
+    pub old_nonce: u32,
+    pub new_nonce: u32,
 }
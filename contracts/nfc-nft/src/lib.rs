@@ -1,17 +1,27 @@
 #![no_std]
 
-use soroban_sdk::{Address, Bytes, BytesN, Env, String, contract, contractmeta};
+use soroban_sdk::{Address, Bytes, BytesN, Env, Map, String, Symbol, Vec, contract, contractmeta};
 
 contractmeta!(key = "Description", val = "ChimpDAO NFC-NFT");
 
+#[allow(clippy::too_many_arguments)]
 mod collection_contract {
     soroban_sdk::contractimport!(file = "../collection.wasm");
 }
 
+#[allow(clippy::too_many_arguments)]
 mod contract;
 
 mod errors;
 mod events;
+
+pub use contract::{
+    ACTION_BACKUP, ACTION_CLAIM, ACTION_MINT, ACTION_ROTATE, ACTION_TRANSFER, ALL_TOKENS,
+    AdminAction, CONTRACT_VERSION, ChipKey, ChipResolution, ClaimRequest, CurveType,
+    DEFAULT_PROVENANCE_LIMIT, MAX_BATCH_LEN, MAX_CHIP_URL_LEN, MAX_OWNERS_PAGE, MAX_TOKENS_PAGE,
+    MintFee, MintRequest, PROPOSAL_LIFETIME, Proposal, ProvenanceEntry, RECOVERY_ID_AUTO,
+    STORAGE_VERSION, SUPPORTED_INTERFACES, SignedAction, TokenInfo, TokenMetadata, TokenStatus,
+};
 #[cfg(test)]
 mod test;
 
@@ -19,6 +29,7 @@ mod test;
 pub struct NFCtoNFT;
 
 pub trait NFCtoNFTTrait {
+    #[allow(clippy::too_many_arguments)]
     fn __constructor(
         e: &Env,
         admin: Address,
@@ -27,272 +38,2960 @@ pub trait NFCtoNFTTrait {
         symbol: String,
         uri: String,
         max_tokens: u32,
+        soulbound: bool,
+        provenance_limit: u32,
+        first_token_id: u32,
     );
 
+    /// Upgrade the contract to a new WASM build.
+    ///
+    /// Call `migrate` afterwards to bring the storage up to the new layout.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `wasm_hash` - Hash of the uploaded WASM.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If several admins must approve ([`errors::NonFungibleTokenError::ProposalRequired`]).
+    ///
+    /// # Events
+    ///
+    /// * topics - `["upgrade", admin: Address]`
+    /// * data - `[wasm_hash: BytesN<32>]`
     fn upgrade(e: &Env, wasm_hash: BytesN<32>);
 
-    /// Mint NFT using NFC chip signature.
+    /// Require `threshold` of `admins` to approve the actions of
+    /// [`AdminAction`], which then go through `propose_action`: upgrades,
+    /// clawbacks and their release, base URI changes, changes of the admins,
+    /// admin transfers and registry changes. `admin_transfer` of a
+    /// quarantined token is refused too, the token leaves through a release.
     ///
-    /// This function verifies that the provided signature was created by an Infineon
-    /// NFC chip by recovering the chip's public key. The public key is converted to
-    /// a SEP-50 compliant u32 token_id.
+    /// Other admin functions stay with the admin. Without a call, the admin
+    /// is the only member with a threshold of 1.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `to` - Account of the token's owner.
-    /// * `message` - The message that was signed (without signer and nonce).
-    /// * `signature` - 64-byte ECDSA signature from NFC chip.
-    /// * `recovery_id` - Recovery ID (0-3) for signature recovery.
-    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
-    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `admins` - Accounts allowed to propose and approve actions.
+    /// * `threshold` - Number of approvals executing an action.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If a threshold above 1 is already set ([`errors::NonFungibleTokenError::ProposalRequired`]).
+    /// * If `threshold` is 0 or above the number of admins
+    ///   ([`errors::NonFungibleTokenError::InvalidInput`]).
+    fn set_admins(e: &Env, admins: Vec<Address>, threshold: u32);
+
+    /// Returns the accounts allowed to propose and approve actions.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn admins(e: &Env) -> Vec<Address>;
+
+    /// Returns the number of approvals executing an action.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn admin_threshold(e: &Env) -> u32;
+
+    /// Propose an admin action, approved by the proposer.
+    ///
+    /// The action executes as soon as it has `admin_threshold` approvals,
+    /// right away with a threshold of 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `proposer` - One of `admins`.
+    /// * `action` - The action to execute, see [`AdminAction`].
     ///
     /// # Returns
     ///
-    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    /// The proposal id.
     ///
     /// # Panics
     ///
-    /// * If the caller is not the admin.
-    /// * If the signature is invalid.
-    /// * If the token was already minted.
-    /// * If there are no more tokens to be minted.
+    /// * If `proposer` is not an admin ([`errors::NonFungibleTokenError::NotAuthorized`]).
+    /// * If the action executes and fails, see the functions of [`AdminAction`].
     ///
     /// # Events
     ///
-    /// * topics - `["mint", to: Address]`
-    /// * data - `[token_id: u32]`
-    fn mint(
-        e: &Env,
-        message: Bytes,
-        signature: BytesN<64>,
-        recovery_id: u32,
-        public_key: BytesN<65>,
-        nonce: u32,
-    ) -> u32;
+    /// * topics - `["action_proposed", proposal_id: u32, proposer: Address]`
+    /// * data - `[action: AdminAction]`
+    ///
+    /// While approvals are missing:
+    ///
+    /// * topics - `["action_approved", proposal_id: u32, approver: Address]`
+    /// * data - `[]`
+    ///
+    /// Once executed, the events of the action and:
+    ///
+    /// * topics - `["action_executed", proposal_id: u32]`
+    /// * data - `[]`
+    fn propose_action(e: &Env, proposer: Address, action: AdminAction) -> u32;
 
-    /// Claim NFT using NFC chip signature.
+    /// Approve a pending proposal, executing it if the threshold is reached.
     ///
-    /// This function verifies that the provided signature was created by an Infineon
-    /// NFC chip by recovering the chip's public key. The public key is converted to
-    /// a SEP-50 compliant u32 token_id.
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `approver` - One of `admins`.
+    /// * `proposal_id` - Id returned by `propose_action`.
+    ///
+    /// # Panics
+    ///
+    /// * If `approver` is not an admin ([`errors::NonFungibleTokenError::NotAuthorized`]).
+    /// * If the proposal does not exist or was executed
+    ///   ([`errors::NonFungibleTokenError::NonExistentProposal`]).
+    /// * If the proposal is older than `PROPOSAL_LIFETIME` ledgers
+    ///   ([`errors::NonFungibleTokenError::ProposalExpired`]).
+    /// * If `approver` already approved ([`errors::NonFungibleTokenError::AlreadyApproved`]).
+    /// * If the action executes and fails.
+    ///
+    /// # Events
+    ///
+    /// See `propose_action`.
+    fn approve_action(e: &Env, approver: Address, proposal_id: u32);
+
+    /// Returns a pending proposal.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `claimant` - Account of the claimant.
-    /// * `message` - The message that was signed (without signer and nonce).
-    /// * `signature` - 64-byte ECDSA signature from NFC chip.
-    /// * `recovery_id` - Recovery ID (0-3) for signature recovery.
-    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
-    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `proposal_id` - Id returned by `propose_action`.
     ///
-    /// # Returns
+    /// # Panics
     ///
-    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    /// * If the proposal does not exist or was executed
+    ///   ([`errors::NonFungibleTokenError::NonExistentProposal`]).
+    fn proposal(e: &Env, proposal_id: u32) -> Proposal;
+
+    /// Migrate the storage to the layout of the current code.
+    ///
+    /// Sets the storage version to `STORAGE_VERSION`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
     ///
     /// # Panics
     ///
-    /// * If the claimant is not the signer.
-    /// * If the signature is invalid.
-    /// * If the token was not yet minted.
-    /// * If the token was already claimed.
+    /// * If the caller is not the admin.
+    fn migrate(e: &Env);
+
+    /// Returns the version of the contract code, `CONTRACT_VERSION`.
     ///
-    /// # Events
+    /// # Arguments
     ///
-    /// * topics - `["claim", claimant: Address]`
-    /// * data - `[token_id: u32]`
-    fn claim(
-        e: &Env,
-        claimant: Address,
-        message: Bytes,
-        signature: BytesN<64>,
-        recovery_id: u32,
-        public_key: BytesN<65>,
-        nonce: u32,
-    ) -> u32;
+    /// * `e` - The environment object.
+    fn version(e: &Env) -> u32;
 
-    /// Transfers `token_id` token from `from` to `to` using NFC chip signature.
+    /// Returns whether the contract exposes `interface`, one of
+    /// [`SUPPORTED_INTERFACES`]:
     ///
-    /// This function verifies that the provided signature was created by a
-    /// NFC chip whose public key corresponds to the token being transferred.
+    /// * `approvals` - `approve`, `get_approved` and `transfer_from`.
+    /// * `chip_auth` - Mints, claims and transfers proven by chip signatures.
+    /// * `enumerable` - `tokens`, `tokens_of_owner` and `total_supply`.
+    /// * `metadata` - `name`, `symbol` and `token_uri`.
+    /// * `royalties` - `royalty_info`.
+    /// * `upgradeable` - `upgrade` by the admin.
     ///
-    /// WARNING: Note that the caller is responsible to confirm that the
-    /// recipient is capable of receiving the `Non-Fungible` or else the NFT
-    /// may be permanently lost.
+    /// Answered without storage reads. There is no `burnable` interface.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `from` - Account of the sender.
-    /// * `to` - Account of the recipient.
-    /// * `token_id` - Token id as a number.
-    /// * `message` - The message that was signed (without signer and nonce).
-    /// * `signature` - 64-byte ECDSA signature from NFC chip.
-    /// * `recovery_id` - Recovery ID (0-3) for signature recovery.
-    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
-    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `interface` - Interface name.
+    fn supports(e: &Env, interface: Symbol) -> bool;
+
+    /// Returns the version of the storage layout, `0` for instances deployed
+    /// before storage versioning until `migrate` is called.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn storage_version(e: &Env) -> u32;
+
+    /// Start a transfer of the admin role to `new_admin`.
+    ///
+    /// The transfer only takes effect once `new_admin` calls `accept_admin`.
+    /// Calling this again replaces the pending admin, which can be used to
+    /// cancel a transfer.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `new_admin` - Account proposed as the new admin.
     ///
     /// # Panics
     ///
-    /// * If the caller is not the owner of the token.
-    /// * If the token was not claimed.
-    /// * If the signature is invalid.
-    /// * If the token was not yet minted.
-    /// * If the token was already claimed.
+    /// * If the caller is not the admin.
+    /// * If several admins must approve ([`errors::NonFungibleTokenError::ProposalRequired`]).
     ///
     /// # Events
     ///
-    /// * topics - `["transfer", from: Address, to: Address]`
-    /// * data - `[token_id: u32]`
-    #[allow(clippy::too_many_arguments)]
-    fn transfer(
-        e: &Env,
-        from: Address,
-        to: Address,
-        token_id: u32,
-        message: Bytes,
-        signature: BytesN<64>,
-        recovery_id: u32,
-        public_key: BytesN<65>,
-        nonce: u32,
-    );
+    /// * topics - `["admin_transfer_started", admin: Address]`
+    /// * data - `[new_admin: Address]`
+    fn transfer_admin(e: &Env, new_admin: Address);
 
-    /// Clawback `token_id` token from owner.
-    ///
-    /// Only the admin can execute this function which sends the token to the
-    /// admin address. This is an extreme measure which quarantines
-    /// the token. Used in case of terms breach.
+    /// Accept the admin role as the pending admin.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If there is no pending admin.
+    /// * If the caller is not the pending admin.
     ///
     /// # Events
     ///
-    /// * topics - `["clawback", from: Address]`
-    /// * data - `[token_id: u32]`
-    fn clawback(e: &Env, token_id: u32);
+    /// * topics - `["admin_transferred", previous_admin: Address]`
+    /// * data - `[new_admin: Address]`
+    fn accept_admin(e: &Env);
 
-    /// Returns the current nonce for the given `public_key`.
+    /// Returns the admin of the contract.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    fn get_admin(e: &Env) -> Address;
+
+    /// Set the collection contract tracking the owners of this contract's tokens.
     ///
-    /// # Returns
+    /// The registry's `assign_collectible` is called with the new owner on
+    /// every claim, transfer and clawback. A failing call reverts the
+    /// operation, so the registry must list this contract as a collection.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `registry` - Address of the collection contract.
+    ///
+    /// # Panics
     ///
-    /// The current nonce for this chip's public_key (defaults to 0 if not set).
-    fn get_nonce(e: &Env, public_key: BytesN<65>) -> u32;
+    /// * If the caller is not the admin.
+    /// * If several admins must approve ([`errors::NonFungibleTokenError::ProposalRequired`]).
+    fn set_registry(e: &Env, registry: Address);
 
-    /// Returns the number of tokens in `owner`'s account.
+    /// Returns the collection contract notified of ownership changes.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `owner` - Account of the token's owner.
-    fn balance(e: &Env, owner: Address) -> u32;
+    fn registry(e: &Env) -> Address;
 
-    /// Returns the address of the owner of the given `token_id`.
+    /// Returns the pending admin, if an admin transfer is in progress.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `token_id` - Token id as a number.
+    fn get_pending_admin(e: &Env) -> Option<Address>;
+
+    /// Allow `minter` to mint tokens.
     ///
-    /// # Notes
+    /// Minters can only mint, all other admin functions stay restricted to
+    /// the admin.
     ///
-    /// If the token does not exist, this function is expected to panic.
-    fn owner_of(e: &Env, token_id: u32) -> Address;
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `minter` - Account allowed to mint.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["minter_set", minter: Address]`
+    /// * data - `[]`
+    fn set_minter(e: &Env, minter: Address);
 
-    /// Returns the token collection name.
+    /// Revoke the minting right of `minter`.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    fn name(e: &Env) -> String;
+    /// * `minter` - Account no longer allowed to mint.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["minter_removed", minter: Address]`
+    /// * data - `[]`
+    fn remove_minter(e: &Env, minter: Address);
 
-    /// Returns the token collection symbol.
+    /// Returns whether `addr` is a registered minter.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    fn symbol(e: &Env) -> String;
+    /// * `addr` - Account to check.
+    fn is_minter(e: &Env, addr: Address) -> bool;
 
-    /// Returns the Uniform Resource Identifier (URI) for `token_id` token.
+    /// Pause minting, claiming and transfers.
+    ///
+    /// View functions and `clawback` keep working while paused.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `token_id` - Token id as a number.
     ///
-    /// # Notes
+    /// # Panics
     ///
-    /// If the token does not exist, this function is expected to panic.
-    fn token_uri(e: &Env, token_id: u32) -> String;
+    /// * If the caller is not the admin.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["paused", admin: Address]`
+    /// * data - `[]`
+    fn pause(e: &Env);
 
-    /// Returns the token ID for the given chip public key.
+    /// Resume minting, claiming and transfers.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
     ///
-    /// # Returns
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
     ///
-    /// The token ID associated with this public key, or panics if not found.
-    fn token_id(e: &Env, public_key: BytesN<65>) -> u32;
+    /// # Events
+    ///
+    /// * topics - `["unpaused", admin: Address]`
+    /// * data - `[]`
+    fn unpause(e: &Env);
 
-    /// Returns the next token ID to mint.
+    /// Returns whether the contract is paused.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
+    fn is_paused(e: &Env) -> bool;
+
+    /// Enable or disable minting and claiming, independently of `pause`.
     ///
-    /// # Returns
+    /// # Arguments
     ///
-    /// The next token ID in the enumeration.
-    fn next_token_id(e: &Env) -> u32;
+    /// * `e` - The environment object.
+    /// * `enabled` - Whether `mint` and `claim` are allowed.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn set_mint_enabled(e: &Env, enabled: bool);
 
-    /// Returns the chip public key for the given token ID.
+    /// Enable or disable transfers, independently of `pause`.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `token_id` - Token id as a number.
+    /// * `enabled` - Whether `transfer` and `transfer_from` are allowed.
     ///
-    /// # Returns
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn set_transfer_enabled(e: &Env, enabled: bool);
+
+    /// Limit the number of tokens minted in a single ledger.
     ///
-    /// The chip's public key associated with this token ID.
+    /// Applies to every mint entry point, including `batch_mint` and `admin_mint`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `max_per_ledger` - Maximum number of mints per ledger, `0` disables the limit.
     ///
     /// # Panics
     ///
-    /// * If the token does not exist.
-    fn public_key(e: &Env, token_id: u32) -> BytesN<65>;
+    /// * If the caller is not the admin.
+    fn set_mint_rate_limit(e: &Env, max_per_ledger: u32);
 
-    /// Verify the chip signature.
+    /// Returns the maximum number of mints per ledger, `0` if unlimited.
+    ///
+    /// # Arguments
     ///
-    /// Verifies that the signature was created by the chip with the given public_key
-    /// Also handles nonce verification and updates the stored nonce for the `public_key`
+    /// * `e` - The environment object.
+    fn mint_rate_limit(e: &Env) -> u32;
+
+    /// Enable or disable `transfer_owner`, which moves tokens without a chip
+    /// signature. Disabled by default so transfers require physical possession.
     ///
     /// # Arguments
     ///
     /// * `e` - The environment object.
-    /// * `signer` - Address of the signer of the message.
-    /// * `message` - The message that was signed (without signer and nonce).
-    /// * `signature` - 64-byte ECDSA signature from NFC chip.
-    /// * `recovery_id` - Recovery ID (0-3) for signature recovery.
-    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
-    /// * `nonce` - A nonce to prevent replay attacks.
-    fn verify_chip_signature(
-        e: &Env,
-        signer: Bytes,
-        message: Bytes,
-        signature: BytesN<64>,
-        recovery_id: u32,
+    /// * `enabled` - Whether `transfer_owner` is allowed.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn set_chipless_transfer_enabled(e: &Env, enabled: bool);
+
+    /// Returns whether minting and claiming are enabled (defaults to true).
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn is_mint_enabled(e: &Env) -> bool;
+
+    /// Returns whether transfers are enabled (defaults to true).
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn is_transfer_enabled(e: &Env) -> bool;
+
+    /// Returns whether `transfer_owner` is enabled (defaults to false).
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn is_chipless_transfer_enabled(e: &Env) -> bool;
+
+    /// Enable or disable the `Transfer` events published next to `Claim` and
+    /// `Clawback` events, for indexers tracking ownership from transfers
+    /// only. Enabled by default.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `enabled` - Whether claims and clawbacks also publish `Transfer`.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn set_claim_transfer_events(e: &Env, enabled: bool);
+
+    /// Returns whether claims and clawbacks also publish `Transfer` events
+    /// (defaults to true).
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn claim_transfer_events(e: &Env) -> bool;
+
+    /// Update the base URI used to build token URIs.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `uri` - The new base URI.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If several admins must approve ([`errors::NonFungibleTokenError::ProposalRequired`]).
+    /// * If the base URI was frozen.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["base_uri_updated"]`
+    /// * data - `[old_uri: String, new_uri: String]`
+    fn set_base_uri(e: &Env, uri: String);
+
+    /// Set the suffix appended to token URIs, e.g. `.json` for `{base_uri}/{token_id}.json`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `suffix` - The new suffix, empty for none.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If the base URI was frozen.
+    fn set_uri_suffix(e: &Env, suffix: String);
+
+    /// Returns the suffix appended to token URIs (empty by default).
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn uri_suffix(e: &Env) -> String;
+
+    /// Permanently lock the base URI and suffix.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn freeze_base_uri(e: &Env);
+
+    /// Set the collection-level metadata URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `uri` - URI of the collection metadata.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["contract_uri_updated"]`
+    /// * data - `[uri: String]`
+    fn set_contract_uri(e: &Env, uri: String);
+
+    /// Raise the maximum number of tokens that can be minted.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `new_max` - The new maximum, not lower than the current one.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If `new_max` is lower than the current maximum.
+    fn set_max_tokens(e: &Env, new_max: u32);
+
+    /// Set the claim window of newly minted tokens.
+    ///
+    /// Tokens minted afterwards must be claimed within `ledgers` ledgers,
+    /// after which the admin can reclaim them. Already minted tokens keep
+    /// their deadline. A window of `0` disables the deadline.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `ledgers` - Number of ledgers a token can be claimed for.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn set_claim_deadline(e: &Env, ledgers: u32);
+
+    /// Make `token_id` token non-transferable.
+    ///
+    /// The token can still be claimed by its first owner and clawed back.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If the token was not yet minted.
+    fn freeze_token(e: &Env, token_id: u32);
+
+    /// Make `token_id` token transferable again.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn unfreeze_token(e: &Env, token_id: u32);
+
+    /// Lock `token_id` token in place until `until_ledger`.
+    ///
+    /// The owner keeps the token, but it cannot be transferred until the
+    /// deadline passes or the admin releases it. Clawbacks ignore the lock
+    /// and clear it.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `owner` - Owner of the token.
+    /// * `token_id` - Token id as a number.
+    /// * `until_ledger` - First ledger the token can move again.
+    ///
+    /// # Panics
+    ///
+    /// * If `owner` does not own the token ([`errors::NonFungibleTokenError::IncorrectOwner`]).
+    /// * If `until_ledger` is not in the future or shortens the current lock
+    ///   ([`errors::NonFungibleTokenError::InvalidInput`]).
+    ///
+    /// # Events
+    ///
+    /// * topics - `["lock", owner: Address]`
+    /// * data - `[token_id: u32, until_ledger: u32]`
+    fn lock(e: &Env, owner: Address, token_id: u32, until_ledger: u32);
+
+    /// Remove the lock of `token_id` token.
+    ///
+    /// The admin can release the token anytime, the owner once the deadline
+    /// passed. Does nothing if the token was never locked.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `caller` - The admin or the owner of the token.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If `caller` is not the admin, nor the owner after the deadline
+    ///   ([`errors::NonFungibleTokenError::NotAuthorized`]).
+    ///
+    /// # Events
+    ///
+    /// * topics - `["unlock", owner: Address]`
+    /// * data - `[token_id: u32]`
+    fn unlock(e: &Env, caller: Address, token_id: u32);
+
+    /// Returns the first ledger `token_id` token can move again.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The deadline or `None` if the token is not locked.
+    fn lock_info(e: &Env, token_id: u32) -> Option<u32>;
+
+    /// Lend `token_id` token to `user` until `expires_ledger`, see ERC-4907.
+    ///
+    /// The owner keeps the token, the user only gets the role. Replaces the
+    /// current user, and transfers clear it.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `owner` - Owner of the token.
+    /// * `token_id` - Token id as a number.
+    /// * `user` - Account using the token.
+    /// * `expires_ledger` - First ledger the user loses the role.
+    ///
+    /// # Panics
+    ///
+    /// * If `owner` does not own the token ([`errors::NonFungibleTokenError::IncorrectOwner`]).
+    /// * If `expires_ledger` is not in the future ([`errors::NonFungibleTokenError::InvalidInput`]).
+    ///
+    /// # Events
+    ///
+    /// * topics - `["user_set", token_id: u32, user: Address]`
+    /// * data - `[expires_ledger: u32]`
+    fn set_user(e: &Env, owner: Address, token_id: u32, user: Address, expires_ledger: u32);
+
+    /// Returns the current user of `token_id` token.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The user or `None` if the token is not lent or the role expired.
+    fn user_of(e: &Env, token_id: u32) -> Option<Address>;
+
+    /// Block a compromised chip.
+    ///
+    /// Any signature of a blocked chip is rejected, preventing mint, claim
+    /// and transfer of its token. The token itself is not moved, use
+    /// `clawback` for that.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn block_chip(e: &Env, public_key: BytesN<65>);
+
+    /// Accept signatures of a previously blocked chip again.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn unblock_chip(e: &Env, public_key: BytesN<65>);
+
+    /// Register chips allowed to mint.
+    ///
+    /// Registration is only enforced once enabled with `require_registration`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_keys` - The chips' public keys (uncompressed SEC1 format, 65 bytes).
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn register_chips(e: &Env, public_keys: Vec<BytesN<65>>);
+
+    /// Enable or disable minting with unregistered chips.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `enabled` - Whether only registered chips can mint.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    fn require_registration(e: &Env, enabled: bool);
+
+    /// Set the royalty applying to tokens without their own royalty.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `receiver` - Account receiving the royalties.
+    /// * `basis_points` - Royalty in basis points, 10_000 being 100%.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If `basis_points` is above 10_000.
+    fn set_default_royalty(e: &Env, receiver: Address, basis_points: u32);
+
+    /// Charge a fee for each chip minted with a signature.
+    ///
+    /// The minter pays `amount` of `asset` to the contract once the chip
+    /// signature is verified, `mint_to` is paid by the admin.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `asset` - Token contract of the fee, e.g. a Stellar Asset Contract.
+    /// * `amount` - Fee per token, `0` disables the fee.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If `amount` is negative ([`errors::NonFungibleTokenError::InvalidFee`]).
+    fn set_mint_fee(e: &Env, asset: Address, amount: i128);
+
+    /// Returns the mint fee, if one was ever set.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn mint_fee(e: &Env) -> Option<MintFee>;
+
+    /// Send the collected fees of `asset` to `to`, including fees collected
+    /// before the mint fee moved to another asset.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `asset` - Token contract of the fees to withdraw.
+    /// * `to` - Account receiving the fees.
+    ///
+    /// # Returns
+    ///
+    /// The amount withdrawn.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["fees_withdrawn", to: Address]`
+    /// * data - `[asset: Address, amount: i128]`
+    fn withdraw_fees(e: &Env, asset: Address, to: Address) -> i128;
+
+    /// Set the royalty of `token_id` token, overriding the default one.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    /// * `receiver` - Account receiving the royalties.
+    /// * `basis_points` - Royalty in basis points, 10_000 being 100%.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If the token was not yet minted.
+    /// * If `basis_points` is above 10_000.
+    fn set_token_royalty(e: &Env, token_id: u32, receiver: Address, basis_points: u32);
+
+    /// Mint NFT using NFC chip signature.
+    ///
+    /// This function verifies that the provided signature was created by an Infineon
+    /// NFC chip by recovering the chip's public key. The public key is converted to
+    /// a SEP-50 compliant u32 token_id.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `minter` - The admin or a registered minter, signer of the chip signature.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `public_key` - The chip's public key (SEC1 format, compressed 33 bytes or uncompressed 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    ///
+    /// # Panics
+    ///
+    /// * If `public_key` is not a 33 or 65 bytes SEC1 key
+    ///   ([`errors::NonFungibleTokenError::InvalidPublicKey`]).
+    /// * If the contract is paused or minting is disabled.
+    /// * If `minter` is neither the admin nor a registered minter.
+    /// * If registration is required and the chip is not registered.
+    /// * If the signature is invalid or expired.
+    /// * If the minter cannot pay the mint fee.
+    /// * If the mint rate limit of the ledger was reached
+    ///   ([`errors::NonFungibleTokenError::RateLimitExceeded`]).
+    /// * If the S value of the signature is not normalized
+    ///   ([`errors::NonFungibleTokenError::MalleableSignature`]).
+    /// * If `recovery_id` is invalid ([`errors::NonFungibleTokenError::InvalidRecoveryId`]).
+    /// * If the nonce was already used ([`errors::NonFungibleTokenError::NonceAlreadyUsed`])
+    ///   or skips ahead ([`errors::NonFungibleTokenError::InvalidNonce`]).
+    /// * If the token was already minted.
+    /// * If the chip was retired by `rotate_chip` or `remove_backup_key`
+    ///   ([`errors::NonFungibleTokenError::ChipRetired`]).
+    /// * If there are no more tokens to be minted.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["mint", to: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    #[allow(clippy::too_many_arguments)]
+    fn mint(
+        e: &Env,
+        minter: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32;
+
+    /// Mint NFT using a secp256r1 (P-256) NFC chip signature.
+    ///
+    /// Same as `mint` for chips which only support the NIST P-256 curve. The
+    /// signature is verified against `public_key` instead of recovering it.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `minter` - The admin or a registered minter, signer of the chip signature.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip, with a low S.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    ///
+    /// # Panics
+    ///
+    /// * Same as `mint`.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["mint", to: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    fn mint_r1(
+        e: &Env,
+        minter: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<65>,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32;
+
+    /// Mint NFT using an ed25519 NFC chip signature.
+    ///
+    /// Same as `mint` for chips with ed25519 keys. The sha256 hash described in
+    /// `check_chip_signature` is the signed message.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `minter` - The admin or a registered minter, signer of the chip signature.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ed25519 signature from NFC chip.
+    /// * `public_key` - The chip's ed25519 public key (32 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    ///
+    /// # Panics
+    ///
+    /// * Same as `mint`.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["mint", to: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    fn mint_ed25519(
+        e: &Env,
+        minter: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<32>,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32;
+
+    /// Mint NFT using NFC chip signature directly to its first owner.
+    ///
+    /// The chip is verified as in `mint`, then the token is claimed for `to`
+    /// without a separate `claim` step.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `to` - Account of the token's owner.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * Otherwise, see `mint`.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["mint", to: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    ///
+    /// * topics - `["claim", claimant: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    ///
+    /// Unless disabled with `set_claim_transfer_events`:
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    fn mint_to(
+        e: &Env,
+        to: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: BytesN<65>,
+        nonce: u32,
+    ) -> u32;
+
+    /// Hold back the token ids from `first_token_id` to `up_to` (excluded) for
+    /// `mint_reserved`, other mints allocating ids from `up_to` on.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `up_to` - First id of the public range, at most `max_tokens` above `first_token_id`.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If a token was already minted ([`errors::NonFungibleTokenError::MintingStarted`]).
+    /// * If `up_to` is out of that range ([`errors::NonFungibleTokenError::InvalidInput`]).
+    fn reserve_range(e: &Env, up_to: u32);
+
+    /// Returns the end of the reserved id range, `0` if no id is reserved.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn reserved_range(e: &Env) -> u32;
+
+    /// Mint NFT with a reserved token id using NFC chip signature.
+    ///
+    /// The chip signs over the admin as in `mint`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - The reserved id, below `reserved_range`.
+    /// * `message`, `signature`, `recovery_id`, `public_key`, `nonce`, `expiration_ledger` - See `mint`.
+    ///
+    /// # Returns
+    ///
+    /// `token_id`.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If `token_id` is not reserved ([`errors::NonFungibleTokenError::InvalidInput`]).
+    /// * If `token_id` or the chip already have a token
+    ///   ([`errors::NonFungibleTokenError::TokenAlreadyMinted`]).
+    /// * Otherwise, see `mint`.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["mint", to: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    #[allow(clippy::too_many_arguments)]
+    fn mint_reserved(
+        e: &Env,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32;
+
+    /// Mint an NFT for a provisioned chip without a chip signature.
+    ///
+    /// For chips whose public keys are known from the factory. The token is
+    /// unclaimed and `claim` still requires a signature of the chip, its first
+    /// nonce being 1.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's secp256k1 public key (uncompressed SEC1 format, 65 bytes).
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant).
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If the contract is paused or minting is disabled.
+    /// * If `public_key` is not an uncompressed key ([`errors::NonFungibleTokenError::InvalidPublicKey`]).
+    /// * If chip registration is required and the chip is not registered.
+    /// * If the chip already has a token ([`errors::NonFungibleTokenError::TokenAlreadyMinted`]).
+    /// * If the chip was retired by `rotate_chip` or `remove_backup_key`
+    ///   ([`errors::NonFungibleTokenError::ChipRetired`]).
+    /// * If the mint rate limit of the ledger was reached
+    ///   ([`errors::NonFungibleTokenError::RateLimitExceeded`]).
+    /// * If `max_tokens` tokens were minted.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["mint", to: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    fn admin_mint(e: &Env, public_key: BytesN<65>) -> u32;
+
+    /// Mint NFTs for several provisioned chips, see `admin_mint`.
+    ///
+    /// The batch is atomic: if any key fails, no token is minted.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_keys` - The chips' secp256k1 public keys.
+    ///
+    /// # Returns
+    ///
+    /// The assigned token ids, in the order of `public_keys`.
+    ///
+    /// # Panics
+    ///
+    /// See `admin_mint`.
+    ///
+    /// # Events
+    ///
+    /// One `mint` event per token, see `admin_mint`.
+    fn admin_mint_batch(e: &Env, public_keys: Vec<BytesN<65>>) -> Vec<u32>;
+
+    /// Mint several NFTs using NFC chip signatures in one invocation.
+    ///
+    /// Each entry is verified independently as in `mint`. The batch is
+    /// atomic: if any entry fails, no token is minted.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `minter` - The admin or a registered minter, see `mint`.
+    /// * `mints` - The chip signatures to mint, see [`MintRequest`].
+    ///
+    /// # Returns
+    ///
+    /// The assigned token ids, in the order of `mints`.
+    ///
+    /// # Panics
+    ///
+    /// See `mint`.
+    ///
+    /// # Events
+    ///
+    /// One `mint` event per token, see `mint`.
+    fn batch_mint(e: &Env, minter: Address, mints: Vec<MintRequest>) -> Vec<u32>;
+
+    /// Mint NFT using NFC chip signature and attach on-chain metadata.
+    ///
+    /// Same as `mint`, additionally storing a [`TokenMetadata`] for the token.
+    /// `minted_ledger` is set to the current ledger sequence.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `edition` - Edition number of the item.
+    /// * `batch` - Chip production batch.
+    /// * `extra` - Additional free-form attributes.
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * Otherwise, see `mint`.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["mint", to: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    #[allow(clippy::too_many_arguments)]
+    fn mint_with_metadata(
+        e: &Env,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: BytesN<65>,
+        nonce: u32,
+        edition: u32,
+        batch: String,
+        extra: Map<String, String>,
+    ) -> u32;
+
+    /// Set the `key` attribute of `token_id` token to `value`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    /// * `key` - Attribute name.
+    /// * `value` - Attribute value.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If the token does not exist.
+    /// * If the metadata of the token was frozen
+    ///   ([`errors::NonFungibleTokenError::MetadataFrozen`]).
+    fn set_token_attribute(e: &Env, token_id: u32, key: String, value: String);
+
+    /// Permanently lock the attributes of `token_id` token.
+    ///
+    /// The token URI derives from the base URI, see `freeze_base_uri`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If the token was not yet minted.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["metadata_frozen"]`
+    /// * data - `[token_id: u32]`
+    fn freeze_token_metadata(e: &Env, token_id: u32);
+
+    /// Permanently lock the attributes of all tokens, including future ones,
+    /// and the base URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["metadata_frozen"]`
+    /// * data - `[token_id: u32]` with [`ALL_TOKENS`]
+    fn freeze_all_metadata(e: &Env);
+
+    /// Returns whether the attributes of `token_id` token are frozen.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    fn is_metadata_frozen(e: &Env, token_id: u32) -> bool;
+
+    /// Set the verification URL written in the NDEF record of the chip of `token_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `caller` - The admin or the owner of the token.
+    /// * `token_id` - Token id as a number.
+    /// * `url` - The URL, at most `MAX_CHIP_URL_LEN` bytes.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist ([`errors::NonFungibleTokenError::NonExistentToken`]).
+    /// * If `caller` is neither the admin nor the owner ([`errors::NonFungibleTokenError::NotAuthorized`]).
+    /// * If `url` is too long ([`errors::NonFungibleTokenError::InvalidInput`]).
+    ///
+    /// # Events
+    ///
+    /// * topics - `["chip_url_updated", token_id: u32]`
+    /// * data - `[url: String]`
+    fn set_chip_url(e: &Env, caller: Address, token_id: u32, url: String);
+
+    /// Returns the verification URL of the chip of `token_id`, if set.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    fn chip_url(e: &Env, token_id: u32) -> Option<String>;
+
+    /// Record the manufacturing batch of the chip with `public_key`.
+    ///
+    /// Works before and after the chip is minted, replacing its previous
+    /// batch. A token follows the batch of its new chip on `rotate_chip`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `batch` - Batch name, at most [`MAX_BATCH_LEN`] bytes.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If `batch` is empty or too long ([`errors::NonFungibleTokenError::InvalidInput`]).
+    ///
+    /// # Events
+    ///
+    /// * topics - `["batch_assigned", public_key: BytesN<65>]`
+    /// * data - `[batch: String]`
+    fn set_chip_batch(e: &Env, public_key: BytesN<65>, batch: String);
+
+    /// Returns the manufacturing batch of the chip of `token_id` token.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The batch or `None` if the token does not exist or has no batch.
+    fn chip_batch(e: &Env, token_id: u32) -> Option<String>;
+
+    /// Returns the token ids minted from chips of `batch`. A chip leaving the
+    /// batch moves the last token id into its place.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `batch` - Batch name.
+    /// * `start` - Index of the first token id to return.
+    /// * `limit` - Maximum number of token ids to return.
+    ///
+    /// # Returns
+    ///
+    /// Up to `limit` token ids, empty for unknown batches.
+    ///
+    /// # Panics
+    ///
+    /// * If `limit` is above [`MAX_TOKENS_PAGE`] ([`errors::NonFungibleTokenError::InvalidInput`]).
+    fn tokens_in_batch(e: &Env, batch: String, start: u32, limit: u32) -> Vec<u32>;
+
+    /// Returns the on-chain metadata of `token_id` token.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The stored metadata, or a default (empty) one if none was set.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist.
+    fn token_metadata(e: &Env, token_id: u32) -> TokenMetadata;
+
+    /// Claim NFT using NFC chip signature.
+    ///
+    /// This function verifies that the provided signature was created by an Infineon
+    /// NFC chip by recovering the chip's public key. The public key is converted to
+    /// a SEP-50 compliant u32 token_id.
+    ///
+    /// Requires both the chip signature, which binds `claimant`, and the
+    /// Soroban auth of `claimant`, so an intercepted signature cannot be
+    /// submitted without the claimant's consent.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `claimant` - Account of the claimant.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `public_key` - The chip's public key (SEC1 format, compressed 33 bytes or uncompressed 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    ///
+    /// # Panics
+    ///
+    /// * If `public_key` is neither 33 nor 65 bytes long.
+    /// * If the contract is paused or minting is disabled.
+    /// * If the claimant is not the signer.
+    /// * If the signature is invalid or expired.
+    /// * If `recovery_id` is invalid ([`errors::NonFungibleTokenError::InvalidRecoveryId`]).
+    /// * If the nonce was already used ([`errors::NonFungibleTokenError::NonceAlreadyUsed`])
+    ///   or skips ahead ([`errors::NonFungibleTokenError::InvalidNonce`]).
+    /// * If the token was minted for another curve ([`errors::NonFungibleTokenError::SignerMismatch`]).
+    /// * If the token was not yet minted.
+    /// * If the token was already claimed.
+    /// * If the claim window of the token elapsed.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["claim", claimant: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    ///
+    /// Unless disabled with `set_claim_transfer_events`:
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    #[allow(clippy::too_many_arguments)]
+    fn claim(
+        e: &Env,
+        claimant: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32;
+
+    /// Claim NFT using NFC chip signature, attributing the claim to `referrer`.
+    ///
+    /// Same as `claim`, and records the referrer of the token. Self-referrals
+    /// and a `None` referrer are not recorded.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `claimant` - Account of the claimant.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `public_key` - The chip's public key (SEC1 format, compressed 33 bytes or uncompressed 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    /// * `referrer` - Account which referred the claimant.
+    ///
+    /// # Returns
+    ///
+    /// The claimed token id.
+    ///
+    /// # Panics
+    ///
+    /// See `claim`.
+    ///
+    /// # Events
+    ///
+    /// The events of `claim` and, if the referral is recorded:
+    ///
+    /// * topics - `["referral", referrer: Address, claimant: Address]`
+    /// * data - `[token_id: u32]`
+    #[allow(clippy::too_many_arguments)]
+    fn claim_with_referrer(
+        e: &Env,
+        claimant: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+        referrer: Option<Address>,
+    ) -> u32;
+
+    /// Returns the account which referred the claim of `token_id` token.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The referrer or `None` if the claim was not referred.
+    fn referrer_of(e: &Env, token_id: u32) -> Option<Address>;
+
+    /// Returns the number of claims referred by `referrer`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `referrer` - Account which referred the claims.
+    fn referral_count(e: &Env, referrer: Address) -> u32;
+
+    /// Claim NFT using a secp256r1 (P-256) NFC chip signature.
+    ///
+    /// Same as `claim` for tokens minted with `mint_r1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `claimant` - Address of the claimant, signer of the chip signature.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip, with a low S.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    ///
+    /// # Panics
+    ///
+    /// * Same as `claim`.
+    /// * If the token was not minted with a secp256r1 chip.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["claim", claimant: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    ///
+    /// Unless disabled with `set_claim_transfer_events`:
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    fn claim_r1(
+        e: &Env,
+        claimant: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<65>,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32;
+
+    /// Claim NFT using an ed25519 NFC chip signature.
+    ///
+    /// Same as `claim` for tokens minted with `mint_ed25519`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `claimant` - Address of the claimant, signer of the chip signature.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ed25519 signature from NFC chip.
+    /// * `public_key` - The chip's ed25519 public key (32 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    ///
+    /// # Panics
+    ///
+    /// * Same as `claim`.
+    /// * If the token was not minted with an ed25519 chip.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["claim", claimant: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    ///
+    /// Unless disabled with `set_claim_transfer_events`:
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    fn claim_ed25519(
+        e: &Env,
+        claimant: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<32>,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32;
+
+    /// Claim NFT on behalf of `claimant`, submitted by the admin.
+    ///
+    /// Lets the admin sponsor the claim of users without funds. The chip
+    /// signature must still be made over the `claimant` address so the
+    /// token cannot be redirected.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `claimant` - Account of the claimant.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * Otherwise, see `claim`.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["claim", claimant: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    ///
+    /// Unless disabled with `set_claim_transfer_events`:
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    fn claim_for(
+        e: &Env,
+        claimant: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: BytesN<65>,
+        nonce: u32,
+    ) -> u32;
+
+    /// Take ownership of a token whose claim window elapsed.
+    ///
+    /// Only the admin can execute this function which claims the token for
+    /// the admin address.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If the token was not yet minted.
+    /// * If the token was already claimed.
+    /// * If the token has no claim deadline or it has not passed yet.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["claim", admin: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    ///
+    /// Unless disabled with `set_claim_transfer_events`:
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    fn reclaim_expired(e: &Env, token_id: u32);
+
+    /// Claim several NFTs for the same claimant in one invocation.
+    ///
+    /// Each entry is verified independently as in `claim`. The batch is
+    /// atomic: if any entry fails, no token is claimed.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `claimant` - Account of the claimant.
+    /// * `claims` - The chip signatures to claim, see [`ClaimRequest`].
+    ///
+    /// # Returns
+    ///
+    /// The claimed token ids, in the order of `claims`.
+    ///
+    /// # Panics
+    ///
+    /// See `claim`.
+    ///
+    /// # Events
+    ///
+    /// One `claim` event per token, see `claim`.
+    fn batch_claim(e: &Env, claimant: Address, claims: Vec<ClaimRequest>) -> Vec<u32>;
+
+    /// Transfers `token_id` token from `from` to `to` using NFC chip signature.
+    ///
+    /// This function verifies that the provided signature was created by a
+    /// NFC chip whose public key corresponds to the token being transferred.
+    /// Requires the Soroban auth of `from` as well.
+    ///
+    /// WARNING: Note that the caller is responsible to confirm that the
+    /// recipient is capable of receiving the `Non-Fungible` or else the NFT
+    /// may be permanently lost.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `from` - Account of the sender.
+    /// * `to` - Account of the recipient.
+    /// * `token_id` - Token id as a number.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `public_key` - The chip's public key (SEC1 format, compressed 33 bytes or uncompressed 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Panics
+    ///
+    /// * If `public_key` is neither 33 nor 65 bytes long.
+    /// * If the contract is paused or transfers are disabled.
+    /// * If the collection is soulbound.
+    /// * If the token is frozen.
+    /// * If the token is locked ([`errors::NonFungibleTokenError::TokenLocked`]).
+    /// * If the caller is not the owner of the token.
+    /// * If the token was not claimed.
+    /// * If the signature is invalid or expired.
+    /// * If `recovery_id` is invalid ([`errors::NonFungibleTokenError::InvalidRecoveryId`]).
+    /// * If the nonce was already used ([`errors::NonFungibleTokenError::NonceAlreadyUsed`])
+    ///   or skips ahead ([`errors::NonFungibleTokenError::InvalidNonce`]).
+    /// * If the chip is not the chip of the token ([`errors::NonFungibleTokenError::SignerMismatch`]).
+    /// * If the token was not yet minted.
+    /// * If the token was already claimed.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    #[allow(clippy::too_many_arguments)]
+    fn transfer(
+        e: &Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    );
+
+    /// Same as `transfer`, checking that a recipient contract can handle the token.
+    ///
+    /// When `to` is a contract, its `on_nft_received(operator: Address, from: Address,
+    /// token_id: u32) -> bool` function is called after the transfer with `from` as
+    /// the operator. The transfer is reverted unless it returns `true`. Accounts are
+    /// not checked.
+    ///
+    /// # Arguments
+    ///
+    /// See `transfer`.
+    ///
+    /// # Panics
+    ///
+    /// * Everything `transfer` panics on.
+    /// * If the recipient contract does not return `true` from `on_nft_received`
+    ///   ([`errors::NonFungibleTokenError::UnsafeRecipient`]).
+    ///
+    /// # Events
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    #[allow(clippy::too_many_arguments)]
+    fn safe_transfer(
+        e: &Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    );
+
+    /// Transfers `token_id` token from `from` to `to` using a secp256r1 (P-256)
+    /// NFC chip signature.
+    ///
+    /// Same as `transfer` for tokens minted with `mint_r1`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `from` - Account of the sender.
+    /// * `to` - Account of the recipient.
+    /// * `token_id` - Token id as a number.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip, with a low S.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Panics
+    ///
+    /// * Same as `transfer`.
+    /// * If the token was not minted with a secp256r1 chip.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_r1(
+        e: &Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<65>,
+        nonce: u32,
+        expiration_ledger: u32,
+    );
+
+    /// Transfers `token_id` token from `from` to `to` using an ed25519
+    /// NFC chip signature.
+    ///
+    /// Same as `transfer` for tokens minted with `mint_ed25519`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `from` - Account of the sender.
+    /// * `to` - Account of the recipient.
+    /// * `token_id` - Token id as a number.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ed25519 signature from NFC chip.
+    /// * `public_key` - The chip's ed25519 public key (32 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Panics
+    ///
+    /// * Same as `transfer`.
+    /// * If the token was not minted with an ed25519 chip.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_ed25519(
+        e: &Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        public_key: BytesN<32>,
+        nonce: u32,
+        expiration_ledger: u32,
+    );
+
+    /// Transfers `token_id` token from `from` to `to` without a chip signature.
+    ///
+    /// Only the owner's authorization is required, e.g. to move a token
+    /// between their own wallets. Must be enabled by the admin with
+    /// `set_chipless_transfer_enabled`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `from` - Account of the sender.
+    /// * `to` - Account of the recipient.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If the contract is paused or transfers are disabled.
+    /// * If chipless transfers are disabled ([`errors::NonFungibleTokenError::OperationDisabled`]).
+    /// * If the collection is soulbound.
+    /// * If the token is frozen.
+    /// * If the token is locked ([`errors::NonFungibleTokenError::TokenLocked`]).
+    /// * If `from` is not the owner of the token ([`errors::NonFungibleTokenError::IncorrectOwner`]).
+    /// * If the token does not exist or was not claimed.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    fn transfer_owner(e: &Env, from: Address, to: Address, token_id: u32);
+
+    /// Transfers `token_id` token to `to` without a chip signature, for
+    /// tokens whose chip was destroyed.
+    ///
+    /// Requires the authorization of both the admin and the owner, so
+    /// neither can move the token alone. The token is then marked with a lost
+    /// chip until `rotate_chip` binds a new one.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    /// * `to` - Account of the recipient.
+    /// * `owner_consent` - Must be `true`, acknowledging the owner signs too.
+    ///
+    /// # Panics
+    ///
+    /// * If the contract is paused.
+    /// * If `owner_consent` is `false` ([`errors::NonFungibleTokenError::InvalidInput`]).
+    /// * If `to` already owns the token ([`errors::NonFungibleTokenError::IncorrectOwner`]).
+    /// * If the token does not exist or was not claimed.
+    /// * If the token is quarantined and several admins must approve
+    ///   ([`errors::NonFungibleTokenError::ProposalRequired`]).
+    ///
+    /// # Events
+    ///
+    /// * topics - `["forced_transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    fn admin_transfer(e: &Env, token_id: u32, to: Address, owner_consent: bool);
+
+    /// Returns whether `token_id` token was moved by `admin_transfer` since
+    /// its chip was last bound.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    fn is_chip_lost(e: &Env, token_id: u32) -> bool;
+
+    /// Same as `mint`, the chip signing a [`SignedAction`] instead of raw bytes.
+    ///
+    /// The XDR encoding of `payload` is used as the message of the signed hash.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `minter` - The admin or a registered minter, signer of the chip signature.
+    /// * `payload` - The signed payload, its action must be `mint`.
+    /// * `signature`, `recovery_id`, `public_key`, `nonce`, `expiration_ledger` - See `mint`.
+    ///
+    /// # Returns
+    ///
+    /// The u32 token_id (SEP-50 compliant) if signature is valid.
+    ///
+    /// # Panics
+    ///
+    /// * If the action is not `mint` or `payload.token_id` is set to another token
+    ///   ([`errors::NonFungibleTokenError::PayloadMismatch`]).
+    /// * Otherwise, see `mint`.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["mint", to: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    ///
+    /// If `payload.memo` is set:
+    ///
+    /// * topics - `["memo", token_id: u32]`
+    /// * data - `[memo: String]`
+    #[allow(clippy::too_many_arguments)]
+    fn mint_v2(
+        e: &Env,
+        minter: Address,
+        payload: SignedAction,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32;
+
+    /// Same as `claim`, the chip signing a [`SignedAction`] instead of raw bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `claimant` - Account of the claimant, signer of the chip signature.
+    /// * `payload` - The signed payload, its action must be `claim`.
+    /// * `signature`, `recovery_id`, `public_key`, `nonce`, `expiration_ledger` - See `claim`.
+    ///
+    /// # Returns
+    ///
+    /// The claimed token id.
+    ///
+    /// # Panics
+    ///
+    /// * If the action is not `claim` or `payload.token_id` is set to another token
+    ///   ([`errors::NonFungibleTokenError::PayloadMismatch`]).
+    /// * Otherwise, see `claim`.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["claim", claimant: Address]`
+    /// * data - `[token_id: u32, public_key: BytesN<65>]`
+    ///
+    /// Unless disabled with `set_claim_transfer_events`:
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    ///
+    /// If `payload.memo` is set:
+    ///
+    /// * topics - `["memo", token_id: u32]`
+    /// * data - `[memo: String]`
+    #[allow(clippy::too_many_arguments)]
+    fn claim_v2(
+        e: &Env,
+        claimant: Address,
+        payload: SignedAction,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    ) -> u32;
+
+    /// Same as `transfer`, the chip signing a [`SignedAction`] instead of raw bytes.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `from` - Account of the sender, signer of the chip signature.
+    /// * `to` - Account of the recipient.
+    /// * `token_id` - Token id as a number.
+    /// * `payload` - The signed payload, its action must be `transfer`.
+    /// * `signature`, `recovery_id`, `public_key`, `nonce`, `expiration_ledger` - See `transfer`.
+    ///
+    /// # Panics
+    ///
+    /// * If the action is not `transfer`, or `payload.token_id` or `payload.to` are
+    ///   set to another token or recipient ([`errors::NonFungibleTokenError::PayloadMismatch`]).
+    /// * Otherwise, see `transfer`.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    ///
+    /// If `payload.memo` is set:
+    ///
+    /// * topics - `["memo", token_id: u32]`
+    /// * data - `[memo: String]`
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_v2(
+        e: &Env,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        payload: SignedAction,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        expiration_ledger: u32,
+    );
+
+    /// Transfers `token_id` token from `from` to `to` on behalf of the owner.
+    ///
+    /// The `spender` must be the owner or hold a live approval for the token.
+    /// Physical possession is still required: the chip signature must be
+    /// made over the `spender` address.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `spender` - Account submitting the transfer.
+    /// * `from` - Account of the current owner.
+    /// * `to` - Account of the recipient.
+    /// * `token_id` - Token id as a number.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `nonce` - A nonce to prevent replay attacks.
+    ///
+    /// # Panics
+    ///
+    /// * If the contract is paused or transfers are disabled.
+    /// * If the collection is soulbound.
+    /// * If the token is frozen.
+    /// * If the token is locked ([`errors::NonFungibleTokenError::TokenLocked`]).
+    /// * If the spender is neither the owner nor approved for the token.
+    /// * If `from` is not the owner of the token.
+    /// * If the signature is invalid.
+    /// * If the nonce was already used ([`errors::NonFungibleTokenError::NonceAlreadyUsed`])
+    ///   or skips ahead ([`errors::NonFungibleTokenError::InvalidNonce`]).
+    /// * If the chip is not the chip of the token ([`errors::NonFungibleTokenError::SignerMismatch`]).
+    /// * If the token does not exist or was not claimed.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    #[allow(clippy::too_many_arguments)]
+    fn transfer_from(
+        e: &Env,
+        spender: Address,
+        from: Address,
+        to: Address,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
         public_key: BytesN<65>,
         nonce: u32,
     );
+
+    /// Gives permission to `approved` to transfer `token_id` token.
+    ///
+    /// The approval is valid until `live_until_ledger` (inclusive). Passing
+    /// `0` as `live_until_ledger` revokes the current approval. Only one
+    /// account can be approved per token at a time.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `approver` - Account of the token's owner.
+    /// * `approved` - Account allowed to transfer the token.
+    /// * `token_id` - Token id as a number.
+    /// * `live_until_ledger` - Last ledger at which the approval is valid.
+    ///
+    /// # Panics
+    ///
+    /// * If `approver` is not the owner of the token ([`errors::NonFungibleTokenError::NotAuthorized`]).
+    /// * If `live_until_ledger` is in the past.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["approve", approver: Address, token_id: u32]`
+    /// * data - `[approved: Address, live_until_ledger: u32]`
+    fn approve(
+        e: &Env,
+        approver: Address,
+        approved: Address,
+        token_id: u32,
+        live_until_ledger: u32,
+    );
+
+    /// Returns the account approved for `token_id` token, if any.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The approved account or `None` if there is no live approval.
+    fn get_approved(e: &Env, token_id: u32) -> Option<Address>;
+
+    /// Clawback `token_id` token from owner.
+    ///
+    /// Only the admin can execute this function which sends the token to the
+    /// admin address. This is an extreme measure which quarantines
+    /// the token until it is moved again. Used in case of terms breach.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    /// * `reason` - DAO defined code recording why the token was taken.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If several admins must approve ([`errors::NonFungibleTokenError::ProposalRequired`]).
+    /// * If the token was not claimed.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["clawback", from: Address]`
+    /// * data - `[token_id: u32, reason: u32]`
+    ///
+    /// Unless disabled with `set_claim_transfer_events`:
+    ///
+    /// * topics - `["transfer", from: Address, to: Address]`
+    /// * data - `[token_id: u32]`
+    fn clawback(e: &Env, token_id: u32, reason: u32);
+
+    /// Release `token_id` token from quarantine.
+    ///
+    /// Only the admin can execute this function which undoes a clawback by
+    /// sending the token to `to`, typically the owner it was taken from.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    /// * `to` - Account receiving the token.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If several admins must approve ([`errors::NonFungibleTokenError::ProposalRequired`]).
+    /// * If the token is not quarantined or no longer held by the admin
+    ///   ([`errors::NonFungibleTokenError::TokenNotQuarantined`]).
+    ///
+    /// # Events
+    ///
+    /// * topics - `["clawback_release", to: Address]`
+    /// * data - `[token_id: u32]`
+    fn clawback_release(e: &Env, token_id: u32, to: Address);
+
+    /// Bind `token_id` token to a replacement chip.
+    ///
+    /// Used when a chip is damaged. The new chip proves possession by signing
+    /// over the owner with `ACTION_ROTATE`, the sha256 hash described in
+    /// `check_chip_signature` being the signed message. The old chip can no
+    /// longer be used for the token, its nonce is kept and it is retired, it
+    /// cannot be minted or rotated to again.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    /// * `new_public_key` - The new chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from the new chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `nonce` - The next nonce of the new chip, see `get_nonce`.
+    ///
+    /// # Panics
+    ///
+    /// * If the owner or the admin does not authorize.
+    /// * If the token does not exist or was not claimed.
+    /// * If the new chip is already bound to a token ([`errors::NonFungibleTokenError::TokenAlreadyMinted`]).
+    /// * If the new chip was retired by `rotate_chip` or `remove_backup_key`
+    ///   ([`errors::NonFungibleTokenError::ChipRetired`]).
+    /// * If the signature is invalid or the nonce is not the next one.
+    /// * If the new chip is blocked.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["chip_rotated", token_id: u32]`
+    /// * data - `[old_public_key: BytesN<65>, new_public_key: BytesN<65>]`
+    #[allow(clippy::too_many_arguments)]
+    fn rotate_chip(
+        e: &Env,
+        token_id: u32,
+        new_public_key: BytesN<65>,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        nonce: u32,
+    );
+
+    /// Bind a backup chip to `token_id` token, e.g. sewn into the packaging.
+    ///
+    /// Transfers accept signatures of either chip and `token_id` resolves
+    /// both keys. The backup chip proves possession by signing over the admin
+    /// with `ACTION_BACKUP`, the sha256 hash described in
+    /// `check_chip_signature` being the signed message.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    /// * `backup_key` - The backup chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from the backup chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `nonce` - The next nonce of the backup chip, see `get_nonce`.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If the token does not exist.
+    /// * If the token already has a backup chip ([`errors::NonFungibleTokenError::InvalidInput`]).
+    /// * If the backup chip is already bound to a token ([`errors::NonFungibleTokenError::TokenAlreadyMinted`]).
+    /// * If the backup chip was retired by `rotate_chip` or `remove_backup_key`
+    ///   ([`errors::NonFungibleTokenError::ChipRetired`]).
+    /// * If the signature is invalid or the nonce is not the next one.
+    /// * If the backup chip is blocked.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["backup_key_added", token_id: u32]`
+    /// * data - `[backup_key: BytesN<65>]`
+    #[allow(clippy::too_many_arguments)]
+    fn add_backup_key(
+        e: &Env,
+        token_id: u32,
+        backup_key: BytesN<65>,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        nonce: u32,
+    );
+
+    /// Unbind the backup chip of `token_id` token, its nonce is kept.
+    ///
+    /// The chip is retired, it cannot be minted or bound to a token again.
+    /// Does nothing if the token has no backup chip.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["backup_key_removed", token_id: u32]`
+    /// * data - `[backup_key: BytesN<65>]`
+    fn remove_backup_key(e: &Env, token_id: u32);
+
+    /// Returns the public key of the backup chip of `token_id` token.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    fn backup_key(e: &Env, token_id: u32) -> Option<BytesN<65>>;
+
+    /// Extend the TTL of the persistent entries of a token.
+    ///
+    /// Covers the token's owner, chip key mappings, nonce, metadata and the
+    /// owner's balance. Anyone can call this to keep a token from being archived.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    /// * `extend_to` - Number of ledgers the entries should live for, capped at
+    ///   the network's maximum TTL.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist ([`errors::NonFungibleTokenError::NonExistentToken`]).
+    fn extend_token_ttl(e: &Env, token_id: u32, extend_to: u32);
+
+    /// Extend the TTL of the contract instance, holding the contract configuration.
+    ///
+    /// Anyone can call this.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `extend_to` - Number of ledgers the instance should live for, capped at
+    ///   the network's maximum TTL.
+    fn extend_instance_ttl(e: &Env, extend_to: u32);
+
+    /// Overwrite the stored nonce of a chip.
+    ///
+    /// Used to recover a chip whose nonce got out of sync with the off-chain
+    /// tooling. Lowering the nonce re-enables old signatures, hence it
+    /// requires `force`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    /// * `new_nonce` - The nonce to store.
+    /// * `force` - Allow `new_nonce` to be lower than the stored nonce.
+    ///
+    /// # Panics
+    ///
+    /// * If the caller is not the admin.
+    /// * If `new_nonce` is lower than the stored nonce and `force` is not set.
+    ///
+    /// # Events
+    ///
+    /// * topics - `["nonce_reset", public_key: BytesN<65>]`
+    /// * data - `[old_nonce: u32, new_nonce: u32]`
+    fn reset_nonce(e: &Env, public_key: BytesN<65>, new_nonce: u32, force: bool);
+
+    /// Returns the last nonce used by the given `public_key`.
+    ///
+    /// The next signature of the chip must use this nonce plus one.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's public key (SEC1 format, compressed 33 bytes or uncompressed
+    ///   65 bytes, or ed25519 32 bytes).
+    ///
+    /// # Returns
+    ///
+    /// The last used nonce for this chip's public_key (defaults to 0 if not set).
+    fn get_nonce(e: &Env, public_key: Bytes) -> u32;
+
+    /// Returns the last nonce used by each of `public_keys`, in input order.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_keys` - The chips' public keys (uncompressed SEC1 format, 65 bytes).
+    ///
+    /// # Returns
+    ///
+    /// The last used nonce of each chip (0 for unknown chips).
+    fn get_nonces(e: &Env, public_keys: Vec<BytesN<65>>) -> Vec<u32>;
+
+    /// Returns whether `nonce` can no longer be signed by the chip.
+    ///
+    /// Nonces strictly increase, so this holds for every nonce up to the one
+    /// returned by `get_nonce`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's public key (SEC1 format, compressed 33 bytes or uncompressed
+    ///   65 bytes, or ed25519 32 bytes).
+    /// * `nonce` - The nonce to check.
+    ///
+    /// # Panics
+    ///
+    /// * If `public_key` has an invalid length ([`errors::NonFungibleTokenError::InvalidPublicKey`]).
+    fn is_nonce_used(e: &Env, public_key: Bytes, nonce: u32) -> bool;
+
+    /// Returns the number of tokens in `owner`'s account.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `owner` - Account of the token's owner.
+    fn balance(e: &Env, owner: Address) -> u32;
+
+    /// Returns all the tokens owned by `owner`.
+    ///
+    /// Tokens claimed before the owner lists existed are only listed once they
+    /// are moved or indexed with `enumerate_tokens`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `owner` - Account of the token's owner.
+    fn tokens_of_owner(e: &Env, owner: Address) -> Vec<u32>;
+
+    /// Returns the token at `index` in the list of tokens owned by `owner`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `owner` - Account of the token's owner.
+    /// * `index` - Index in the owner's token list (lower than the length of
+    ///   `tokens_of_owner`).
+    ///
+    /// # Panics
+    ///
+    /// * If `index` is out of bounds.
+    fn token_of_owner_by_index(e: &Env, owner: Address, index: u32) -> u32;
+
+    /// Add claimed tokens to their owner's token list, for tokens claimed
+    /// before the lists existed. Tokens which are unclaimed or already listed
+    /// are skipped, so anyone can call it.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_ids` - Token ids to index.
+    fn enumerate_tokens(e: &Env, token_ids: Vec<u32>);
+
+    /// Returns minted token ids in order, starting at `start`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `start` - First token id to consider.
+    /// * `limit` - Maximum number of token ids to return.
+    ///
+    /// # Returns
+    ///
+    /// Up to `limit` token ids. Empty if `limit` is 0 or `start` is past the
+    /// last minted token.
+    ///
+    /// # Panics
+    ///
+    /// * If `limit` is above [`MAX_TOKENS_PAGE`] ([`errors::NonFungibleTokenError::InvalidInput`]).
+    fn tokens(e: &Env, start: u32, limit: u32) -> Vec<u32>;
+
+    /// Returns minted token ids which were not claimed yet, starting at `start`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `start` - First token id to consider.
+    /// * `limit` - Maximum number of token ids to look at.
+    ///
+    /// # Returns
+    ///
+    /// The unclaimed token ids among the `limit` ids from `start`, so a page
+    /// can hold fewer than `limit` ids while later pages still have some.
+    ///
+    /// # Panics
+    ///
+    /// * If `limit` is above [`MAX_TOKENS_PAGE`] ([`errors::NonFungibleTokenError::InvalidInput`]).
+    fn unclaimed_tokens(e: &Env, start: u32, limit: u32) -> Vec<u32>;
+
+    /// Returns minted token ids with their owner, starting at `start`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `start` - First token id to consider.
+    /// * `limit` - Maximum number of tokens to return.
+    ///
+    /// # Returns
+    ///
+    /// Up to `limit` `(token_id, owner)` pairs, the owner being `None` for
+    /// unclaimed tokens.
+    ///
+    /// # Panics
+    ///
+    /// * If `limit` is above [`MAX_TOKENS_PAGE`] ([`errors::NonFungibleTokenError::InvalidInput`]).
+    fn tokens_detailed(e: &Env, start: u32, limit: u32) -> Vec<(u32, Option<Address>)>;
+
+    /// Returns claimed token ids with their owner, starting at `start_token`.
+    ///
+    /// Meant for owner snapshots, e.g. airdrops: page through the ids with
+    /// `start_token` advancing by `limit` until `next_token_id`, within a
+    /// single ledger for a consistent snapshot. Each id costs one storage
+    /// read, so a page looks at no more than [`MAX_OWNERS_PAGE`] ids.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `start_token` - First token id to consider.
+    /// * `limit` - Maximum number of token ids to look at.
+    ///
+    /// # Returns
+    ///
+    /// The `(token_id, owner)` pairs of the claimed tokens among the `limit`
+    /// ids from `start_token`.
+    ///
+    /// # Panics
+    ///
+    /// * If `limit` is above [`MAX_OWNERS_PAGE`] ([`errors::NonFungibleTokenError::InvalidInput`]).
+    fn owners(e: &Env, start_token: u32, limit: u32) -> Vec<(u32, Address)>;
+
+    /// Returns the address of the owner of the given `token_id`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist ([`errors::NonFungibleTokenError::NonExistentToken`]).
+    /// * If the token was not claimed yet ([`errors::NonFungibleTokenError::TokenNotClaimed`]).
+    fn owner_of(e: &Env, token_id: u32) -> Address;
+
+    /// Returns the owners of the given `token_ids`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_ids` - Token ids, duplicates are allowed.
+    ///
+    /// # Returns
+    ///
+    /// The owners in the order of `token_ids`, `None` for unclaimed or
+    /// non-existent tokens.
+    fn owners_of(e: &Env, token_ids: Vec<u32>) -> Vec<Option<Address>>;
+
+    /// Returns the number of tokens of each of the given `owners`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `owners` - Accounts to query, duplicates are allowed.
+    ///
+    /// # Returns
+    ///
+    /// The balances in the order of `owners`.
+    fn balances_of(e: &Env, owners: Vec<Address>) -> Vec<u32>;
+
+    /// Returns whether `token_id` token was minted.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    fn is_minted(e: &Env, token_id: u32) -> bool;
+
+    /// Returns whether `token_id` token was claimed.
+    ///
+    /// Quarantined tokens count as claimed.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    fn is_claimed(e: &Env, token_id: u32) -> bool;
+
+    /// Returns the lifecycle status of `token_id` token.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The [`TokenStatus`], `NotMinted` for unknown tokens.
+    fn token_status(e: &Env, token_id: u32) -> TokenStatus;
+
+    /// Returns the owner, chip, mint and claim details of `token_id` token in one call.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The [`TokenInfo`], the claim fields are `None` until the token is claimed.
+    /// Tokens minted before mint ledgers were recorded report ledger and timestamp `0`.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist ([`errors::NonFungibleTokenError::NonExistentToken`]).
+    fn token_info(e: &Env, token_id: u32) -> TokenInfo;
+
+    /// Returns the last ledger `token_id` token can be claimed at.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The deadline or `None` if the token was minted without a claim window.
+    fn claim_deadline(e: &Env, token_id: u32) -> Option<u32>;
+
+    /// Returns whether `token_id` token was frozen by the admin.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    fn is_frozen(e: &Env, token_id: u32) -> bool;
+
+    /// Returns whether the chip with `public_key` is blocked.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    fn is_chip_blocked(e: &Env, public_key: BytesN<65>) -> bool;
+
+    /// Returns the token and owner of the chip with `public_key` in one call.
+    ///
+    /// Never panics, unknown chips resolve to no token and no owner.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    ///
+    /// # Returns
+    ///
+    /// The [`ChipResolution`], `owner` is `None` until the token is claimed.
+    fn resolve_chip(e: &Env, public_key: BytesN<65>) -> ChipResolution;
+
+    /// Returns whether the chip with `public_key` is registered.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's public key (uncompressed SEC1 format, 65 bytes).
+    fn is_chip_registered(e: &Env, public_key: BytesN<65>) -> bool;
+
+    /// Returns the last ownership changes of `token_id` token, oldest first.
+    ///
+    /// Claims, transfers, clawbacks and releases are recorded, claims coming from
+    /// the contract address. Only the last `provenance_limit` changes given to the
+    /// constructor are kept.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist ([`errors::NonFungibleTokenError::NonExistentToken`]).
+    fn provenance(e: &Env, token_id: u32) -> Vec<ProvenanceEntry>;
+
+    /// Returns whether tokens of the collection can never be transferred.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn is_soulbound(e: &Env) -> bool;
+
+    /// Returns the royalty owed for a sale of `token_id` token.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    /// * `sale_price` - Price the token is sold for.
+    ///
+    /// # Returns
+    ///
+    /// The receiver and the royalty amount, rounded down. The contract itself
+    /// with an amount of 0 if no royalty was set for the token nor by default.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist ([`errors::NonFungibleTokenError::NonExistentToken`]).
+    fn royalty_info(e: &Env, token_id: u32, sale_price: i128) -> (Address, i128);
+
+    /// Returns the token collection name.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn name(e: &Env) -> String;
+
+    /// Returns the token collection symbol.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn symbol(e: &Env) -> String;
+
+    /// Returns the base URI used to build token URIs.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn base_uri(e: &Env) -> String;
+
+    /// Returns the collection-level metadata URI.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    ///
+    /// # Returns
+    ///
+    /// The URI, or an empty string if it was never set.
+    fn contract_uri(e: &Env) -> String;
+
+    /// Returns the maximum number of tokens that can be minted.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn max_tokens(e: &Env) -> u32;
+
+    /// Returns the id of the first token, set at deployment. Ids below it are
+    /// never minted.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn first_token_id(e: &Env) -> u32;
+
+    /// Returns the Uniform Resource Identifier (URI) for `token_id` token:
+    /// `{base_uri}/{token_id}{suffix}`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist ([`errors::NonFungibleTokenError::NonExistentToken`]).
+    fn token_uri(e: &Env, token_id: u32) -> String;
+
+    /// Returns the token ID for the given chip public key.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `public_key` - The chip's public key (SEC1 format, compressed 33 bytes or uncompressed
+    ///   65 bytes, or ed25519 32 bytes).
+    ///
+    /// # Returns
+    ///
+    /// The token ID associated with this public key.
+    ///
+    /// # Panics
+    ///
+    /// * If no token was minted for the chip ([`errors::NonFungibleTokenError::NonExistentToken`]).
+    /// * If the public key has an invalid length ([`errors::NonFungibleTokenError::InvalidPublicKey`]).
+    fn token_id(e: &Env, public_key: Bytes) -> u32;
+
+    /// Returns the next token ID to mint.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    ///
+    /// # Returns
+    ///
+    /// The next token ID in the enumeration.
+    fn next_token_id(e: &Env) -> u32;
+
+    /// Returns the number of minted tokens.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn total_supply(e: &Env) -> u32;
+
+    /// Returns the number of claimed tokens.
+    ///
+    /// Clawbacks do not change this count.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn claimed_count(e: &Env) -> u32;
+
+    /// Returns the token at `index` in the list of claimed tokens, in claim order.
+    ///
+    /// Clawed back tokens keep their index.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `index` - Index in the claimed token list (lower than `claimed_count`).
+    ///
+    /// # Panics
+    ///
+    /// * If `index` is out of bounds.
+    fn claimed_token_by_index(e: &Env, index: u32) -> u32;
+
+    /// Returns the number of minted tokens which were not claimed yet.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    fn unclaimed_count(e: &Env) -> u32;
+
+    /// Returns the chip public key for the given token ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The chip's public key associated with this token ID. Ed25519 keys are
+    /// returned as `0xED || key || zero padding`, see `chip_key`.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist ([`errors::NonFungibleTokenError::NonExistentToken`]).
+    fn public_key(e: &Env, token_id: u32) -> BytesN<65>;
+
+    /// Returns the curve of the chip public key for the given token ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// `CurveType::Secp256r1` for tokens minted with `mint_r1`, `CurveType::Ed25519`
+    /// for tokens minted with `mint_ed25519`, otherwise `CurveType::Secp256k1`.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist.
+    fn curve(e: &Env, token_id: u32) -> CurveType;
+
+    /// Returns the chip public key for the given token ID with its curve.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    ///
+    /// # Returns
+    ///
+    /// The chip's public key, 32 bytes for ed25519 chips.
+    ///
+    /// # Panics
+    ///
+    /// * If the token does not exist.
+    fn chip_key(e: &Env, token_id: u32) -> ChipKey;
+
+    /// Check a chip signature without consuming its nonce.
+    ///
+    /// Performs the same recovery and comparison as `mint`, `claim` and `transfer`
+    /// but never writes storage, so a signature can be dry-run before being submitted
+    /// and other contracts can check chip signatures for their own actions.
+    ///
+    /// The signed hash is `sha256(message || signer || nonce || action || contract ||
+    /// network_id [|| expiration_ledger])`, so a signature is only valid for one action
+    /// of this contract on this network. The action is hashed as a single byte.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `signer` - The signer bytes hashed into the message, the XDR of an address.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `public_key` - The chip's secp256k1 public key, 65 bytes uncompressed or 33 bytes compressed.
+    /// * `nonce` - The next nonce of the chip, see `get_nonce`.
+    /// * `action` - `ACTION_MINT`, `ACTION_CLAIM`, `ACTION_TRANSFER`, `ACTION_ROTATE`,
+    ///   `ACTION_BACKUP`, 3 and values from 6 to 255 are free for other contracts.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the signature recovers to `public_key`, the chip is not blocked,
+    /// the signature did not expire and `nonce` is the stored nonce plus one.
+    ///
+    /// # Panics
+    ///
+    /// * If the r value of the signature is not the x coordinate of a curve
+    ///   point, as the host fails the recovery.
+    #[allow(clippy::too_many_arguments)]
+    fn check_chip_signature(
+        e: &Env,
+        signer: Bytes,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        action: u32,
+        expiration_ledger: u32,
+    ) -> bool;
+
+    /// Verify a chip signature for an action of another contract and consume
+    /// its nonce, so the signature cannot be used again.
+    ///
+    /// Meant for contracts acting on chip signatures, e.g. prize redemptions,
+    /// while `check_chip_signature` is the read-only dry run. The signature
+    /// is made over the XDR of `signer`, who has to authorize the call, so
+    /// nobody else can burn the nonce.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `signer` - The address the signature is made over, e.g. the redeemer.
+    /// * `message` - The message that was signed (without signer and nonce).
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    /// * `public_key` - The chip's secp256k1 public key, 65 bytes uncompressed or 33 bytes compressed.
+    /// * `nonce` - The next nonce of the chip, see `get_nonce`.
+    /// * `action` - 3 or a value from 6 to 255.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Panics
+    ///
+    /// * If `action` is an action of this contract or does not fit in a byte
+    ///   ([`errors::NonFungibleTokenError::InvalidAction`]).
+    /// * If the signature is invalid, expired or from a blocked chip.
+    /// * If the nonce was already used ([`errors::NonFungibleTokenError::NonceAlreadyUsed`])
+    ///   or skips ahead ([`errors::NonFungibleTokenError::InvalidNonce`]).
+    #[allow(clippy::too_many_arguments)]
+    fn consume_chip_signature(
+        e: &Env,
+        signer: Address,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+        public_key: Bytes,
+        nonce: u32,
+        action: u32,
+        expiration_ledger: u32,
+    );
+
+    /// Returns the digest a chip has to sign for an action of this contract.
+    ///
+    /// Clients should sign this hash rather than re-implementing the XDR
+    /// encoding of `sha256(message || signer || nonce || action || contract ||
+    /// network_id [|| expiration_ledger])`.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `signer` - The address the signature is made over, e.g. the claimant.
+    /// * `message` - The message to sign (without signer and nonce).
+    /// * `nonce` - The next nonce of the chip, see `get_nonce`.
+    /// * `action` - `ACTION_MINT`, `ACTION_CLAIM`, `ACTION_TRANSFER`, `ACTION_ROTATE` or
+    ///   `ACTION_BACKUP`.
+    /// * `expiration_ledger` - Last ledger the signature is valid at, `0` never expires.
+    ///
+    /// # Panics
+    ///
+    /// * If `action` does not fit in a byte ([`errors::NonFungibleTokenError::InvalidAction`]).
+    fn message_hash(
+        e: &Env,
+        signer: Address,
+        message: Bytes,
+        nonce: u32,
+        action: u32,
+        expiration_ledger: u32,
+    ) -> BytesN<32>;
+
+    /// Check that a chip is the chip of `token_id`, e.g. for an authenticity
+    /// tap at a point of sale.
+    ///
+    /// The chip signs `sha256(message)` directly, without signer, nonce or
+    /// domain, and no storage is written. Verifiers should use a fresh random
+    /// challenge as `message` so an old signature cannot be presented again.
+    ///
+    /// # Arguments
+    ///
+    /// * `e` - The environment object.
+    /// * `token_id` - Token id as a number.
+    /// * `message` - The challenge signed by the chip.
+    /// * `signature` - 64-byte ECDSA signature from NFC chip.
+    /// * `recovery_id` - Recovery ID (0 or 1) for signature recovery, `RECOVERY_ID_AUTO` to try 0 and 1.
+    ///
+    /// # Returns
+    ///
+    /// `true` if the signature recovers to the public key of the token, `false`
+    /// otherwise and for tokens which do not exist or are not secp256k1 chips.
+    ///
+    /// # Panics
+    ///
+    /// * If the r value of the signature is not the x coordinate of a curve
+    ///   point, as the host fails the recovery.
+    fn verify_presence(
+        e: &Env,
+        token_id: u32,
+        message: Bytes,
+        signature: BytesN<64>,
+        recovery_id: u32,
+    ) -> bool;
 }
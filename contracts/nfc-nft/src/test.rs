@@ -12,7 +12,9 @@
 //!
 //! ## Important Notes
 //!
-//! - Message hash = SHA256(message_bytes || signer.to_xdr() || nonce.to_xdr())
+//! - Fixture hash = SHA256(message_bytes || signer.to_xdr() || nonce.to_xdr())
+//! - The contract additionally hashes action byte || contract.to_xdr() || network_id [|| expiration_ledger.to_xdr()],
+//!   so the fixtures only cover the chip signature format; contract flows use software chips
 //! - Soroban's to_xdr() for u32 uses type tag 0x00000003, NOT 0x00000004
 //! - Signatures must have S normalized (low S form) for Soroban's secp256k1_recover
 //! - The normalize_s() function handles this automatically
@@ -25,10 +27,24 @@ extern crate std;
 use alloc::format;
 use alloc::vec::Vec;
 
+use ed25519_dalek::Signer;
+use k256::ecdsa::SigningKey;
+use p256::ecdsa::signature::hazmat::PrehashSigner;
 use soroban_sdk::xdr::ToXdr;
-use soroban_sdk::{Address, Bytes, BytesN, Env, String, crypto::Hash, testutils::Address as _};
+use soroban_sdk::{
+    Address, Bytes, BytesN, Env, Event, IntoVal, Map, String, Symbol, contract, contractimpl,
+    crypto::Hash,
+    testutils::{Address as _, Events, Ledger, MockAuth, MockAuthInvoke, storage::Persistent as _},
+    token, vec,
+};
 
-use crate::{NFCtoNFT, NFCtoNFTClient};
+use crate::{
+    ACTION_BACKUP, ACTION_CLAIM, ACTION_MINT, ACTION_ROTATE, ACTION_TRANSFER, ALL_TOKENS,
+    AdminAction, CONTRACT_VERSION, ChipKey, ChipResolution, ClaimRequest, CurveType, MAX_BATCH_LEN,
+    MAX_CHIP_URL_LEN, MAX_OWNERS_PAGE, MAX_TOKENS_PAGE, MintFee, MintRequest, NFCtoNFT,
+    NFCtoNFTClient, PROPOSAL_LIFETIME, ProvenanceEntry, RECOVERY_ID_AUTO, STORAGE_VERSION,
+    SignedAction, TokenInfo, TokenMetadata, TokenStatus, errors, events,
+};
 
 struct TestSignature {
     nonce: u32,
@@ -40,6 +56,15 @@ struct TestSignature {
 
 const TEST_MESSAGE: &[u8] = b"test message for minting";
 
+// Budget ceilings of the hot entry points, about 10% above the native test
+// host cost, raise them only knowingly
+const MINT_CPU_LIMIT: u64 = 3_700_000;
+const MINT_MEM_LIMIT: u64 = 330_000;
+const CLAIM_CPU_LIMIT: u64 = 3_950_000;
+const CLAIM_MEM_LIMIT: u64 = 410_000;
+const TRANSFER_CPU_LIMIT: u64 = 3_950_000;
+const TRANSFER_MEM_LIMIT: u64 = 410_000;
+
 // Public keys recovered from signatures (empirically: Chip 1 from sigs 0,1,2 with normalized S; Chip 2 from sigs 3,4)
 const CHIP1_PUBLIC_KEY: [u8; 65] = [
     0x04, 0xbd, 0xc2, 0x5d, 0x45, 0x2c, 0xaf, 0xaa, 0x18, 0x2b, 0x6b, 0x5e, 0x68, 0xbe, 0xe9, 0xf2,
@@ -226,8 +251,43 @@ fn create_test_signature_and_recovery_id(
     panic!("No valid recovery ID found for test signature");
 }
 
-// Helper function to calculate message hash exactly as contract does (message || signer || nonce)
-fn calculate_message_hash(e: &Env, message: &[u8], signer: &Address, nonce: u32) -> Hash<32> {
+// Helper function to calculate message hash of the hardware fixtures
+// (message || signer || nonce), signed before domain separation
+fn calculate_fixture_message_hash(
+    e: &Env,
+    message: &[u8],
+    signer: &Address,
+    nonce: u32,
+) -> Hash<32> {
+    let mut builder = Bytes::from_slice(e, message);
+    builder.append(&signer.to_xdr(e));
+    builder.append(&nonce.to_xdr(e));
+    e.crypto().sha256(&builder)
+}
+
+// Helper function to calculate message hash of a signature that never expires
+fn calculate_message_hash(
+    e: &Env,
+    contract: &Address,
+    message: &[u8],
+    signer: &Address,
+    nonce: u32,
+    action: u32,
+) -> Hash<32> {
+    calculate_message_hash_with_expiration(e, contract, message, signer, nonce, action, 0)
+}
+
+// Helper function to calculate message hash exactly as contract does
+// (message || signer || nonce || action || contract || network_id [|| expiration_ledger])
+fn calculate_message_hash_with_expiration(
+    e: &Env,
+    contract: &Address,
+    message: &[u8],
+    signer: &Address,
+    nonce: u32,
+    action: u32,
+    expiration_ledger: u32,
+) -> Hash<32> {
     let message_bytes = Bytes::from_slice(e, message);
     let signer_xdr = signer.to_xdr(e);
     let nonce_xdr = nonce.to_xdr(e);
@@ -235,10 +295,17 @@ fn calculate_message_hash(e: &Env, message: &[u8], signer: &Address, nonce: u32)
     builder.append(&message_bytes);
     builder.append(&signer_xdr);
     builder.append(&nonce_xdr);
+    builder.push_back(action as u8);
+    builder.append(&contract.to_xdr(e));
+    builder.append(&e.ledger().network_id().into());
+    if expiration_ledger != 0 {
+        builder.append(&expiration_ledger.to_xdr(e));
+    }
     e.crypto().sha256(&builder)
 }
 
-// Helper function to print message hash for manual signing (new formula: message || signer || nonce)
+// Helper function to print the fixture message hash for manual signing
+// (message || signer || nonce)
 fn print_message_hash_for_signing_with_signer(
     e: &Env,
     message: &[u8],
@@ -246,15 +313,7 @@ fn print_message_hash_for_signing_with_signer(
     nonce: u32,
     label: &str,
 ) {
-    let message_bytes = Bytes::from_slice(e, message);
-    let signer_xdr = signer.to_xdr(e);
-    let nonce_xdr = nonce.to_xdr(e);
-
-    let mut builder = Bytes::new(e);
-    builder.append(&message_bytes);
-    builder.append(&signer_xdr);
-    builder.append(&nonce_xdr);
-    let message_hash = e.crypto().sha256(&builder);
+    let message_hash = calculate_fixture_message_hash(e, message, signer, nonce);
 
     let hash_bytes: BytesN<32> = message_hash.clone().into();
     let hash_array = hash_bytes.to_array();
@@ -270,6 +329,11 @@ fn print_message_hash_for_signing_with_signer(
     std::println!();
 }
 
+// Message bytes of a SignedAction payload, its XDR encoding
+fn signed_action_bytes(e: &Env, payload: &SignedAction) -> Vec<u8> {
+    payload.clone().to_xdr(e).iter().collect()
+}
+
 // Helper function to parse DER signature and extract R and S
 // DER format: 0x30 [length] 0x02 [R length] [R bytes] 0x02 [S length] [S bytes]
 fn parse_der_signature(der_hex: &str) -> ([u8; 32], [u8; 32]) {
@@ -434,6 +498,34 @@ fn print_message_hash_for_signing() {
         "Hash 5 - Chip 2, nonce 4 (claim): sign with Chip 2",
     );
 
+    // SignedAction payloads of the v2 entry points replace the message by their XDR
+    let payload = SignedAction {
+        action: Symbol::new(&e, "claim"),
+        token_id: Some(0),
+        to: None,
+        memo: None,
+    };
+    print_message_hash_for_signing_with_signer(
+        &e,
+        &signed_action_bytes(&e, &payload),
+        &claimant,
+        2,
+        "SignedAction claim of token 0 - Chip 1, nonce 2 (claim_v2): sign with Chip 1",
+    );
+    let payload = SignedAction {
+        action: Symbol::new(&e, "transfer"),
+        token_id: Some(0),
+        to: Some(addr_3rd.clone()),
+        memo: None,
+    };
+    print_message_hash_for_signing_with_signer(
+        &e,
+        &signed_action_bytes(&e, &payload),
+        &claimant,
+        3,
+        "SignedAction transfer of token 0 to the 3rd addr - Chip 1, nonce 3 (transfer_v2): sign with Chip 1",
+    );
+
     std::println!("=== End of Message Hashes ===\n");
     std::println!("Sign each message_hash above with the indicated chip:");
     std::println!(
@@ -458,6 +550,29 @@ mod collection {
 }
 
 fn create_client<'a>(e: &Env, admin: &Address) -> NFCtoNFTClient<'a> {
+    create_client_with_max_tokens(e, admin, 10_000)
+}
+
+fn create_client_with_max_tokens<'a>(
+    e: &Env,
+    admin: &Address,
+    max_tokens: u32,
+) -> NFCtoNFTClient<'a> {
+    deploy_client(e, admin, max_tokens, false, 0, 0)
+}
+
+fn create_soulbound_client<'a>(e: &Env, admin: &Address) -> NFCtoNFTClient<'a> {
+    deploy_client(e, admin, 10_000, true, 0, 0)
+}
+
+fn deploy_client<'a>(
+    e: &Env,
+    admin: &Address,
+    max_tokens: u32,
+    soulbound: bool,
+    provenance_limit: u32,
+    first_token_id: u32,
+) -> NFCtoNFTClient<'a> {
     let collection_id = e.register(collection::Mock, ());
 
     let address = e.register(
@@ -468,12 +583,273 @@ fn create_client<'a>(e: &Env, admin: &Address) -> NFCtoNFTClient<'a> {
             &String::from_str(e, "TestNFT"),
             &String::from_str(e, "TNFT"),
             &String::from_str(e, "ipfs://abcd"),
-            &10_000u32, // max_tokens
+            &max_tokens,
+            &soulbound,
+            &provenance_limit,
+            &first_token_id,
         ),
     );
     NFCtoNFTClient::new(e, &address)
 }
 
+// Software chips: deterministic secp256k1 keys signing the domain separated
+// payload of the contract under test. The hardware fixtures above predate it.
+fn software_chip(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32].into()).unwrap()
+}
+
+fn software_chip_public_key(e: &Env, chip: &SigningKey) -> BytesN<65> {
+    let point = chip.verifying_key().to_encoded_point(false);
+    BytesN::from_array(e, point.as_bytes().try_into().unwrap())
+}
+
+// SEC1 compressed form of an uncompressed public key
+fn compressed_public_key(e: &Env, public_key: &[u8; 65]) -> Bytes {
+    let point = k256::ecdsa::VerifyingKey::from_sec1_bytes(public_key)
+        .unwrap()
+        .to_encoded_point(true);
+    Bytes::from_slice(e, point.as_bytes())
+}
+
+fn software_chip_sign(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    chip: &SigningKey,
+    signer: &Address,
+    nonce: u32,
+    action: u32,
+) -> (BytesN<64>, u32) {
+    software_chip_sign_with_expiration(e, client, chip, signer, nonce, action, 0)
+}
+
+fn software_chip_sign_with_expiration(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    chip: &SigningKey,
+    signer: &Address,
+    nonce: u32,
+    action: u32,
+    expiration_ledger: u32,
+) -> (BytesN<64>, u32) {
+    let message_hash = calculate_message_hash_with_expiration(
+        e,
+        &client.address,
+        TEST_MESSAGE,
+        signer,
+        nonce,
+        action,
+        expiration_ledger,
+    );
+    let (signature, recovery_id) = chip
+        .sign_prehash_recoverable(&message_hash.to_array())
+        .unwrap();
+    let signature_bytes: [u8; 64] = signature.to_bytes().into();
+    (
+        BytesN::from_array(e, &signature_bytes),
+        recovery_id.to_byte() as u32,
+    )
+}
+
+// Software P-256 chips for the secp256r1 entry points
+fn software_r1_chip(seed: u8) -> p256::ecdsa::SigningKey {
+    p256::ecdsa::SigningKey::from_bytes(&[seed; 32].into()).unwrap()
+}
+
+fn software_r1_chip_public_key(e: &Env, chip: &p256::ecdsa::SigningKey) -> BytesN<65> {
+    let point = chip.verifying_key().to_encoded_point(false);
+    BytesN::from_array(e, point.as_bytes().try_into().unwrap())
+}
+
+fn software_r1_chip_sign(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    chip: &p256::ecdsa::SigningKey,
+    signer: &Address,
+    nonce: u32,
+    action: u32,
+) -> BytesN<64> {
+    let message_hash =
+        calculate_message_hash(e, &client.address, TEST_MESSAGE, signer, nonce, action);
+    let signature: p256::ecdsa::Signature = chip.sign_prehash(&message_hash.to_array()).unwrap();
+    let signature = signature.normalize_s().unwrap_or(signature);
+    let signature_bytes: [u8; 64] = signature.to_bytes().into();
+    BytesN::from_array(e, &signature_bytes)
+}
+
+// Software ed25519 chips for the ed25519 entry points
+fn software_ed25519_chip(seed: u8) -> ed25519_dalek::SigningKey {
+    ed25519_dalek::SigningKey::from_bytes(&[seed; 32])
+}
+
+fn software_ed25519_chip_public_key(e: &Env, chip: &ed25519_dalek::SigningKey) -> BytesN<32> {
+    BytesN::from_array(e, &chip.verifying_key().to_bytes())
+}
+
+fn software_ed25519_chip_sign(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    chip: &ed25519_dalek::SigningKey,
+    signer: &Address,
+    nonce: u32,
+    action: u32,
+) -> BytesN<64> {
+    let message_hash =
+        calculate_message_hash(e, &client.address, TEST_MESSAGE, signer, nonce, action);
+    BytesN::from_array(e, &chip.sign(&message_hash.to_array()).to_bytes())
+}
+
+fn software_chip_mint_request(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    minter: &Address,
+    chip: &SigningKey,
+    nonce: u32,
+) -> MintRequest {
+    let (signature, recovery_id) = software_chip_sign(e, client, chip, minter, nonce, ACTION_MINT);
+    MintRequest {
+        message: Bytes::from_slice(e, TEST_MESSAGE),
+        signature,
+        recovery_id,
+        public_key: software_chip_public_key(e, chip),
+        nonce,
+    }
+}
+
+fn software_chip_mint(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    admin: &Address,
+    chip: &SigningKey,
+    nonce: u32,
+) -> u32 {
+    let (signature, recovery_id) = software_chip_sign(e, client, chip, admin, nonce, ACTION_MINT);
+    client.mint(
+        admin,
+        &Bytes::from_slice(e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(e, chip).into(),
+        &nonce,
+        &0,
+    )
+}
+
+fn software_chip_claim(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    claimant: &Address,
+    chip: &SigningKey,
+    nonce: u32,
+) -> u32 {
+    let (signature, recovery_id) =
+        software_chip_sign(e, client, chip, claimant, nonce, ACTION_CLAIM);
+    client.claim(
+        claimant,
+        &Bytes::from_slice(e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(e, chip).into(),
+        &nonce,
+        &0,
+    )
+}
+
+fn software_chip_transfer(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    from: &Address,
+    to: &Address,
+    chip: &SigningKey,
+    nonce: u32,
+) {
+    let token_id = client.token_id(&software_chip_public_key(e, chip).into());
+    let (signature, recovery_id) =
+        software_chip_sign(e, client, chip, from, nonce, ACTION_TRANSFER);
+    client.transfer(
+        from,
+        to,
+        &token_id,
+        &Bytes::from_slice(e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(e, chip).into(),
+        &nonce,
+        &0,
+    );
+}
+
+fn software_chip_safe_transfer(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    from: &Address,
+    to: &Address,
+    chip: &SigningKey,
+    nonce: u32,
+) -> Result<(), Result<soroban_sdk::Error, soroban_sdk::InvokeError>> {
+    let token_id = client.token_id(&software_chip_public_key(e, chip).into());
+    let (signature, recovery_id) =
+        software_chip_sign(e, client, chip, from, nonce, ACTION_TRANSFER);
+    client
+        .try_safe_transfer(
+            from,
+            to,
+            &token_id,
+            &Bytes::from_slice(e, TEST_MESSAGE),
+            &signature,
+            &recovery_id,
+            &software_chip_public_key(e, chip).into(),
+            &nonce,
+            &0,
+        )
+        .map(|_| ())
+}
+
+// Receivers for `safe_transfer`
+#[contract]
+struct AcceptingReceiver;
+
+#[contractimpl]
+impl AcceptingReceiver {
+    pub fn on_nft_received(_operator: Address, _from: Address, _token_id: u32) -> bool {
+        true
+    }
+}
+
+#[contract]
+struct RejectingReceiver;
+
+#[contractimpl]
+impl RejectingReceiver {
+    pub fn on_nft_received(_operator: Address, _from: Address, _token_id: u32) -> bool {
+        false
+    }
+}
+
+fn software_chip_transfer_from(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    spender: &Address,
+    from: &Address,
+    to: &Address,
+    chip: &SigningKey,
+    nonce: u32,
+) {
+    let token_id = client.token_id(&software_chip_public_key(e, chip).into());
+    let (signature, recovery_id) =
+        software_chip_sign(e, client, chip, spender, nonce, ACTION_TRANSFER);
+    client.transfer_from(
+        spender,
+        from,
+        to,
+        &token_id,
+        &Bytes::from_slice(e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(e, chip),
+        &nonce,
+    );
+}
+
 #[test]
 fn test_metadata() {
     let e = Env::default();
@@ -487,6 +863,10 @@ fn test_metadata() {
 
     let symbol = client.symbol();
     assert_eq!(symbol, String::from_str(&e, "TNFT"));
+
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.max_tokens(), 10_000u32);
+    assert_eq!(client.base_uri(), String::from_str(&e, "ipfs://abcd"));
 }
 
 #[test]
@@ -497,39 +877,55 @@ fn test_claim() {
     let admin = Address::generate(&e);
     let claimant = Address::generate(&e);
     let client = create_client(&e, &admin);
+    let chip_1 = software_chip(1);
 
     // Chip 1, nonce 1 (mint)
-    let mint_sig = &TEST_SIGNATURES[0];
-    let mint_message_hash = calculate_message_hash(&e, mint_sig.message, &admin, mint_sig.nonce);
     let (mint_signature, mint_recovery_id) =
-        create_test_signature_and_recovery_id(&e, &mint_message_hash, mint_sig);
-    let message = Bytes::from_slice(&e, mint_sig.message);
-    let public_key = BytesN::from_array(&e, &mint_sig.public_key);
+        software_chip_sign(&e, &client, &chip_1, &admin, 1, ACTION_MINT);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let public_key = software_chip_public_key(&e, &chip_1);
 
     let token_id = client.mint(
+        &admin,
         &message,
         &mint_signature,
         &mint_recovery_id,
-        &public_key,
-        &mint_sig.nonce,
+        &public_key.clone().into(),
+        &1,
+        &0,
     );
     assert_eq!(token_id, 0u32);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::Mint {
+            to: client.address.clone(),
+            token_id,
+            public_key: public_key.clone(),
+        }
+        .to_xdr(&e, &client.address)]
+    );
 
     // Verify token is unclaimed after mint
-    let owner_result =
-        std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| client.owner_of(&token_id)));
-    assert!(
-        owner_result.is_err(),
+    assert_eq!(
+        client.try_owner_of(&token_id),
+        Err(Ok(errors::NonFungibleTokenError::TokenNotClaimed.into())),
         "Token should be unclaimed after mint"
     );
 
+    // Lookups of tokens that were never minted fail with NonExistentToken
+    let missing = errors::NonFungibleTokenError::NonExistentToken;
+    assert_eq!(client.try_owner_of(&1), Err(Ok(missing.into())));
+    assert_eq!(client.try_token_uri(&1), Err(Ok(missing.into())));
+    assert_eq!(client.try_public_key(&1), Err(Ok(missing.into())));
+    assert_eq!(
+        client.try_token_id(&software_chip_public_key(&e, &software_chip(2)).into()),
+        Err(Ok(missing.into()))
+    );
+
     // Chip 1, nonce 2 (claim)
-    let claim_sig = &TEST_SIGNATURES[1];
-    let claim_message_hash =
-        calculate_message_hash(&e, claim_sig.message, &claimant, claim_sig.nonce);
     let (claim_signature, claim_recovery_id) =
-        create_test_signature_and_recovery_id(&e, &claim_message_hash, claim_sig);
-    let message = Bytes::from_slice(&e, claim_sig.message);
+        software_chip_sign(&e, &client, &chip_1, &claimant, 2, ACTION_CLAIM);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
 
     // Claim the token
     let claimed_token_id = client.claim(
@@ -537,13 +933,31 @@ fn test_claim() {
         &message,
         &claim_signature,
         &claim_recovery_id,
-        &public_key,
-        &claim_sig.nonce,
+        &public_key.clone().into(),
+        &2,
+        &0,
     );
     assert_eq!(
         claimed_token_id, token_id,
         "Claim should return the same token ID"
     );
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [
+            events::Claim {
+                claimant: claimant.clone(),
+                token_id,
+                public_key: public_key.clone(),
+            }
+            .to_xdr(&e, &client.address),
+            events::Transfer {
+                from: client.address.clone(),
+                to: claimant.clone(),
+                token_id,
+            }
+            .to_xdr(&e, &client.address),
+        ]
+    );
 
     // Verify ownership was transferred
     let owner = client.owner_of(&token_id);
@@ -560,7 +974,24 @@ fn test_claim() {
     );
 
     // Verify clawback
-    client.clawback(&token_id);
+    client.clawback(&token_id, &0);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [
+            events::Clawback {
+                from: claimant.clone(),
+                token_id,
+                reason: 0,
+            }
+            .to_xdr(&e, &client.address),
+            events::Transfer {
+                from: claimant.clone(),
+                to: admin.clone(),
+                token_id,
+            }
+            .to_xdr(&e, &client.address),
+        ]
+    );
     let claimant_balance = client.balance(&claimant);
     assert_eq!(
         claimant_balance, 0u32,
@@ -574,26 +1005,77 @@ fn test_claim() {
 }
 
 #[test]
-#[should_panic]
 fn test_nonce_reuse_prevention() {
     let e = Env::default();
     e.mock_all_auths();
 
     let admin = Address::generate(&e);
     let client = create_client(&e, &admin);
+    let chip_1 = software_chip(1);
 
     // Chip 1, nonce 1
-    let sig = &TEST_SIGNATURES[0];
-    let message_hash = calculate_message_hash(&e, sig.message, &admin, sig.nonce);
-    let (signature, recovery_id) = create_test_signature_and_recovery_id(&e, &message_hash, sig);
-    let message = Bytes::from_slice(&e, sig.message);
-    let public_key = BytesN::from_array(&e, &sig.public_key);
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip_1, &admin, 1, ACTION_MINT);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let public_key = software_chip_public_key(&e, &chip_1);
 
     // First mint should succeed
-    let _token_id = client.mint(&message, &signature, &recovery_id, &public_key, &sig.nonce);
+    let _token_id = client.mint(
+        &admin,
+        &message,
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &1,
+        &0,
+    );
+
+    // Second mint with same nonce should fail (nonce reuse prevention)
+    let result = client.try_mint(
+        &admin,
+        &message,
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &1,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::NonceAlreadyUsed.into()))
+    );
+
+    // Nonces skipping ahead are rejected separately
+    let chip_2 = software_chip(2);
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip_2, &admin, 2, ACTION_MINT);
+    let result = client.try_mint(
+        &admin,
+        &message,
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip_2).into(),
+        &2,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::InvalidNonce.into()))
+    );
 
-    // Second mint with same nonce should panic (nonce reuse prevention)
-    client.mint(&message, &signature, &recovery_id, &public_key, &sig.nonce);
+    // Recovery ids above 1 are rejected before recovering
+    let (signature, _) = software_chip_sign(&e, &client, &chip_2, &admin, 1, ACTION_MINT);
+    let result = client.try_mint(
+        &admin,
+        &message,
+        &signature,
+        &2,
+        &software_chip_public_key(&e, &chip_2).into(),
+        &1,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::InvalidRecoveryId.into()))
+    );
 }
 
 #[test]
@@ -632,37 +1114,36 @@ fn test_transfer() {
     let claimant = Address::generate(&e);
     let recipient = Address::generate(&e);
     let client = create_client(&e, &admin);
+    let chip_1 = software_chip(1);
 
     // Chip 1, nonce 1 (mint)
-    let mint_sig = &TEST_SIGNATURES[0];
-    let mint_message_hash = calculate_message_hash(&e, mint_sig.message, &admin, mint_sig.nonce);
     let (mint_signature, mint_recovery_id) =
-        create_test_signature_and_recovery_id(&e, &mint_message_hash, mint_sig);
-    let message = Bytes::from_slice(&e, mint_sig.message);
-    let public_key = BytesN::from_array(&e, &mint_sig.public_key);
+        software_chip_sign(&e, &client, &chip_1, &admin, 1, ACTION_MINT);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let public_key = software_chip_public_key(&e, &chip_1);
     let token_id = client.mint(
+        &admin,
         &message,
         &mint_signature,
         &mint_recovery_id,
-        &public_key,
-        &mint_sig.nonce,
+        &public_key.clone().into(),
+        &1,
+        &0,
     );
     assert_eq!(token_id, 0u32);
 
     // Chip 1, nonce 2 (claim)
-    let claim_sig = &TEST_SIGNATURES[1];
-    let claim_message_hash =
-        calculate_message_hash(&e, claim_sig.message, &claimant, claim_sig.nonce);
     let (claim_signature, claim_recovery_id) =
-        create_test_signature_and_recovery_id(&e, &claim_message_hash, claim_sig);
-    let message = Bytes::from_slice(&e, claim_sig.message);
+        software_chip_sign(&e, &client, &chip_1, &claimant, 2, ACTION_CLAIM);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
     let claimed_token_id = client.claim(
         &claimant,
         &message,
         &claim_signature,
         &claim_recovery_id,
-        &public_key,
-        &claim_sig.nonce,
+        &public_key.clone().into(),
+        &2,
+        &0,
     );
     assert_eq!(claimed_token_id, token_id);
 
@@ -675,12 +1156,9 @@ fn test_transfer() {
     assert_eq!(recipient_balance_before, 0u32);
 
     // Chip 1, nonce 3 (transfer)
-    let transfer_sig = &TEST_SIGNATURES[2];
-    let transfer_message_hash =
-        calculate_message_hash(&e, transfer_sig.message, &claimant, transfer_sig.nonce);
     let (transfer_signature, transfer_recovery_id) =
-        create_test_signature_and_recovery_id(&e, &transfer_message_hash, transfer_sig);
-    let message = Bytes::from_slice(&e, transfer_sig.message);
+        software_chip_sign(&e, &client, &chip_1, &claimant, 3, ACTION_TRANSFER);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
     client.transfer(
         &claimant,
         &recipient,
@@ -688,8 +1166,18 @@ fn test_transfer() {
         &message,
         &transfer_signature,
         &transfer_recovery_id,
-        &public_key,
-        &transfer_sig.nonce,
+        &public_key.clone().into(),
+        &3,
+        &0,
+    );
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::Transfer {
+            from: claimant.clone(),
+            to: recipient.clone(),
+            token_id,
+        }
+        .to_xdr(&e, &client.address)]
     );
 
     // Verify ownership changed
@@ -721,68 +1209,66 @@ fn test_multiple_chips_and_nfts() {
     let claimant1 = Address::generate(&e);
     let claimant2 = Address::generate(&e);
     let client = create_client(&e, &admin);
+    let chip_1 = software_chip(1);
+    let chip_2 = software_chip(2);
 
     // Chip 1: Mint NFT 1 (nonce 1) and claim it (nonce 2)
-    let mint1_sig = &TEST_SIGNATURES[0];
-    let mint1_message_hash = calculate_message_hash(&e, mint1_sig.message, &admin, mint1_sig.nonce);
     let (mint1_signature, mint1_recovery_id) =
-        create_test_signature_and_recovery_id(&e, &mint1_message_hash, mint1_sig);
-    let message = Bytes::from_slice(&e, mint1_sig.message);
-    let public_key_1 = BytesN::from_array(&e, &mint1_sig.public_key);
+        software_chip_sign(&e, &client, &chip_1, &admin, 1, ACTION_MINT);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let public_key_1 = software_chip_public_key(&e, &chip_1);
     let token_id_1 = client.mint(
+        &admin,
         &message,
         &mint1_signature,
         &mint1_recovery_id,
-        &public_key_1,
-        &mint1_sig.nonce,
+        &public_key_1.clone().into(),
+        &1,
+        &0,
     );
     assert_eq!(token_id_1, 0u32);
 
-    let claim1_sig = &TEST_SIGNATURES[1];
-    let claim1_message_hash =
-        calculate_message_hash(&e, claim1_sig.message, &claimant1, claim1_sig.nonce);
     let (claim1_signature, claim1_recovery_id) =
-        create_test_signature_and_recovery_id(&e, &claim1_message_hash, claim1_sig);
-    let message = Bytes::from_slice(&e, claim1_sig.message);
+        software_chip_sign(&e, &client, &chip_1, &claimant1, 2, ACTION_CLAIM);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
     let claimed_token_id_1 = client.claim(
         &claimant1,
         &message,
         &claim1_signature,
         &claim1_recovery_id,
-        &public_key_1,
-        &claim1_sig.nonce,
+        &public_key_1.clone().into(),
+        &2,
+        &0,
     );
     assert_eq!(claimed_token_id_1, token_id_1);
 
-    // Chip 2: Mint NFT 2 (nonce 3) and claim it (nonce 4)
-    let mint2_sig = &TEST_SIGNATURES[3];
-    let mint2_message_hash = calculate_message_hash(&e, mint2_sig.message, &admin, mint2_sig.nonce);
+    // Chip 2: Mint NFT 2 (nonce 1) and claim it (nonce 2)
     let (mint2_signature, mint2_recovery_id) =
-        create_test_signature_and_recovery_id(&e, &mint2_message_hash, mint2_sig);
-    let message = Bytes::from_slice(&e, mint2_sig.message);
-    let public_key_2 = BytesN::from_array(&e, &mint2_sig.public_key);
+        software_chip_sign(&e, &client, &chip_2, &admin, 1, ACTION_MINT);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let public_key_2 = software_chip_public_key(&e, &chip_2);
     let token_id_2 = client.mint(
+        &admin,
         &message,
         &mint2_signature,
         &mint2_recovery_id,
-        &public_key_2,
-        &mint2_sig.nonce,
+        &public_key_2.clone().into(),
+        &1,
+        &0,
     );
     assert_eq!(token_id_2, 1u32, "Second token should have ID 1");
 
-    let claim2_sig = &TEST_SIGNATURES[4];
-    let claim2_message_hash =
-        calculate_message_hash(&e, claim2_sig.message, &claimant2, claim2_sig.nonce);
     let (claim2_signature, claim2_recovery_id) =
-        create_test_signature_and_recovery_id(&e, &claim2_message_hash, claim2_sig);
-    let message = Bytes::from_slice(&e, claim2_sig.message);
+        software_chip_sign(&e, &client, &chip_2, &claimant2, 2, ACTION_CLAIM);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
     let claimed_token_id_2 = client.claim(
         &claimant2,
         &message,
         &claim2_signature,
         &claim2_recovery_id,
-        &public_key_2,
-        &claim2_sig.nonce,
+        &public_key_2.clone().into(),
+        &2,
+        &0,
     );
     assert_eq!(claimed_token_id_2, token_id_2);
 
@@ -807,13 +1293,13 @@ fn test_multiple_chips_and_nfts() {
     );
 
     // Verify token IDs are mapped correctly
-    let stored_token_id_1 = client.token_id(&public_key_1);
+    let stored_token_id_1 = client.token_id(&public_key_1.clone().into());
     assert_eq!(
         stored_token_id_1, token_id_1,
         "Chip 1's public key should map to token ID 1"
     );
 
-    let stored_token_id_2 = client.token_id(&public_key_2);
+    let stored_token_id_2 = client.token_id(&public_key_2.clone().into());
     assert_eq!(
         stored_token_id_2, token_id_2,
         "Chip 2's public key should map to token ID 2"
@@ -832,3 +1318,4362 @@ fn test_multiple_chips_and_nfts() {
     assert_eq!(uri1, String::from_str(&e, "ipfs://abcd/0"));
     assert_eq!(uri2, String::from_str(&e, "ipfs://abcd/1"));
 }
+
+#[test]
+fn test_fixture_signatures() {
+    let e = Env::default();
+    // Same address order as print_message_hash_for_signing
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let claimant2 = Address::generate(&e);
+    let signers = [&admin, &claimant, &claimant, &admin, &claimant2];
+
+    for (sig, signer) in TEST_SIGNATURES.iter().zip(signers) {
+        let message_hash = calculate_fixture_message_hash(&e, sig.message, signer, sig.nonce);
+        let (signature, recovery_id) =
+            create_test_signature_and_recovery_id(&e, &message_hash, sig);
+        let recovered = e
+            .crypto()
+            .secp256k1_recover(&message_hash, &signature, recovery_id);
+        assert_eq!(recovered, BytesN::from_array(&e, &sig.public_key));
+        // Chip signatures only use the ids RECOVERY_ID_AUTO tries
+        assert!(recovery_id < 2);
+    }
+}
+
+#[test]
+fn test_signature_bound_to_contract() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let other_client = create_client(&e, &admin);
+
+    // Signed for the first contract, replayed on the second one
+    let chip = software_chip(1);
+    let mint = software_chip_mint_request(&e, &client, &admin, &chip, 1);
+    let result = other_client.try_mint(
+        &admin,
+        &mint.message,
+        &mint.signature,
+        &mint.recovery_id,
+        &mint.public_key.clone().into(),
+        &mint.nonce,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::InvalidSignature.into()))
+    );
+
+    let token_id = client.mint(
+        &admin,
+        &mint.message,
+        &mint.signature,
+        &mint.recovery_id,
+        &mint.public_key.clone().into(),
+        &mint.nonce,
+        &0,
+    );
+    assert_eq!(client.public_key(&token_id), mint.public_key);
+}
+
+#[test]
+fn test_compressed_public_key() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    // The fixture keys compress to their x coordinate with the parity of y
+    for public_key in [CHIP1_PUBLIC_KEY, CHIP2_PUBLIC_KEY] {
+        let compressed = compressed_public_key(&e, &public_key);
+        assert_eq!(compressed.len(), 33);
+        assert_eq!(compressed.get_unchecked(0), 0x02 | (public_key[64] & 1));
+        assert_eq!(
+            compressed.slice(1..),
+            Bytes::from_slice(&e, &public_key[1..33])
+        );
+    }
+
+    let chip = software_chip(1);
+    let public_key = software_chip_public_key(&e, &chip);
+    let compressed = compressed_public_key(&e, &public_key.to_array());
+
+    // Unknown compressed keys have no nonce yet
+    assert_eq!(client.get_nonce(&compressed), 0);
+
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip, &admin, 1, ACTION_MINT);
+    let token_id = client.mint(
+        &admin,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &compressed,
+        &1,
+        &0,
+    );
+
+    // Stored uncompressed, both forms resolve to the same chip
+    assert_eq!(client.public_key(&token_id), public_key);
+    assert_eq!(client.token_id(&compressed), token_id);
+    assert_eq!(client.token_id(&public_key.clone().into()), token_id);
+    assert_eq!(client.get_nonce(&compressed), 1);
+    assert_eq!(client.get_nonce(&public_key.clone().into()), 1);
+
+    // Compressed and uncompressed keys can be mixed for the same chip
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 2, ACTION_CLAIM);
+    client.claim(
+        &claimant,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &2,
+        &0,
+    );
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    // The compressed key of another chip does not match the signature
+    let other = compressed_public_key(
+        &e,
+        &software_chip_public_key(&e, &software_chip(2)).to_array(),
+    );
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 3, ACTION_TRANSFER);
+    let result = client.try_transfer(
+        &claimant,
+        &admin,
+        &token_id,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &other,
+        &3,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::InvalidSignature.into()))
+    );
+
+    // Any other length is rejected
+    let result = client.try_transfer(
+        &claimant,
+        &admin,
+        &token_id,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &compressed.slice(2..),
+        &3,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::InvalidPublicKey.into()))
+    );
+    assert_eq!(
+        client.try_get_nonce(&compressed.slice(2..)),
+        Err(Ok(errors::NonFungibleTokenError::InvalidPublicKey.into()))
+    );
+}
+
+#[test]
+fn test_secp256r1_chip() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+
+    let k1_token_id = software_chip_mint(&e, &client, &admin, &software_chip(1), 1);
+    assert_eq!(client.curve(&k1_token_id), CurveType::Secp256k1);
+
+    let chip = software_r1_chip(1);
+    let public_key = software_r1_chip_public_key(&e, &chip);
+    let signature = software_r1_chip_sign(&e, &client, &chip, &admin, 1, ACTION_MINT);
+    let token_id = client.mint_r1(&admin, &message, &signature, &public_key, &1, &0);
+
+    // Shares the token mappings of secp256k1 chips
+    assert_eq!(client.curve(&token_id), CurveType::Secp256r1);
+    assert_eq!(client.public_key(&token_id), public_key);
+    assert_eq!(client.token_id(&public_key.clone().into()), token_id);
+    assert_eq!(client.get_nonce(&public_key.clone().into()), 1);
+
+    // A P-256 signature does not go through the secp256k1 entry points, the
+    // recovery either fails or yields another key
+    let signature = software_r1_chip_sign(&e, &client, &chip, &claimant, 2, ACTION_CLAIM);
+    let result = client.try_claim(
+        &claimant,
+        &message,
+        &signature,
+        &0,
+        &public_key.clone().into(),
+        &2,
+        &0,
+    );
+    assert!(result.is_err());
+
+    // Nor does a signature made for another signer
+    let other_signature = software_r1_chip_sign(&e, &client, &chip, &recipient, 2, ACTION_CLAIM);
+    assert!(
+        client
+            .try_claim_r1(&claimant, &message, &other_signature, &public_key, &2, &0)
+            .is_err()
+    );
+
+    let claimed_token_id = client.claim_r1(&claimant, &message, &signature, &public_key, &2, &0);
+    assert_eq!(claimed_token_id, token_id);
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    let signature = software_r1_chip_sign(&e, &client, &chip, &claimant, 3, ACTION_TRANSFER);
+    client.transfer_r1(
+        &claimant,
+        &recipient,
+        &token_id,
+        &message,
+        &signature,
+        &public_key,
+        &3,
+        &0,
+    );
+    assert_eq!(client.owner_of(&token_id), recipient);
+    assert_eq!(client.get_nonce(&public_key.into()), 3);
+}
+
+#[test]
+fn test_ed25519_chip() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+
+    let chip = software_ed25519_chip(1);
+    let public_key = software_ed25519_chip_public_key(&e, &chip);
+
+    // The key must be 32 bytes long
+    let signature = software_ed25519_chip_sign(&e, &client, &chip, &admin, 1, ACTION_MINT);
+    let result = client.try_mint(
+        &admin,
+        &message,
+        &signature,
+        &0,
+        &public_key.clone().into(),
+        &1,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::InvalidPublicKey.into()))
+    );
+
+    let token_id = client.mint_ed25519(&admin, &message, &signature, &public_key, &1, &0);
+    assert_eq!(client.curve(&token_id), CurveType::Ed25519);
+    assert_eq!(
+        client.chip_key(&token_id),
+        ChipKey::Ed25519(public_key.clone())
+    );
+    assert_eq!(client.token_id(&public_key.clone().into()), token_id);
+    assert_eq!(client.get_nonce(&public_key.clone().into()), 1);
+
+    // Same nonce handling as the other curves
+    let result = client.try_mint_ed25519(&admin, &message, &signature, &public_key, &1, &0);
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::NonceAlreadyUsed.into()))
+    );
+
+    let signature = software_ed25519_chip_sign(&e, &client, &chip, &claimant, 2, ACTION_CLAIM);
+    let claimed_token_id =
+        client.claim_ed25519(&claimant, &message, &signature, &public_key, &2, &0);
+    assert_eq!(claimed_token_id, token_id);
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    // A signature for another action is rejected
+    let signature = software_ed25519_chip_sign(&e, &client, &chip, &claimant, 3, ACTION_CLAIM);
+    assert!(
+        client
+            .try_transfer_ed25519(
+                &claimant,
+                &recipient,
+                &token_id,
+                &message,
+                &signature,
+                &public_key,
+                &3,
+                &0,
+            )
+            .is_err()
+    );
+
+    let signature = software_ed25519_chip_sign(&e, &client, &chip, &claimant, 3, ACTION_TRANSFER);
+    client.transfer_ed25519(
+        &claimant,
+        &recipient,
+        &token_id,
+        &message,
+        &signature,
+        &public_key,
+        &3,
+        &0,
+    );
+    assert_eq!(client.owner_of(&token_id), recipient);
+
+    // Other chips keep their curve
+    let k1_token_id = software_chip_mint(&e, &client, &admin, &software_chip(1), 1);
+    assert_eq!(
+        client.chip_key(&k1_token_id),
+        ChipKey::Secp256k1(software_chip_public_key(&e, &software_chip(1)))
+    );
+}
+
+#[test]
+fn test_recovery_id_auto() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+
+    // Enough chips to get signatures with both recovery ids
+    let mut recovery_ids = Vec::new();
+    for seed in 1..=8 {
+        let chip = software_chip(seed);
+        let public_key = software_chip_public_key(&e, &chip);
+        let (signature, recovery_id) =
+            software_chip_sign(&e, &client, &chip, &admin, 1, ACTION_MINT);
+        recovery_ids.push(recovery_id);
+        let token_id = client.mint(
+            &admin,
+            &message,
+            &signature,
+            &RECOVERY_ID_AUTO,
+            &public_key.clone().into(),
+            &1,
+            &0,
+        );
+        assert_eq!(client.public_key(&token_id), public_key);
+
+        // Compressed keys are matched the same way
+        let (signature, _) = software_chip_sign(&e, &client, &chip, &claimant, 2, ACTION_CLAIM);
+        client.claim(
+            &claimant,
+            &message,
+            &signature,
+            &RECOVERY_ID_AUTO,
+            &compressed_public_key(&e, &public_key.to_array()),
+            &2,
+            &0,
+        );
+    }
+    assert!(recovery_ids.contains(&0) && recovery_ids.contains(&1));
+    assert_eq!(client.balance(&claimant), 8);
+
+    // A key recovered by neither id is rejected
+    let chip = software_chip(9);
+    let (signature, _) = software_chip_sign(&e, &client, &chip, &admin, 1, ACTION_MINT);
+    let result = client.try_mint(
+        &admin,
+        &message,
+        &signature,
+        &RECOVERY_ID_AUTO,
+        &software_chip_public_key(&e, &software_chip(10)).into(),
+        &1,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::InvalidSignature.into()))
+    );
+}
+
+#[test]
+fn test_signature_bound_to_action() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+
+    // Signed for a claim, replayed on transfer with the same signer and nonce
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 3, ACTION_CLAIM);
+    let result = client.try_transfer(
+        &claimant,
+        &recipient,
+        &token_id,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip).into(),
+        &3,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::InvalidSignature.into()))
+    );
+    assert_eq!(client.owner_of(&token_id), claimant);
+    assert_eq!(
+        client.get_nonce(&software_chip_public_key(&e, &chip).into()),
+        2
+    );
+}
+
+#[test]
+fn test_approve_and_transfer_from() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let spender = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &owner, &chip, 2);
+
+    assert_eq!(client.get_approved(&token_id), None);
+
+    client.approve(&owner, &spender, &token_id, &1000);
+    assert_eq!(client.get_approved(&token_id), Some(spender.clone()));
+
+    software_chip_transfer_from(&e, &client, &spender, &owner, &recipient, &chip, 3);
+
+    assert_eq!(client.owner_of(&token_id), recipient);
+    assert_eq!(client.balance(&owner), 0u32);
+    assert_eq!(client.balance(&recipient), 1u32);
+    assert_eq!(
+        client.get_approved(&token_id),
+        None,
+        "Approval should be cleared on transfer"
+    );
+
+    // revoke an approval by passing 0 as live_until_ledger
+    client.approve(&recipient, &spender, &token_id, &1000);
+    client.approve(&recipient, &spender, &token_id, &0);
+    assert_eq!(client.get_approved(&token_id), None);
+}
+
+#[test]
+fn test_approval_cleared_on_clawback() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let spender = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &owner, &chip, 2);
+
+    client.approve(&owner, &spender, &token_id, &1000);
+    client.clawback(&token_id, &0);
+
+    assert_eq!(client.get_approved(&token_id), None);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #204)")]
+fn test_transfer_from_expired_approval() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let spender = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &owner, &chip, 2);
+
+    let live_until_ledger = e.ledger().sequence() + 10;
+    client.approve(&owner, &spender, &token_id, &live_until_ledger);
+
+    e.ledger().set_sequence_number(live_until_ledger + 1);
+    assert_eq!(client.get_approved(&token_id), None);
+
+    software_chip_transfer_from(&e, &client, &spender, &owner, &recipient, &chip, 3);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #204)")]
+fn test_transfer_from_unauthorized_spender() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let spender = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &owner, &chip, 2);
+
+    software_chip_transfer_from(&e, &client, &spender, &owner, &recipient, &chip, 3);
+}
+
+#[test]
+fn test_admin_transfer() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let new_admin = Address::generate(&e);
+    let other_admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    assert_eq!(client.get_admin(), admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    client.transfer_admin(&new_admin);
+    assert_eq!(client.get_pending_admin(), Some(new_admin.clone()));
+    assert_eq!(client.get_admin(), admin, "Admin changes only on accept");
+
+    // a second call replaces the pending admin
+    client.transfer_admin(&other_admin);
+    assert_eq!(client.get_pending_admin(), Some(other_admin.clone()));
+
+    client.accept_admin();
+    assert_eq!(client.get_admin(), other_admin);
+    assert_eq!(client.get_pending_admin(), None);
+
+    // the new admin is now in charge of admin operations
+    client.transfer_admin(&new_admin);
+    assert_eq!(
+        e.auths()[0].0,
+        other_admin,
+        "transfer_admin should require the new admin's auth"
+    );
+}
+
+#[test]
+fn test_accept_admin_unauthorized() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let new_admin = Address::generate(&e);
+    let attacker = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    client.transfer_admin(&new_admin);
+
+    let result = client
+        .mock_auths(&[MockAuth {
+            address: &attacker,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "accept_admin",
+                args: ().into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .try_accept_admin();
+    assert!(result.is_err(), "Only the pending admin can accept");
+    assert_eq!(client.get_admin(), admin);
+}
+
+#[test]
+#[should_panic(expected = "Error(Contract, #206)")]
+fn test_accept_admin_without_pending() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    client.accept_admin();
+}
+
+#[test]
+fn test_pause() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+
+    assert!(!client.is_paused());
+    client.pause();
+    assert!(client.is_paused());
+
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 2, ACTION_CLAIM);
+    let result = client.try_claim(
+        &claimant,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip).into(),
+        &2,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::ContractPaused.into()))
+    );
+
+    // views keep working while paused
+    assert_eq!(
+        client.public_key(&token_id),
+        software_chip_public_key(&e, &chip)
+    );
+
+    client.unpause();
+    assert!(!client.is_paused());
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    // clawback is allowed while paused
+    client.pause();
+    client.clawback(&token_id, &0);
+    assert_eq!(client.owner_of(&token_id), admin);
+}
+
+#[test]
+fn test_mint_and_transfer_flags() {
+    for (mint_enabled, transfer_enabled) in
+        [(true, true), (true, false), (false, true), (false, false)]
+    {
+        let e = Env::default();
+        e.mock_all_auths();
+
+        let admin = Address::generate(&e);
+        let owner = Address::generate(&e);
+        let recipient = Address::generate(&e);
+        let client = create_client(&e, &admin);
+
+        let chip_1 = software_chip(1);
+        software_chip_mint(&e, &client, &admin, &chip_1, 1);
+        software_chip_claim(&e, &client, &owner, &chip_1, 2);
+
+        client.set_mint_enabled(&mint_enabled);
+        client.set_transfer_enabled(&transfer_enabled);
+        assert_eq!(client.is_mint_enabled(), mint_enabled);
+        assert_eq!(client.is_transfer_enabled(), transfer_enabled);
+
+        let chip_2 = software_chip(2);
+        let (signature, recovery_id) =
+            software_chip_sign(&e, &client, &chip_2, &admin, 1, ACTION_MINT);
+        let mint_result = client.try_mint(
+            &admin,
+            &Bytes::from_slice(&e, TEST_MESSAGE),
+            &signature,
+            &recovery_id,
+            &software_chip_public_key(&e, &chip_2).into(),
+            &1,
+            &0,
+        );
+        if mint_enabled {
+            assert_eq!(mint_result, Ok(Ok(1u32)));
+        } else {
+            assert_eq!(
+                mint_result,
+                Err(Ok(errors::NonFungibleTokenError::OperationDisabled.into()))
+            );
+        }
+
+        let token_id = client.token_id(&software_chip_public_key(&e, &chip_1).into());
+        let (signature, recovery_id) =
+            software_chip_sign(&e, &client, &chip_1, &owner, 3, ACTION_TRANSFER);
+        let transfer_result = client.try_transfer(
+            &owner,
+            &recipient,
+            &token_id,
+            &Bytes::from_slice(&e, TEST_MESSAGE),
+            &signature,
+            &recovery_id,
+            &software_chip_public_key(&e, &chip_1).into(),
+            &3,
+            &0,
+        );
+        if transfer_enabled {
+            assert_eq!(transfer_result, Ok(Ok(())));
+        } else {
+            assert_eq!(
+                transfer_result,
+                Err(Ok(errors::NonFungibleTokenError::OperationDisabled.into()))
+            );
+        }
+    }
+}
+
+#[test]
+fn test_supply_counters() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant_1 = Address::generate(&e);
+    let claimant_2 = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    assert_eq!(client.total_supply(), 0u32);
+    assert_eq!(client.claimed_count(), 0u32);
+
+    let chip_1 = software_chip(1);
+    let chip_2 = software_chip(2);
+    software_chip_mint(&e, &client, &admin, &chip_1, 1);
+    let token_id_2 = software_chip_mint(&e, &client, &admin, &chip_2, 1);
+    assert_eq!(client.total_supply(), 2u32);
+    assert_eq!(client.claimed_count(), 0u32);
+
+    software_chip_claim(&e, &client, &claimant_1, &chip_1, 2);
+    assert_eq!(client.claimed_count(), 1u32);
+    software_chip_claim(&e, &client, &claimant_2, &chip_2, 2);
+    assert_eq!(client.claimed_count(), 2u32);
+
+    client.clawback(&token_id_2, &0);
+    assert_eq!(client.total_supply(), 2u32);
+    assert_eq!(client.claimed_count(), 2u32);
+}
+
+#[test]
+fn test_tokens_of_owner() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chips = [software_chip(1), software_chip(2), software_chip(3)];
+    for chip in chips.iter() {
+        software_chip_mint(&e, &client, &admin, chip, 1);
+        software_chip_claim(&e, &client, &owner, chip, 2);
+    }
+    assert_eq!(client.tokens_of_owner(&owner), vec![&e, 0u32, 1u32, 2u32]);
+    assert_eq!(client.token_of_owner_by_index(&owner, &1), 1u32);
+
+    // transferring token 0 away moves the last token into its slot
+    software_chip_transfer(&e, &client, &owner, &recipient, &chips[0], 3);
+    assert_eq!(client.tokens_of_owner(&owner), vec![&e, 2u32, 1u32]);
+    assert_eq!(client.tokens_of_owner(&recipient), vec![&e, 0u32]);
+
+    client.clawback(&1, &0);
+    assert_eq!(client.tokens_of_owner(&owner), vec![&e, 2u32]);
+    assert_eq!(client.tokens_of_owner(&admin), vec![&e, 1u32]);
+
+    assert_eq!(
+        client.try_token_of_owner_by_index(&owner, &1),
+        Err(Ok(errors::NonFungibleTokenError::IndexOutOfBounds.into()))
+    );
+}
+
+#[test]
+fn test_tokens_of_owner_claimed_before_enumeration() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chips = [software_chip(1), software_chip(2), software_chip(3)];
+    for chip in chips.iter() {
+        software_chip_mint(&e, &client, &admin, chip, 1);
+        software_chip_claim(&e, &client, &owner, chip, 2);
+    }
+
+    // Owners as releases without token lists left them, only a balance
+    e.as_contract(&client.address, || {
+        for token_id in 0..3u32 {
+            e.storage()
+                .persistent()
+                .remove(&crate::contract::NFTStorageKey::OwnerToken(
+                    owner.clone(),
+                    token_id,
+                ));
+            e.storage()
+                .persistent()
+                .remove(&crate::contract::NFTStorageKey::OwnerTokenIndex(token_id));
+        }
+        e.storage()
+            .persistent()
+            .remove(&crate::contract::NFTStorageKey::OwnerTokenCount(
+                owner.clone(),
+            ));
+    });
+    assert_eq!(client.balance(&owner), 3);
+    assert_eq!(client.tokens_of_owner(&owner), vec![&e]);
+
+    // Unlisted tokens can still be transferred and clawed back
+    software_chip_transfer(&e, &client, &owner, &recipient, &chips[0], 3);
+    assert_eq!(client.tokens_of_owner(&owner), vec![&e]);
+    assert_eq!(client.tokens_of_owner(&recipient), vec![&e, 0u32]);
+    client.clawback(&1, &0);
+    assert_eq!(client.tokens_of_owner(&admin), vec![&e, 1u32]);
+    assert_eq!(client.balance(&owner), 1);
+
+    // and indexed by anyone
+    e.set_auths(&[]);
+    client.enumerate_tokens(&vec![&e, 0u32, 1u32, 2u32, 7u32]);
+    assert_eq!(client.tokens_of_owner(&owner), vec![&e, 2u32]);
+    assert_eq!(client.tokens_of_owner(&recipient), vec![&e, 0u32]);
+    assert_eq!(client.tokens_of_owner(&admin), vec![&e, 1u32]);
+}
+
+#[test]
+fn test_tokens_pagination() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    for seed in 1..=5u8 {
+        software_chip_mint(&e, &client, &admin, &software_chip(seed), 1);
+    }
+    software_chip_claim(&e, &client, &claimant, &software_chip(2), 2);
+
+    assert_eq!(client.tokens(&0, &2), vec![&e, 0u32, 1u32]);
+    assert_eq!(client.tokens(&2, &2), vec![&e, 2u32, 3u32]);
+    assert_eq!(client.tokens(&4, &2), vec![&e, 4u32]);
+    assert_eq!(client.tokens(&5, &2), vec![&e]);
+    assert_eq!(client.tokens(&0, &0), vec![&e]);
+    assert_eq!(client.tokens(&u32::MAX, &MAX_TOKENS_PAGE), vec![&e]);
+
+    assert_eq!(
+        client.tokens_detailed(&0, &2),
+        vec![&e, (0u32, None), (1u32, Some(claimant.clone()))]
+    );
+
+    // Larger pages are rejected
+    for seed in 6..=MAX_TOKENS_PAGE as u8 + 1 {
+        client.admin_mint(&software_chip_public_key(&e, &software_chip(seed)));
+    }
+    let page = client.tokens(&0, &MAX_TOKENS_PAGE);
+    assert_eq!(page.len(), MAX_TOKENS_PAGE);
+    assert_eq!(page.last(), Some(MAX_TOKENS_PAGE - 1));
+    assert_eq!(
+        client.tokens(&MAX_TOKENS_PAGE, &MAX_TOKENS_PAGE),
+        vec![&e, MAX_TOKENS_PAGE]
+    );
+    assert_eq!(
+        client.unclaimed_tokens(&0, &MAX_TOKENS_PAGE).len(),
+        MAX_TOKENS_PAGE - 1
+    );
+    assert_eq!(
+        client.try_tokens(&0, &(MAX_TOKENS_PAGE + 1)),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+    assert_eq!(
+        client.try_unclaimed_tokens(&0, &(MAX_TOKENS_PAGE + 1)),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+    assert_eq!(
+        client.try_tokens_detailed(&u32::MAX, &u32::MAX),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+}
+
+#[test]
+fn test_set_base_uri() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &software_chip(1), 1);
+    assert_eq!(
+        client.token_uri(&token_id),
+        String::from_str(&e, "ipfs://abcd/0")
+    );
+
+    client.set_base_uri(&String::from_str(&e, "ipfs://efgh"));
+    assert_eq!(client.base_uri(), String::from_str(&e, "ipfs://efgh"));
+    assert_eq!(
+        client.token_uri(&token_id),
+        String::from_str(&e, "ipfs://efgh/0")
+    );
+
+    assert_eq!(client.uri_suffix(), String::from_str(&e, ""));
+    client.set_uri_suffix(&String::from_str(&e, ".json"));
+    assert_eq!(client.uri_suffix(), String::from_str(&e, ".json"));
+    assert_eq!(
+        client.token_uri(&token_id),
+        String::from_str(&e, "ipfs://efgh/0.json")
+    );
+
+    client.freeze_base_uri();
+    assert_eq!(
+        client.try_set_base_uri(&String::from_str(&e, "ipfs://ijkl")),
+        Err(Ok(errors::NonFungibleTokenError::MetadataFrozen.into()))
+    );
+    assert_eq!(
+        client.try_set_uri_suffix(&String::from_str(&e, "")),
+        Err(Ok(errors::NonFungibleTokenError::MetadataFrozen.into()))
+    );
+    assert_eq!(client.base_uri(), String::from_str(&e, "ipfs://efgh"));
+}
+
+#[test]
+fn test_token_metadata() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.ledger().set_sequence_number(42);
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    // plain mint has default metadata
+    let token_id_1 = software_chip_mint(&e, &client, &admin, &software_chip(1), 1);
+    let metadata = client.token_metadata(&token_id_1);
+    assert_eq!(metadata.edition, 0u32);
+    assert_eq!(metadata.batch, String::from_str(&e, ""));
+    assert_eq!(metadata.extra, Map::new(&e));
+
+    let chip_2 = software_chip(2);
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip_2, &admin, 1, ACTION_MINT);
+    let mut extra = Map::new(&e);
+    extra.set(
+        String::from_str(&e, "manufactured"),
+        String::from_str(&e, "2025-11-03"),
+    );
+    let token_id_2 = client.mint_with_metadata(
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip_2),
+        &1,
+        &7,
+        &String::from_str(&e, "B-01"),
+        &extra,
+    );
+
+    let expected = TokenMetadata {
+        edition: 7,
+        batch: String::from_str(&e, "B-01"),
+        minted_ledger: 42,
+        extra: extra.clone(),
+    };
+    assert_eq!(client.token_metadata(&token_id_2), expected);
+
+    client.set_token_attribute(
+        &token_id_2,
+        &String::from_str(&e, "color"),
+        &String::from_str(&e, "green"),
+    );
+    let metadata = client.token_metadata(&token_id_2);
+    assert_eq!(metadata.extra.len(), 2);
+    assert_eq!(
+        metadata.extra.get(String::from_str(&e, "color")),
+        Some(String::from_str(&e, "green"))
+    );
+}
+
+#[test]
+fn test_contract_uri() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    assert_eq!(client.contract_uri(), String::from_str(&e, ""));
+
+    client.set_contract_uri(&String::from_str(&e, "ipfs://collection"));
+    assert_eq!(
+        client.contract_uri(),
+        String::from_str(&e, "ipfs://collection")
+    );
+
+    client.set_contract_uri(&String::from_str(&e, "ipfs://collection-v2"));
+    assert_eq!(
+        client.contract_uri(),
+        String::from_str(&e, "ipfs://collection-v2")
+    );
+}
+
+#[test]
+fn test_set_max_tokens() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client_with_max_tokens(&e, &admin, 1);
+
+    software_chip_mint(&e, &client, &admin, &software_chip(1), 1);
+
+    let chip_2 = software_chip(2);
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip_2, &admin, 1, ACTION_MINT);
+    let result = client.try_mint(
+        &admin,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip_2).into(),
+        &1,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::TokenIDsAreDepleted.into()))
+    );
+
+    client.set_max_tokens(&2);
+    assert_eq!(client.max_tokens(), 2u32);
+    assert_eq!(software_chip_mint(&e, &client, &admin, &chip_2, 1), 1u32);
+
+    assert_eq!(
+        client.try_set_max_tokens(&1),
+        Err(Ok(errors::NonFungibleTokenError::InvalidMaxTokens.into()))
+    );
+}
+
+#[test]
+fn test_batch_mint() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let mints = vec![
+        &e,
+        software_chip_mint_request(&e, &client, &admin, &software_chip(1), 1),
+        software_chip_mint_request(&e, &client, &admin, &software_chip(2), 1),
+    ];
+    let token_ids = client.batch_mint(&admin, &mints);
+    assert_eq!(token_ids, vec![&e, 0u32, 1u32]);
+
+    assert_eq!(
+        client.public_key(&0),
+        software_chip_public_key(&e, &software_chip(1))
+    );
+    assert_eq!(
+        client.public_key(&1),
+        software_chip_public_key(&e, &software_chip(2))
+    );
+    assert_eq!(client.next_token_id(), 2u32);
+}
+
+#[test]
+fn test_batch_mint_is_atomic() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    // the second entry carries chip 1's signature, so it does not match chip 2's key
+    let valid = software_chip_mint_request(&e, &client, &admin, &software_chip(1), 1);
+    let mut invalid = software_chip_mint_request(&e, &client, &admin, &software_chip(2), 1);
+    invalid.signature = valid.signature.clone();
+
+    let mints = vec![&e, valid, invalid];
+    assert!(client.try_batch_mint(&admin, &mints).is_err());
+
+    assert_eq!(client.next_token_id(), 0u32);
+    assert_eq!(
+        client.get_nonce(&software_chip_public_key(&e, &software_chip(1)).into()),
+        0u32
+    );
+}
+
+#[test]
+fn test_batch_claim() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip_1 = software_chip(1);
+    let chip_2 = software_chip(2);
+    let token_id_1 = software_chip_mint(&e, &client, &admin, &chip_1, 1);
+    let token_id_2 = software_chip_mint(&e, &client, &admin, &chip_2, 1);
+
+    let mut claims = vec![&e];
+    for chip in [&chip_1, &chip_2] {
+        let (signature, recovery_id) =
+            software_chip_sign(&e, &client, chip, &claimant, 2, ACTION_CLAIM);
+        claims.push_back(ClaimRequest {
+            message: Bytes::from_slice(&e, TEST_MESSAGE),
+            signature,
+            recovery_id,
+            public_key: software_chip_public_key(&e, chip),
+            nonce: 2,
+        });
+    }
+
+    let token_ids = client.batch_claim(&claimant, &claims);
+    assert_eq!(token_ids, vec![&e, token_id_1, token_id_2]);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [
+            events::Claim {
+                claimant: claimant.clone(),
+                token_id: token_id_1,
+                public_key: software_chip_public_key(&e, &chip_1),
+            }
+            .to_xdr(&e, &client.address),
+            events::Transfer {
+                from: client.address.clone(),
+                to: claimant.clone(),
+                token_id: token_id_1,
+            }
+            .to_xdr(&e, &client.address),
+            events::Claim {
+                claimant: claimant.clone(),
+                token_id: token_id_2,
+                public_key: software_chip_public_key(&e, &chip_2),
+            }
+            .to_xdr(&e, &client.address),
+            events::Transfer {
+                from: client.address.clone(),
+                to: claimant.clone(),
+                token_id: token_id_2,
+            }
+            .to_xdr(&e, &client.address),
+        ]
+    );
+    assert_eq!(client.owner_of(&token_id_1), claimant);
+    assert_eq!(client.owner_of(&token_id_2), claimant);
+    assert_eq!(client.balance(&claimant), 2u32);
+
+    // the same nonce cannot be replayed
+    assert!(client.try_batch_claim(&claimant, &claims).is_err());
+}
+
+#[test]
+fn test_owners_of_and_balances_of() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let nobody = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip_1 = software_chip(1);
+    let claimed = software_chip_mint(&e, &client, &admin, &chip_1, 1);
+    let unclaimed = software_chip_mint(&e, &client, &admin, &software_chip(2), 1);
+    software_chip_claim(&e, &client, &claimant, &chip_1, 2);
+
+    let owners = client.owners_of(&vec![&e, unclaimed, claimed, 99u32, claimed]);
+    assert_eq!(
+        owners,
+        vec![
+            &e,
+            None,
+            Some(claimant.clone()),
+            None,
+            Some(claimant.clone())
+        ]
+    );
+
+    let balances = client.balances_of(&vec![&e, nobody, claimant.clone(), claimant]);
+    assert_eq!(balances, vec![&e, 0u32, 1u32, 1u32]);
+}
+
+#[test]
+fn test_token_status() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    assert!(!client.is_minted(&0));
+    assert!(!client.is_claimed(&0));
+    assert_eq!(client.token_status(&0), TokenStatus::NotMinted);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    assert!(client.is_minted(&token_id));
+    assert!(!client.is_claimed(&token_id));
+    assert_eq!(client.token_status(&token_id), TokenStatus::Minted);
+
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+    assert!(client.is_claimed(&token_id));
+    assert_eq!(client.token_status(&token_id), TokenStatus::Claimed);
+
+    client.clawback(&token_id, &0);
+    assert!(client.is_claimed(&token_id));
+    assert_eq!(client.token_status(&token_id), TokenStatus::Quarantined);
+
+    // moving the token again ends the quarantine
+    software_chip_transfer(&e, &client, &admin, &recipient, &chip, 3);
+    assert_eq!(client.token_status(&token_id), TokenStatus::Claimed);
+}
+
+#[test]
+fn test_mint_to() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip, &admin, 1, ACTION_MINT);
+    let token_id = client.mint_to(
+        &buyer,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip),
+        &1,
+    );
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [
+            events::Mint {
+                to: client.address.clone(),
+                token_id,
+                public_key: software_chip_public_key(&e, &chip),
+            }
+            .to_xdr(&e, &client.address),
+            events::Claim {
+                claimant: buyer.clone(),
+                token_id,
+                public_key: software_chip_public_key(&e, &chip),
+            }
+            .to_xdr(&e, &client.address),
+            events::Transfer {
+                from: client.address.clone(),
+                to: buyer.clone(),
+                token_id,
+            }
+            .to_xdr(&e, &client.address),
+        ]
+    );
+
+    assert_eq!(client.owner_of(&token_id), buyer);
+    assert_eq!(client.balance(&buyer), 1u32);
+    assert_eq!(client.token_status(&token_id), TokenStatus::Claimed);
+
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip, &buyer, 2, ACTION_CLAIM);
+    let result = client.try_claim(
+        &buyer,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip).into(),
+        &2,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::TokenAlreadyClaimed.into()))
+    );
+}
+
+#[test]
+fn test_claim_for() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 2, ACTION_CLAIM);
+    client.claim_for(
+        &claimant,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip),
+        &2,
+    );
+    assert_eq!(
+        e.auths()[0].0,
+        admin,
+        "claim_for should require the admin's auth"
+    );
+    assert_eq!(client.owner_of(&token_id), claimant);
+    assert_eq!(client.balance(&claimant), 1u32);
+    assert_eq!(client.claimed_count(), 1u32);
+}
+
+#[test]
+fn test_claim_for_redirected_claimant() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let other = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    software_chip_mint(&e, &client, &admin, &chip, 1);
+
+    // signed for `claimant`, submitted for `other`
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 2, ACTION_CLAIM);
+    let result = client.try_claim_for(
+        &other,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip),
+        &2,
+    );
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_claim_deadline() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip_without_window = software_chip(1);
+    let token_without_window = software_chip_mint(&e, &client, &admin, &chip_without_window, 1);
+    assert_eq!(client.claim_deadline(&token_without_window), None);
+
+    client.set_claim_deadline(&50);
+    let chip_on_time = software_chip(2);
+    let token_on_time = software_chip_mint(&e, &client, &admin, &chip_on_time, 1);
+    let chip_expired = software_chip(3);
+    let token_expired = software_chip_mint(&e, &client, &admin, &chip_expired, 1);
+    assert_eq!(client.claim_deadline(&token_on_time), Some(150));
+
+    // the window is still open
+    assert_eq!(
+        client.try_reclaim_expired(&token_expired),
+        Err(Ok(errors::NonFungibleTokenError::ClaimWindowOpen.into()))
+    );
+
+    e.ledger().set_sequence_number(150);
+    software_chip_claim(&e, &client, &claimant, &chip_on_time, 2);
+    assert_eq!(client.owner_of(&token_on_time), claimant);
+
+    e.ledger().set_sequence_number(151);
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip_expired, &claimant, 2, ACTION_CLAIM);
+    let result = client.try_claim(
+        &claimant,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip_expired).into(),
+        &2,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::ClaimWindowExpired.into()))
+    );
+
+    // tokens minted without a window never expire
+    software_chip_claim(&e, &client, &claimant, &chip_without_window, 2);
+    assert_eq!(client.owner_of(&token_without_window), claimant);
+
+    // claimed tokens cannot be reclaimed
+    assert_eq!(
+        client.try_reclaim_expired(&token_on_time),
+        Err(Ok(errors::NonFungibleTokenError::TokenAlreadyClaimed.into()))
+    );
+
+    client.reclaim_expired(&token_expired);
+    assert_eq!(client.owner_of(&token_expired), admin);
+    assert_eq!(client.balance(&admin), 1u32);
+    assert_eq!(client.balance(&claimant), 2u32);
+    assert_eq!(client.claimed_count(), 3u32);
+}
+
+#[test]
+fn test_clawback_event() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+
+    client.clawback(&token_id, &7);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [
+            events::Clawback {
+                from: claimant.clone(),
+                token_id,
+                reason: 7,
+            }
+            .to_xdr(&e, &client.address),
+            events::Transfer {
+                from: claimant.clone(),
+                to: admin.clone(),
+                token_id,
+            }
+            .to_xdr(&e, &client.address),
+        ]
+    );
+    assert_eq!(client.owner_of(&token_id), admin);
+
+    // Indexers relying on Claim and Clawback only can turn the transfers off
+    client.set_claim_transfer_events(&false);
+    assert!(!client.claim_transfer_events());
+    client.clawback_release(&token_id, &claimant);
+    client.clawback(&token_id, &7);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::Clawback {
+            from: claimant.clone(),
+            token_id,
+            reason: 7,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+}
+
+#[test]
+fn test_clawback_release() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+
+    assert_eq!(
+        client.try_clawback_release(&token_id, &claimant),
+        Err(Ok(errors::NonFungibleTokenError::TokenNotQuarantined.into()))
+    );
+
+    client.clawback(&token_id, &0);
+    assert_eq!(client.balance(&claimant), 0u32);
+    assert_eq!(client.balance(&admin), 1u32);
+
+    // a second clawback keeps the owner it was taken from
+    client.clawback(&token_id, &1);
+    let quarantined_from = || {
+        e.as_contract(&client.address, || {
+            e.storage()
+                .persistent()
+                .get::<_, Address>(&crate::contract::NFTStorageKey::Quarantine(token_id))
+        })
+    };
+    assert_eq!(quarantined_from(), Some(claimant.clone()));
+
+    client.clawback_release(&token_id, &claimant);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::ClawbackRelease {
+            to: claimant.clone(),
+            token_id,
+        }
+        .to_xdr(&e, &client.address),]
+    );
+    assert_eq!(client.owner_of(&token_id), claimant);
+    assert_eq!(client.balance(&claimant), 1u32);
+    assert_eq!(client.balance(&admin), 0u32);
+    assert_eq!(client.tokens_of_owner(&claimant), vec![&e, token_id]);
+    assert_eq!(client.token_status(&token_id), TokenStatus::Claimed);
+
+    // the quarantine is over
+    assert_eq!(
+        client.try_clawback_release(&token_id, &claimant),
+        Err(Ok(errors::NonFungibleTokenError::TokenNotQuarantined.into()))
+    );
+
+    // only the admin holding the token releases it
+    client.clawback(&token_id, &0);
+    let new_admin = Address::generate(&e);
+    client.transfer_admin(&new_admin);
+    client.accept_admin();
+    assert_eq!(
+        client.try_clawback_release(&token_id, &claimant),
+        Err(Ok(errors::NonFungibleTokenError::TokenNotQuarantined.into()))
+    );
+    assert_eq!(client.owner_of(&token_id), admin);
+}
+
+#[test]
+fn test_freeze_token() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let buyer = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    // frozen before the claim, the first owner still receives it
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    client.freeze_token(&token_id);
+    assert!(client.is_frozen(&token_id));
+    software_chip_claim(&e, &client, &buyer, &chip, 2);
+    assert_eq!(client.owner_of(&token_id), buyer);
+
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &buyer, 3, ACTION_TRANSFER);
+    let result = client.try_transfer(
+        &buyer,
+        &recipient,
+        &token_id,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip).into(),
+        &3,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::TokenFrozen.into()))
+    );
+
+    client.unfreeze_token(&token_id);
+    assert!(!client.is_frozen(&token_id));
+    software_chip_transfer(&e, &client, &buyer, &recipient, &chip, 3);
+    assert_eq!(client.owner_of(&token_id), recipient);
+
+    // frozen after the claim, approved spenders are blocked too
+    let other_chip = software_chip(2);
+    let other_token_id = software_chip_mint(&e, &client, &admin, &other_chip, 1);
+    software_chip_claim(&e, &client, &buyer, &other_chip, 2);
+    client.approve(&buyer, &recipient, &other_token_id, &1000);
+    client.freeze_token(&other_token_id);
+
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &other_chip, &recipient, 3, ACTION_TRANSFER);
+    let result = client.try_transfer_from(
+        &recipient,
+        &buyer,
+        &recipient,
+        &other_token_id,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &other_chip),
+        &3,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::TokenFrozen.into()))
+    );
+
+    // clawback bypasses the freeze
+    client.clawback(&other_token_id, &0);
+    assert_eq!(client.owner_of(&other_token_id), admin);
+    assert!(client.is_frozen(&other_token_id));
+
+    assert_eq!(
+        client.try_freeze_token(&99),
+        Err(Ok(errors::NonFungibleTokenError::NonExistentToken.into()))
+    );
+}
+
+#[test]
+fn test_soulbound() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_soulbound_client(&e, &admin);
+    let chip_1 = software_chip(1);
+    assert!(client.is_soulbound());
+    assert!(!create_client(&e, &admin).is_soulbound());
+
+    // Chip 1, nonce 1 (mint)
+    let mint = software_chip_mint_request(&e, &client, &admin, &software_chip(1), 1);
+    let token_id = client.mint(
+        &admin,
+        &mint.message,
+        &mint.signature,
+        &mint.recovery_id,
+        &mint.public_key.clone().into(),
+        &mint.nonce,
+        &0,
+    );
+
+    // Chip 1, nonce 2 (claim)
+    let (claim_signature, claim_recovery_id) =
+        software_chip_sign(&e, &client, &chip_1, &claimant, 2, ACTION_CLAIM);
+    client.claim(
+        &claimant,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &claim_signature,
+        &claim_recovery_id,
+        &mint.public_key.clone().into(),
+        &2,
+        &0,
+    );
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    // Chip 1, nonce 3 (transfer)
+    let (transfer_signature, transfer_recovery_id) =
+        software_chip_sign(&e, &client, &chip_1, &claimant, 3, ACTION_TRANSFER);
+    let result = client.try_transfer(
+        &claimant,
+        &recipient,
+        &token_id,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &transfer_signature,
+        &transfer_recovery_id,
+        &mint.public_key.clone().into(),
+        &3,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::SoulboundCollection.into()))
+    );
+
+    // clawback still works
+    client.clawback(&token_id, &0);
+    assert_eq!(client.owner_of(&token_id), admin);
+}
+
+#[test]
+fn test_royalty_info() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let artist = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &software_chip(1), 1);
+    let other_token_id = software_chip_mint(&e, &client, &admin, &software_chip(2), 1);
+
+    // Nothing is owed without a royalty
+    assert_eq!(
+        client.royalty_info(&token_id, &100),
+        (client.address.clone(), 0)
+    );
+    assert_eq!(
+        client.try_royalty_info(&99, &100),
+        Err(Ok(errors::NonFungibleTokenError::NonExistentToken.into()))
+    );
+
+    // 2.5% of 101 is rounded down
+    client.set_default_royalty(&admin, &250);
+    assert_eq!(client.royalty_info(&token_id, &101), (admin.clone(), 2));
+    assert_eq!(
+        client.royalty_info(&token_id, &1_000_000),
+        (admin.clone(), 25_000)
+    );
+
+    client.set_token_royalty(&token_id, &artist, &1_000);
+    assert_eq!(client.royalty_info(&token_id, &101), (artist.clone(), 10));
+    assert_eq!(
+        client.royalty_info(&other_token_id, &101),
+        (admin.clone(), 2)
+    );
+
+    assert_eq!(
+        client.try_set_default_royalty(&admin, &10_001),
+        Err(Ok(errors::NonFungibleTokenError::InvalidRoyalty.into()))
+    );
+    assert_eq!(
+        client.try_set_token_royalty(&99, &artist, &100),
+        Err(Ok(errors::NonFungibleTokenError::NonExistentToken.into()))
+    );
+}
+
+#[test]
+fn test_minter_role() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let minter = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    assert!(!client.is_minter(&minter));
+    client.set_minter(&minter);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::MinterSet {
+            minter: minter.clone(),
+        }
+        .to_xdr(&e, &client.address),]
+    );
+    assert!(client.is_minter(&minter));
+
+    // the chip signs over the minter
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &minter, &chip, 1);
+    assert_eq!(
+        e.auths()[0].0,
+        minter,
+        "mint should require the minter's auth"
+    );
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+
+    // minting is the only admin power of a minter
+    let result = client
+        .mock_auths(&[MockAuth {
+            address: &minter,
+            invoke: &MockAuthInvoke {
+                contract: &client.address,
+                fn_name: "clawback",
+                args: (token_id, 0u32).into_val(&e),
+                sub_invokes: &[],
+            },
+        }])
+        .try_clawback(&token_id, &0);
+    assert!(result.is_err());
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    client.remove_minter(&minter);
+    assert!(!client.is_minter(&minter));
+
+    let other_chip = software_chip(2);
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &other_chip, &minter, 1, ACTION_MINT);
+    let result = client.try_mint(
+        &minter,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &other_chip).into(),
+        &1,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::NotMinter.into()))
+    );
+}
+
+#[test]
+fn test_block_chip() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    // Chip 2, nonce 1 (mint)
+    let mint = software_chip_mint_request(&e, &client, &admin, &software_chip(2), 1);
+    client.block_chip(&mint.public_key);
+    assert!(client.is_chip_blocked(&mint.public_key));
+
+    let result = client.try_mint(
+        &admin,
+        &mint.message,
+        &mint.signature,
+        &mint.recovery_id,
+        &mint.public_key.clone().into(),
+        &mint.nonce,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::ChipBlocked.into()))
+    );
+
+    client.unblock_chip(&mint.public_key);
+    assert!(!client.is_chip_blocked(&mint.public_key));
+    let token_id = client.mint(
+        &admin,
+        &mint.message,
+        &mint.signature,
+        &mint.recovery_id,
+        &mint.public_key.clone().into(),
+        &mint.nonce,
+        &0,
+    );
+
+    // blocking a claimed chip leaves the token with its owner
+    let chip = software_chip(1);
+    let other_token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+    client.block_chip(&software_chip_public_key(&e, &chip));
+    assert_eq!(client.owner_of(&other_token_id), claimant);
+    assert_ne!(token_id, other_token_id);
+
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 3, ACTION_TRANSFER);
+    let result = client.try_transfer(
+        &claimant,
+        &admin,
+        &other_token_id,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip).into(),
+        &3,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::ChipBlocked.into()))
+    );
+}
+
+#[test]
+fn test_chip_registration() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    // Chip 1, nonce 1 and chip 2, nonce 1 (mint)
+    let chip_1 = software_chip_mint_request(&e, &client, &admin, &software_chip(1), 1);
+    let chip_2 = software_chip_mint_request(&e, &client, &admin, &software_chip(2), 1);
+
+    client.register_chips(&vec![&e, chip_1.public_key.clone()]);
+    assert!(client.is_chip_registered(&chip_1.public_key));
+    assert!(!client.is_chip_registered(&chip_2.public_key));
+
+    client.require_registration(&true);
+    let result = client.try_mint(
+        &admin,
+        &chip_2.message,
+        &chip_2.signature,
+        &chip_2.recovery_id,
+        &chip_2.public_key.clone().into(),
+        &chip_2.nonce,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::ChipNotRegistered.into()))
+    );
+    let token_id_1 = client.mint(
+        &admin,
+        &chip_1.message,
+        &chip_1.signature,
+        &chip_1.recovery_id,
+        &chip_1.public_key.clone().into(),
+        &chip_1.nonce,
+        &0,
+    );
+    assert_eq!(token_id_1, 0u32);
+
+    // open minting again
+    client.require_registration(&false);
+    let token_id_2 = client.mint(
+        &admin,
+        &chip_2.message,
+        &chip_2.signature,
+        &chip_2.recovery_id,
+        &chip_2.public_key.clone().into(),
+        &chip_2.nonce,
+        &0,
+    );
+    assert_eq!(token_id_2, 1u32);
+}
+
+#[test]
+fn test_reset_nonce() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let public_key = software_chip_public_key(&e, &chip);
+    software_chip_mint(&e, &client, &admin, &chip, 1);
+    assert_eq!(client.get_nonce(&public_key.clone().into()), 1u32);
+
+    client.reset_nonce(&public_key, &5, &false);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::NonceReset {
+            public_key: public_key.clone(),
+            old_nonce: 1,
+            new_nonce: 5,
+        }
+        .to_xdr(&e, &client.address),]
+    );
+    assert_eq!(client.get_nonce(&public_key.clone().into()), 5u32);
+
+    // signatures made with older nonces are rejected
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 2, ACTION_CLAIM);
+    let result = client.try_claim(
+        &claimant,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &2,
+        &0,
+    );
+    assert!(result.is_err());
+
+    // lowering the nonce must be explicit
+    assert_eq!(
+        client.try_reset_nonce(&public_key, &1, &false),
+        Err(Ok(errors::NonFungibleTokenError::InvalidNonce.into()))
+    );
+    client.reset_nonce(&public_key, &1, &true);
+    assert_eq!(client.get_nonce(&public_key.clone().into()), 1u32);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+}
+
+#[test]
+fn test_nonce_out_of_order() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let public_key = software_chip_public_key(&e, &chip);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    assert_eq!(client.get_nonce(&public_key.clone().into()), 1u32);
+
+    // a signature made with a future nonce cannot skip ahead
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 3, ACTION_CLAIM);
+    let result = client.try_claim(
+        &claimant,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &3,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::InvalidNonce.into()))
+    );
+
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+    assert_eq!(client.owner_of(&token_id), claimant);
+    assert_eq!(client.get_nonce(&public_key.clone().into()), 2u32);
+}
+
+#[test]
+fn test_signature_expiration() {
+    let e = Env::default();
+    e.mock_all_auths();
+    e.ledger().set_sequence_number(100);
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let public_key = software_chip_public_key(&e, &chip);
+    let (signature, recovery_id) =
+        software_chip_sign_with_expiration(&e, &client, &chip, &admin, 1, ACTION_MINT, 110);
+    let token_id = client.mint(
+        &admin,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &1,
+        &110,
+    );
+
+    // the expiration is part of the signed message
+    let (signature, recovery_id) =
+        software_chip_sign_with_expiration(&e, &client, &chip, &claimant, 2, ACTION_CLAIM, 120);
+    let result = client.try_claim(
+        &claimant,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &2,
+        &130,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::InvalidSignature.into()))
+    );
+
+    e.ledger().set_sequence_number(121);
+    let result = client.try_claim(
+        &claimant,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &2,
+        &120,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::SignatureExpired.into()))
+    );
+
+    e.ledger().set_sequence_number(120);
+    client.claim(
+        &claimant,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &public_key.clone().into(),
+        &2,
+        &120,
+    );
+    assert_eq!(client.owner_of(&token_id), claimant);
+}
+
+#[test]
+fn test_check_chip_signature() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let chip = software_chip(1);
+    let public_key: Bytes = software_chip_public_key(&e, &chip).into();
+    let signer = admin.clone().to_xdr(&e);
+
+    // Checking the same signature twice does not consume the nonce
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip, &admin, 1, ACTION_MINT);
+    for _ in 0..2 {
+        assert!(client.check_chip_signature(
+            &signer,
+            &message,
+            &signature,
+            &recovery_id,
+            &public_key,
+            &1,
+            &ACTION_MINT,
+            &0,
+        ));
+        assert_eq!(client.get_nonce(&public_key), 0);
+    }
+
+    // Mismatches are reported as false instead of panicking
+    let other_key: Bytes = software_chip_public_key(&e, &software_chip(2)).into();
+    for (public_key, nonce, action) in [
+        (other_key, 1, ACTION_MINT),
+        (public_key.clone(), 2, ACTION_MINT),
+        (public_key.clone(), 1, ACTION_CLAIM),
+        (public_key.slice(1..), 1, ACTION_MINT),
+    ] {
+        assert!(!client.check_chip_signature(
+            &signer,
+            &message,
+            &signature,
+            &recovery_id,
+            &public_key,
+            &nonce,
+            &action,
+            &0,
+        ));
+    }
+
+    // The checked signature can still be used to mint
+    client.mint(
+        &admin,
+        &message,
+        &signature,
+        &recovery_id,
+        &public_key,
+        &1,
+        &0,
+    );
+    assert_eq!(client.get_nonce(&public_key), 1);
+}
+
+#[test]
+fn test_consume_chip_signature() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let redeemer = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let chip = software_chip(1);
+    let public_key: Bytes = software_chip_public_key(&e, &chip).into();
+
+    // 3 is free for other contracts, e.g. prize redemptions
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip, &redeemer, 1, 3);
+    client.consume_chip_signature(
+        &redeemer,
+        &message,
+        &signature,
+        &recovery_id,
+        &public_key,
+        &1,
+        &3,
+        &0,
+    );
+    assert_eq!(e.auths()[0].0, redeemer);
+    assert_eq!(client.get_nonce(&public_key), 1);
+
+    // The signature cannot be replayed
+    assert_eq!(
+        client.try_consume_chip_signature(
+            &redeemer,
+            &message,
+            &signature,
+            &recovery_id,
+            &public_key,
+            &1,
+            &3,
+            &0,
+        ),
+        Err(Ok(errors::NonFungibleTokenError::NonceAlreadyUsed.into()))
+    );
+
+    // Nor can signatures for the actions of this contract be burnt
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &redeemer, 2, ACTION_CLAIM);
+    assert_eq!(
+        client.try_consume_chip_signature(
+            &redeemer,
+            &message,
+            &signature,
+            &recovery_id,
+            &public_key,
+            &2,
+            &ACTION_CLAIM,
+            &0,
+        ),
+        Err(Ok(errors::NonFungibleTokenError::InvalidAction.into()))
+    );
+    assert_eq!(client.get_nonce(&public_key), 1);
+}
+
+#[test]
+fn test_upgrade_and_migrate() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    assert_eq!(client.version(), CONTRACT_VERSION);
+    assert_eq!(client.storage_version(), STORAGE_VERSION);
+
+    // Instances deployed before storage versioning have no stored version
+    e.as_contract(&client.address, || {
+        e.storage()
+            .instance()
+            .remove(&crate::contract::DataKey::StorageVersion);
+    });
+    assert_eq!(client.storage_version(), 0);
+    client.migrate();
+    assert_eq!(e.auths()[0].0, admin);
+    assert_eq!(client.storage_version(), STORAGE_VERSION);
+
+    let wasm_hash = e
+        .deployer()
+        .upload_contract_wasm(include_bytes!("../../nfc_nft.wasm").as_slice());
+    client.upgrade(&wasm_hash);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::Upgrade {
+            admin: admin.clone(),
+            wasm_hash,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert_eq!(client.version(), CONTRACT_VERSION);
+}
+
+#[test]
+fn test_extend_ttl() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+    let public_key: Bytes = software_chip_public_key(&e, &chip).into();
+
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+
+    // Claims extend the token entries to 30 days
+    let day = 17280;
+    let owner_ttl = || {
+        e.as_contract(&client.address, || {
+            e.storage()
+                .persistent()
+                .get_ttl(&crate::contract::NFTStorageKey::Owner(token_id))
+        })
+    };
+    assert_eq!(owner_ttl(), 30 * day);
+
+    client.extend_token_ttl(&token_id, &(90 * day));
+    client.extend_instance_ttl(&(90 * day));
+    assert_eq!(owner_ttl(), 90 * day);
+
+    // Past the 30 days default everything is still readable
+    e.ledger()
+        .set_sequence_number(e.ledger().sequence() + 60 * day);
+    assert_eq!(client.owner_of(&token_id), claimant);
+    assert_eq!(client.token_id(&public_key), token_id);
+    assert_eq!(client.get_nonce(&public_key), 2);
+    assert_eq!(client.balance(&claimant), 1);
+    assert_eq!(client.tokens_of_owner(&claimant), vec![&e, token_id]);
+
+    // Extensions are capped at the maximum TTL
+    client.extend_token_ttl(&token_id, &u32::MAX);
+    assert_eq!(owner_ttl(), e.storage().max_ttl());
+
+    assert_eq!(
+        client.try_extend_token_ttl(&1, &day),
+        Err(Ok(errors::NonFungibleTokenError::NonExistentToken.into()))
+    );
+}
+
+#[test]
+fn test_transfer_extends_touched_entries() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &owner, &chip, 2);
+
+    let day = 17280;
+    let ttl = |key: crate::contract::NFTStorageKey| {
+        e.as_contract(&client.address, || e.storage().persistent().get_ttl(&key))
+    };
+    e.ledger()
+        .set_sequence_number(e.ledger().sequence() + 10 * day);
+    client.extend_instance_ttl(&(30 * day));
+    software_chip_transfer(&e, &client, &owner, &recipient, &chip, 3);
+
+    // The entries the transfer wrote are extended, the others are left alone
+    let touched = [
+        crate::contract::NFTStorageKey::Owner(token_id),
+        crate::contract::NFTStorageKey::Balance(recipient.clone()),
+        crate::contract::NFTStorageKey::OwnerTokenIndex(token_id),
+        crate::contract::NFTStorageKey::Provenance(token_id),
+    ];
+    for key in touched {
+        assert_eq!(ttl(key), 30 * day);
+    }
+    assert_eq!(
+        ttl(crate::contract::NFTStorageKey::History(token_id)),
+        20 * day
+    );
+}
+
+#[test]
+fn test_rotate_chip() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let chip_1 = software_chip(1);
+    let chip_2 = software_chip(2);
+    let old_public_key = software_chip_public_key(&e, &chip_1);
+    let new_public_key = software_chip_public_key(&e, &chip_2);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &chip_1, 1);
+    software_chip_claim(&e, &client, &claimant, &chip_1, 2);
+
+    // Chip 2 signs over the owner to take over the token
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip_2, &claimant, 1, ACTION_ROTATE);
+    client.rotate_chip(
+        &token_id,
+        &new_public_key,
+        &message,
+        &signature,
+        &recovery_id,
+        &1,
+    );
+    let auths: Vec<Address> = e.auths().into_iter().map(|(address, _)| address).collect();
+    assert!(auths.contains(&claimant) && auths.contains(&admin));
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::ChipRotated {
+            token_id,
+            old_public_key: old_public_key.clone(),
+            new_public_key: new_public_key.clone(),
+        }
+        .to_xdr(&e, &client.address)]
+    );
+
+    assert_eq!(client.public_key(&token_id), new_public_key);
+    assert_eq!(client.token_id(&new_public_key.clone().into()), token_id);
+    assert_eq!(client.get_nonce(&new_public_key.clone().into()), 1);
+    assert_eq!(
+        client.try_token_id(&old_public_key.clone().into()),
+        Err(Ok(errors::NonFungibleTokenError::NonExistentToken.into()))
+    );
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    // The new chip transfers the token, the old one cannot anymore
+    let recipient = Address::generate(&e);
+    software_chip_transfer(&e, &client, &claimant, &recipient, &chip_2, 2);
+    assert_eq!(client.owner_of(&token_id), recipient);
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip_1, &recipient, 3, ACTION_TRANSFER);
+    assert!(
+        client
+            .try_transfer(
+                &recipient,
+                &claimant,
+                &token_id,
+                &message,
+                &signature,
+                &recovery_id,
+                &old_public_key.clone().into(),
+                &3,
+                &0,
+            )
+            .is_err()
+    );
+
+    // The old chip cannot be minted as a new token nor rotated to again
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip_1, &admin, 3, ACTION_MINT);
+    let result = client.try_mint(
+        &admin,
+        &message,
+        &signature,
+        &recovery_id,
+        &old_public_key.clone().into(),
+        &3,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::ChipRetired.into()))
+    );
+    assert_eq!(
+        client.try_admin_mint(&old_public_key),
+        Err(Ok(errors::NonFungibleTokenError::ChipRetired.into()))
+    );
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip_1, &recipient, 3, ACTION_ROTATE);
+    let result = client.try_rotate_chip(
+        &token_id,
+        &old_public_key,
+        &message,
+        &signature,
+        &recovery_id,
+        &3,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::ChipRetired.into()))
+    );
+
+    // Rotating to a chip bound to another token fails
+    let chip_3 = software_chip(3);
+    software_chip_mint(&e, &client, &admin, &chip_3, 1);
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip_3, &recipient, 2, ACTION_ROTATE);
+    let result = client.try_rotate_chip(
+        &token_id,
+        &software_chip_public_key(&e, &chip_3),
+        &message,
+        &signature,
+        &recovery_id,
+        &2,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::TokenAlreadyMinted.into()))
+    );
+}
+
+#[test]
+fn test_token_info() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+
+    e.ledger().with_mut(|ledger| {
+        ledger.sequence_number = 100;
+        ledger.timestamp = 1_700_000_000;
+    });
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    assert_eq!(
+        client.token_info(&token_id),
+        TokenInfo {
+            owner: None,
+            public_key: software_chip_public_key(&e, &chip),
+            minted_ledger: 100,
+            minted_timestamp: 1_700_000_000,
+            claimed_ledger: None,
+            claimed_timestamp: None,
+            status: TokenStatus::Minted,
+        }
+    );
+
+    e.ledger().with_mut(|ledger| {
+        ledger.sequence_number = 250;
+        ledger.timestamp = 1_700_000_750;
+    });
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+    let info = client.token_info(&token_id);
+    assert_eq!(info.owner, Some(claimant));
+    assert_eq!(info.minted_ledger, 100);
+    assert_eq!(info.claimed_ledger, Some(250));
+    assert_eq!(info.claimed_timestamp, Some(1_700_000_750));
+    assert_eq!(info.status, TokenStatus::Claimed);
+
+    assert_eq!(
+        client.try_token_info(&1),
+        Err(Ok(errors::NonFungibleTokenError::NonExistentToken.into()))
+    );
+}
+
+#[test]
+fn test_provenance() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    assert_eq!(client.provenance(&token_id), vec![&e]);
+
+    e.ledger().set_sequence_number(10);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+    e.ledger().set_sequence_number(20);
+    software_chip_transfer(&e, &client, &claimant, &recipient, &chip, 3);
+    e.ledger().set_sequence_number(30);
+    client.clawback(&token_id, &0);
+
+    assert_eq!(
+        client.provenance(&token_id),
+        vec![
+            &e,
+            ProvenanceEntry {
+                from: client.address.clone(),
+                to: claimant.clone(),
+                ledger: 10,
+            },
+            ProvenanceEntry {
+                from: claimant.clone(),
+                to: recipient.clone(),
+                ledger: 20,
+            },
+            ProvenanceEntry {
+                from: recipient.clone(),
+                to: admin.clone(),
+                ledger: 30,
+            },
+        ]
+    );
+
+    // Only the last `provenance_limit` changes are kept
+    let client = deploy_client(&e, &admin, 10_000, false, 2, 0);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+    software_chip_transfer(&e, &client, &claimant, &recipient, &chip, 3);
+    client.clawback(&token_id, &0);
+    let provenance = client.provenance(&token_id);
+    assert_eq!(provenance.len(), 2);
+    assert_eq!(provenance.get_unchecked(0).from, claimant);
+    assert_eq!(provenance.get_unchecked(1).to, admin);
+
+    assert_eq!(
+        client.try_provenance(&1),
+        Err(Ok(errors::NonFungibleTokenError::NonExistentToken.into()))
+    );
+}
+
+#[test]
+fn test_safe_transfer() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let accepting = e.register(AcceptingReceiver, ());
+    let rejecting = e.register(RejectingReceiver, ());
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+
+    // Accounts are not checked
+    software_chip_safe_transfer(&e, &client, &claimant, &recipient, &chip, 3).unwrap();
+    assert_eq!(client.owner_of(&token_id), recipient);
+
+    software_chip_safe_transfer(&e, &client, &recipient, &accepting, &chip, 4).unwrap();
+    assert_eq!(client.owner_of(&token_id), accepting);
+
+    // A rejecting contract reverts the whole transfer
+    let client = create_client(&e, &admin);
+    software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+    assert_eq!(
+        software_chip_safe_transfer(&e, &client, &claimant, &rejecting, &chip, 3),
+        Err(Ok(errors::NonFungibleTokenError::UnsafeRecipient.into()))
+    );
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    // Contracts without the hook are rejected as well
+    let other = create_client(&e, &admin);
+    assert_eq!(
+        software_chip_safe_transfer(&e, &client, &claimant, &other.address, &chip, 3),
+        Err(Ok(errors::NonFungibleTokenError::UnsafeRecipient.into()))
+    );
+}
+
+#[test]
+fn test_transfer_owner() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+
+    // Disabled by default
+    assert!(!client.is_chipless_transfer_enabled());
+    assert_eq!(
+        client.try_transfer_owner(&claimant, &recipient, &token_id),
+        Err(Ok(errors::NonFungibleTokenError::OperationDisabled.into()))
+    );
+
+    client.set_chipless_transfer_enabled(&true);
+    assert!(client.is_chipless_transfer_enabled());
+
+    assert_eq!(
+        client.try_transfer_owner(&recipient, &claimant, &token_id),
+        Err(Ok(errors::NonFungibleTokenError::IncorrectOwner.into()))
+    );
+
+    client.transfer_owner(&claimant, &recipient, &token_id);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::Transfer {
+            from: claimant.clone(),
+            to: recipient.clone(),
+            token_id,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert_eq!(client.owner_of(&token_id), recipient);
+    assert_eq!(client.balance(&claimant), 0);
+    assert_eq!(client.balance(&recipient), 1);
+    assert_eq!(client.tokens_of_owner(&recipient), vec![&e, token_id]);
+}
+
+#[test]
+fn test_message_hash() {
+    let e = Env::default();
+
+    let admin = Address::generate(&e);
+    let signer = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+
+    assert_eq!(
+        client.message_hash(&signer, &message, &3, &ACTION_CLAIM, &0),
+        calculate_message_hash(&e, &client.address, TEST_MESSAGE, &signer, 3, ACTION_CLAIM)
+            .to_bytes()
+    );
+    assert_eq!(
+        client.message_hash(&signer, &message, &3, &ACTION_TRANSFER, &100),
+        calculate_message_hash_with_expiration(
+            &e,
+            &client.address,
+            TEST_MESSAGE,
+            &signer,
+            3,
+            ACTION_TRANSFER,
+            100
+        )
+        .to_bytes()
+    );
+    assert_eq!(
+        client.try_message_hash(&signer, &message, &3, &256, &0),
+        Err(Ok(errors::NonFungibleTokenError::InvalidAction.into()))
+    );
+}
+
+#[test]
+fn test_unclaimed_tokens() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip_1 = software_chip(1);
+    let chip_2 = software_chip(2);
+
+    assert_eq!(client.unclaimed_count(), 0);
+    assert_eq!(client.unclaimed_tokens(&0, &10), vec![&e]);
+
+    let token_id_1 = software_chip_mint(&e, &client, &admin, &chip_1, 1);
+    let token_id_2 = software_chip_mint(&e, &client, &admin, &chip_2, 1);
+    assert_eq!(client.unclaimed_count(), 2);
+
+    software_chip_claim(&e, &client, &claimant, &chip_1, 2);
+    assert_eq!(client.unclaimed_count(), 1);
+    assert_eq!(client.unclaimed_tokens(&0, &10), vec![&e, token_id_2]);
+
+    // `limit` bounds the ids looked at, not the ids returned
+    assert_eq!(client.unclaimed_tokens(&0, &1), vec![&e]);
+    assert_eq!(client.unclaimed_tokens(&1, &1), vec![&e, token_id_2]);
+    assert_eq!(client.unclaimed_tokens(&2, &10), vec![&e]);
+
+    // Clawbacks do not make a token unclaimed
+    client.clawback(&token_id_1, &0);
+    assert_eq!(client.unclaimed_count(), 1);
+}
+
+#[test]
+fn test_admin_mint() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip_1 = software_chip(1);
+    let chip_2 = software_chip(2);
+    let chip_3 = software_chip(3);
+
+    let token_id = client.admin_mint(&software_chip_public_key(&e, &chip_1));
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::Mint {
+            to: client.address.clone(),
+            token_id,
+            public_key: software_chip_public_key(&e, &chip_1),
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert_eq!(
+        client.public_key(&token_id),
+        software_chip_public_key(&e, &chip_1)
+    );
+    assert_eq!(
+        client.get_nonce(&software_chip_public_key(&e, &chip_1).into()),
+        0
+    );
+
+    // Claiming still requires the chip, starting at nonce 1
+    assert_eq!(
+        software_chip_claim(&e, &client, &claimant, &chip_1, 1),
+        token_id
+    );
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    let token_ids = client.admin_mint_batch(&vec![
+        &e,
+        software_chip_public_key(&e, &chip_2),
+        software_chip_public_key(&e, &chip_3),
+    ]);
+    assert_eq!(token_ids, vec![&e, 1, 2]);
+    assert_eq!(client.unclaimed_count(), 2);
+
+    assert_eq!(
+        client.try_admin_mint(&software_chip_public_key(&e, &chip_1)),
+        Err(Ok(errors::NonFungibleTokenError::TokenAlreadyMinted.into()))
+    );
+    assert_eq!(
+        client.try_admin_mint(&BytesN::from_array(&e, &[0u8; 65])),
+        Err(Ok(errors::NonFungibleTokenError::InvalidPublicKey.into()))
+    );
+
+    // Batches are atomic
+    let chip_4 = software_chip(4);
+    assert_eq!(
+        client.try_admin_mint_batch(&vec![
+            &e,
+            software_chip_public_key(&e, &chip_4),
+            software_chip_public_key(&e, &chip_4),
+        ]),
+        Err(Ok(errors::NonFungibleTokenError::TokenAlreadyMinted.into()))
+    );
+    assert_eq!(client.total_supply(), 3);
+}
+
+// The same signature with S replaced by n - S, which also verifies mathematically
+fn high_s_signature(e: &Env, signature: &BytesN<64>) -> BytesN<64> {
+    let signature = k256::ecdsa::Signature::from_slice(&signature.to_array()).unwrap();
+    let (r, s) = signature.split_scalars();
+    let high_s = k256::ecdsa::Signature::from_scalars(r, -s).unwrap();
+    let signature_bytes: [u8; 64] = high_s.to_bytes().into();
+    BytesN::from_array(e, &signature_bytes)
+}
+
+#[test]
+fn test_malleable_signature() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let public_key = software_chip_public_key(&e, &chip);
+
+    // The fixtures are normalized off-chain, flipping S back makes them malleable
+    let fixture = &TEST_SIGNATURES[0];
+    let mut fixture_signature = [0u8; 64];
+    fixture_signature[..32].copy_from_slice(&fixture.sig_r);
+    fixture_signature[32..].copy_from_slice(&normalize_s(&fixture.sig_s));
+    let fixture_signature = high_s_signature(&e, &BytesN::from_array(&e, &fixture_signature));
+    let result = client.try_mint(
+        &admin,
+        &Bytes::from_slice(&e, fixture.message),
+        &fixture_signature,
+        &RECOVERY_ID_AUTO,
+        &Bytes::from_array(&e, &fixture.public_key),
+        &fixture.nonce,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::MalleableSignature.into()))
+    );
+
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip, &admin, 1, ACTION_MINT);
+    let high_s = high_s_signature(&e, &signature);
+    assert_eq!(
+        client.try_mint(
+            &admin,
+            &message,
+            &high_s,
+            &(recovery_id ^ 1),
+            &public_key.clone().into(),
+            &1,
+            &0,
+        ),
+        Err(Ok(errors::NonFungibleTokenError::MalleableSignature.into()))
+    );
+    assert!(!client.check_chip_signature(
+        &admin.clone().to_xdr(&e),
+        &message,
+        &high_s,
+        &(recovery_id ^ 1),
+        &public_key.clone().into(),
+        &1,
+        &ACTION_MINT,
+        &0,
+    ));
+
+    // Only SEC1 prefixes are accepted
+    let mut bad_key = public_key.to_array();
+    bad_key[0] = 0x05;
+    assert_eq!(
+        client.try_mint(
+            &admin,
+            &message,
+            &signature,
+            &recovery_id,
+            &Bytes::from_array(&e, &bad_key),
+            &1,
+            &0,
+        ),
+        Err(Ok(errors::NonFungibleTokenError::InvalidPublicKey.into()))
+    );
+
+    // The normalized signature still mints
+    client.mint(
+        &admin,
+        &message,
+        &signature,
+        &recovery_id,
+        &public_key.into(),
+        &1,
+        &0,
+    );
+}
+
+#[test]
+fn test_mint_fee() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let treasury = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+
+    let sac = e.register_stellar_asset_contract_v2(Address::generate(&e));
+    let asset = sac.address();
+    token::StellarAssetClient::new(&e, &asset).mint(&admin, &1_000);
+    let token_client = token::TokenClient::new(&e, &asset);
+
+    assert_eq!(client.mint_fee(), None);
+    assert_eq!(client.withdraw_fees(&asset, &treasury), 0);
+    assert_eq!(
+        client.try_set_mint_fee(&asset, &-1),
+        Err(Ok(errors::NonFungibleTokenError::InvalidFee.into()))
+    );
+    client.set_mint_fee(&asset, &100);
+    assert_eq!(
+        client.mint_fee(),
+        Some(MintFee {
+            asset: asset.clone(),
+            amount: 100,
+        })
+    );
+
+    // Failed mints are not charged
+    let chip_1 = software_chip(1);
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip_1, &admin, 2, ACTION_MINT);
+    assert!(
+        client
+            .try_mint(
+                &admin,
+                &message,
+                &signature,
+                &recovery_id,
+                &software_chip_public_key(&e, &chip_1).into(),
+                &2,
+                &0,
+            )
+            .is_err()
+    );
+    assert_eq!(token_client.balance(&admin), 1_000);
+
+    software_chip_mint(&e, &client, &admin, &chip_1, 1);
+    assert_eq!(token_client.balance(&admin), 900);
+    assert_eq!(token_client.balance(&client.address), 100);
+
+    let chip_2 = software_chip(2);
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip_2, &admin, 1, ACTION_MINT);
+    client.mint_to(
+        &treasury,
+        &message,
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip_2),
+        &1,
+    );
+    assert_eq!(token_client.balance(&admin), 800);
+
+    // A zero fee disables it, the collected fees can still be withdrawn
+    client.set_mint_fee(&asset, &0);
+    software_chip_mint(&e, &client, &admin, &software_chip(3), 1);
+    assert_eq!(token_client.balance(&admin), 800);
+
+    assert_eq!(client.withdraw_fees(&asset, &treasury), 200);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::FeesWithdrawn {
+            to: treasury.clone(),
+            asset: asset.clone(),
+            amount: 200,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert_eq!(token_client.balance(&treasury), 200);
+    assert_eq!(token_client.balance(&client.address), 0);
+
+    // Fees of a previous asset can still be withdrawn
+    let other_sac = e.register_stellar_asset_contract_v2(Address::generate(&e));
+    let other_asset = other_sac.address();
+    token::StellarAssetClient::new(&e, &other_asset).mint(&admin, &1_000);
+    client.set_mint_fee(&asset, &100);
+    software_chip_mint(&e, &client, &admin, &software_chip(4), 1);
+    client.set_mint_fee(&other_asset, &50);
+    software_chip_mint(&e, &client, &admin, &software_chip(5), 1);
+    assert_eq!(client.withdraw_fees(&asset, &treasury), 100);
+    assert_eq!(client.withdraw_fees(&other_asset, &treasury), 50);
+    assert_eq!(token_client.balance(&treasury), 300);
+    assert_eq!(
+        token::TokenClient::new(&e, &other_asset).balance(&treasury),
+        50
+    );
+}
+
+#[test]
+fn test_verify_presence() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    // Same address order as print_message_hash_for_signing
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let token_id_1 = client.admin_mint(&BytesN::from_array(&e, &CHIP1_PUBLIC_KEY));
+    let token_id_2 = client.admin_mint(&BytesN::from_array(&e, &CHIP2_PUBLIC_KEY));
+
+    // The fixtures signed sha256(message || signer || nonce), which is the
+    // challenge the chip signs here
+    let fixture = &TEST_SIGNATURES[0];
+    let mut challenge = Bytes::from_slice(&e, fixture.message);
+    challenge.append(&admin.clone().to_xdr(&e));
+    challenge.append(&fixture.nonce.to_xdr(&e));
+    let (signature, recovery_id) = create_test_signature_and_recovery_id(
+        &e,
+        &calculate_fixture_message_hash(&e, fixture.message, &admin, fixture.nonce),
+        fixture,
+    );
+
+    assert!(client.verify_presence(&token_id_1, &challenge, &signature, &recovery_id));
+    assert!(client.verify_presence(&token_id_1, &challenge, &signature, &RECOVERY_ID_AUTO));
+    assert!(!client.verify_presence(&token_id_2, &challenge, &signature, &recovery_id));
+    assert!(!client.verify_presence(
+        &token_id_1,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id
+    ));
+    assert!(!client.verify_presence(&token_id_1, &challenge, &signature, &2));
+    assert!(!client.verify_presence(&2, &challenge, &signature, &recovery_id));
+
+    // Nothing is consumed
+    assert_eq!(
+        client.get_nonce(&Bytes::from_array(&e, &CHIP1_PUBLIC_KEY)),
+        0
+    );
+}
+
+#[test]
+fn test_garbage_signature() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+    let public_key = software_chip_public_key(&e, &chip);
+    let token_id = client.admin_mint(&public_key);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let signer = admin.clone().to_xdr(&e);
+    let signature = |r: u8, s: u8| {
+        let mut bytes = [0u8; 64];
+        bytes[..32].fill(r);
+        bytes[63] = s;
+        BytesN::from_array(&e, &bytes)
+    };
+    let check = |signature: &BytesN<64>| {
+        client.try_check_chip_signature(
+            &signer,
+            &message,
+            signature,
+            &RECOVERY_ID_AUTO,
+            &public_key.clone().into(),
+            &1,
+            &ACTION_MINT,
+            &0,
+        )
+    };
+    let presence = |signature: &BytesN<64>| {
+        client.try_verify_presence(&token_id, &message, signature, &RECOVERY_ID_AUTO)
+    };
+
+    // Zero or out of range scalars are reported as invalid
+    for garbage in [
+        signature(0, 0),
+        signature(0, 1),
+        signature(0xFF, 1),
+        signature(1, 0),
+    ] {
+        assert_eq!(check(&garbage), Ok(Ok(false)));
+        assert_eq!(presence(&garbage), Ok(Ok(false)));
+    }
+
+    // The host fails recoveries from an r off the curve, x = 5 has no y
+    let mut off_curve = [0u8; 64];
+    off_curve[31] = 5;
+    off_curve[63] = 1;
+    let off_curve = BytesN::from_array(&e, &off_curve);
+    assert!(check(&off_curve).is_err());
+    assert!(presence(&off_curve).is_err());
+}
+
+fn software_chip_sign_payload(
+    e: &Env,
+    client: &NFCtoNFTClient,
+    chip: &SigningKey,
+    signer: &Address,
+    nonce: u32,
+    action: u32,
+    payload: &SignedAction,
+) -> (BytesN<64>, u32) {
+    let message_hash = calculate_message_hash(
+        e,
+        &client.address,
+        &signed_action_bytes(e, payload),
+        signer,
+        nonce,
+        action,
+    );
+    let (signature, recovery_id) = chip
+        .sign_prehash_recoverable(&message_hash.to_array())
+        .unwrap();
+    let signature_bytes: [u8; 64] = signature.to_bytes().into();
+    (
+        BytesN::from_array(e, &signature_bytes),
+        recovery_id.to_byte() as u32,
+    )
+}
+
+#[test]
+fn test_signed_action() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+    let public_key: Bytes = software_chip_public_key(&e, &chip).into();
+
+    let mint = SignedAction {
+        action: Symbol::new(&e, "mint"),
+        token_id: None,
+        to: None,
+        memo: Some(String::from_str(&e, "batch 7")),
+    };
+    let (signature, recovery_id) =
+        software_chip_sign_payload(&e, &client, &chip, &admin, 1, ACTION_MINT, &mint);
+    let token_id = client.mint_v2(&admin, &mint, &signature, &recovery_id, &public_key, &1, &0);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [
+            events::Mint {
+                to: client.address.clone(),
+                token_id,
+                public_key: software_chip_public_key(&e, &chip),
+            }
+            .to_xdr(&e, &client.address),
+            events::Memo {
+                token_id,
+                memo: String::from_str(&e, "batch 7"),
+            }
+            .to_xdr(&e, &client.address),
+        ]
+    );
+
+    // The action must match the entry point
+    let (signature, recovery_id) =
+        software_chip_sign_payload(&e, &client, &chip, &claimant, 2, ACTION_CLAIM, &mint);
+    assert_eq!(
+        client.try_claim_v2(
+            &claimant,
+            &mint,
+            &signature,
+            &recovery_id,
+            &public_key,
+            &2,
+            &0
+        ),
+        Err(Ok(errors::NonFungibleTokenError::PayloadMismatch.into()))
+    );
+
+    let claim = SignedAction {
+        action: Symbol::new(&e, "claim"),
+        token_id: Some(token_id),
+        to: None,
+        memo: None,
+    };
+    let (signature, recovery_id) =
+        software_chip_sign_payload(&e, &client, &chip, &claimant, 2, ACTION_CLAIM, &claim);
+    assert_eq!(
+        client.claim_v2(
+            &claimant,
+            &claim,
+            &signature,
+            &recovery_id,
+            &public_key,
+            &2,
+            &0
+        ),
+        token_id
+    );
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    // The signed recipient cannot be swapped
+    let transfer = SignedAction {
+        action: Symbol::new(&e, "transfer"),
+        token_id: Some(token_id),
+        to: Some(recipient.clone()),
+        memo: None,
+    };
+    let (signature, recovery_id) =
+        software_chip_sign_payload(&e, &client, &chip, &claimant, 3, ACTION_TRANSFER, &transfer);
+    assert_eq!(
+        client.try_transfer_v2(
+            &claimant,
+            &admin,
+            &token_id,
+            &transfer,
+            &signature,
+            &recovery_id,
+            &public_key,
+            &3,
+            &0,
+        ),
+        Err(Ok(errors::NonFungibleTokenError::PayloadMismatch.into()))
+    );
+    client.transfer_v2(
+        &claimant,
+        &recipient,
+        &token_id,
+        &transfer,
+        &signature,
+        &recovery_id,
+        &public_key,
+        &3,
+        &0,
+    );
+    assert_eq!(client.owner_of(&token_id), recipient);
+}
+
+#[test]
+fn test_chip_url() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let other = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    assert_eq!(client.chip_url(&token_id), None);
+
+    let url = String::from_str(&e, "https://chimpdao.xyz/verify/0");
+    client.set_chip_url(&admin, &token_id, &url);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::ChipUrlUpdated {
+            token_id,
+            url: url.clone(),
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert_eq!(client.chip_url(&token_id), Some(url));
+
+    // The owner can overwrite it, others cannot
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+    let url = String::from_str(&e, "https://example.com/chip");
+    client.set_chip_url(&claimant, &token_id, &url);
+    assert_eq!(client.chip_url(&token_id), Some(url.clone()));
+    assert_eq!(
+        client.try_set_chip_url(&other, &token_id, &url),
+        Err(Ok(errors::NonFungibleTokenError::NotAuthorized.into()))
+    );
+    assert_eq!(
+        client.try_set_chip_url(&admin, &1, &url),
+        Err(Ok(errors::NonFungibleTokenError::NonExistentToken.into()))
+    );
+
+    let long_url = String::from_bytes(&e, &[b'a'; MAX_CHIP_URL_LEN as usize + 1]);
+    assert_eq!(
+        client.try_set_chip_url(&admin, &token_id, &long_url),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+}
+
+#[test]
+fn test_mint_rate_limit() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+
+    assert_eq!(client.mint_rate_limit(), 0);
+    client.set_mint_rate_limit(&1);
+    assert_eq!(client.mint_rate_limit(), 1);
+
+    software_chip_mint(&e, &client, &admin, &software_chip(1), 1);
+
+    let chip_2 = software_chip(2);
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip_2, &admin, 1, ACTION_MINT);
+    assert_eq!(
+        client.try_mint(
+            &admin,
+            &message,
+            &signature,
+            &recovery_id,
+            &software_chip_public_key(&e, &chip_2).into(),
+            &1,
+            &0,
+        ),
+        Err(Ok(errors::NonFungibleTokenError::RateLimitExceeded.into()))
+    );
+    assert_eq!(
+        client.try_admin_mint(&software_chip_public_key(&e, &chip_2)),
+        Err(Ok(errors::NonFungibleTokenError::RateLimitExceeded.into()))
+    );
+
+    // The count restarts in the next ledger
+    e.ledger().set_sequence_number(e.ledger().sequence() + 1);
+    software_chip_mint(&e, &client, &admin, &chip_2, 1);
+
+    // Batches count every token
+    e.ledger().set_sequence_number(e.ledger().sequence() + 1);
+    assert_eq!(
+        client.try_admin_mint_batch(&vec![
+            &e,
+            software_chip_public_key(&e, &software_chip(3)),
+            software_chip_public_key(&e, &software_chip(4)),
+        ]),
+        Err(Ok(errors::NonFungibleTokenError::RateLimitExceeded.into()))
+    );
+
+    client.set_mint_rate_limit(&0);
+    client.admin_mint_batch(&vec![
+        &e,
+        software_chip_public_key(&e, &software_chip(3)),
+        software_chip_public_key(&e, &software_chip(4)),
+    ]);
+    assert_eq!(client.total_supply(), 4);
+}
+
+#[test]
+fn test_set_registry() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let registry = e.register(collection::Mock, ());
+
+    assert_ne!(client.registry(), registry);
+    client.set_registry(&registry);
+    assert_eq!(client.registry(), registry);
+
+    // Claims keep working against the new registry
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &claimant, &chip, 2);
+    assert_eq!(client.owner_of(&token_id), claimant);
+}
+
+#[test]
+fn test_reserve_range() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+
+    assert_eq!(
+        client.try_reserve_range(&10_001),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+    client.reserve_range(&10);
+    assert_eq!(client.reserved_range(), 10);
+    assert_eq!(client.next_token_id(), 10);
+    assert_eq!(client.total_supply(), 0);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &software_chip(1), 1);
+    assert_eq!(token_id, 10);
+    assert_eq!(
+        client.try_reserve_range(&20),
+        Err(Ok(errors::NonFungibleTokenError::MintingStarted.into()))
+    );
+
+    let mint_reserved = |chip: &SigningKey, token_id: u32| {
+        let (signature, recovery_id) =
+            software_chip_sign(&e, &client, chip, &admin, 1, ACTION_MINT);
+        client.try_mint_reserved(
+            &token_id,
+            &message,
+            &signature,
+            &recovery_id,
+            &software_chip_public_key(&e, chip).into(),
+            &1,
+            &0,
+        )
+    };
+
+    assert_eq!(mint_reserved(&software_chip(2), 3), Ok(Ok(3)));
+    assert_eq!(
+        client.public_key(&3),
+        software_chip_public_key(&e, &software_chip(2))
+    );
+    assert_eq!(client.next_token_id(), 11);
+    assert_eq!(client.total_supply(), 2);
+    assert_eq!(client.tokens(&0, &20), vec![&e, 3, 10]);
+
+    assert_eq!(
+        mint_reserved(&software_chip(3), 3),
+        Err(Ok(errors::NonFungibleTokenError::TokenAlreadyMinted.into()))
+    );
+    assert_eq!(
+        mint_reserved(&software_chip(3), 10),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+}
+
+#[test]
+fn test_admin_threshold() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let admin_2 = Address::generate(&e);
+    let admin_3 = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    assert_eq!(client.admins(), vec![&e, admin.clone()]);
+    assert_eq!(client.admin_threshold(), 1);
+
+    let admins = vec![&e, admin.clone(), admin_2.clone(), admin_3.clone()];
+    assert_eq!(
+        client.try_set_admins(&admins, &0),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+    assert_eq!(
+        client.try_set_admins(&admins, &4),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+    client.set_admins(&admins, &2);
+    assert_eq!(client.admins(), admins);
+    assert_eq!(client.admin_threshold(), 2);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &owner, &chip, 2);
+
+    assert_eq!(
+        client.try_clawback(&token_id, &0),
+        Err(Ok(errors::NonFungibleTokenError::ProposalRequired.into()))
+    );
+    assert_eq!(
+        client.try_set_admins(&admins, &1),
+        Err(Ok(errors::NonFungibleTokenError::ProposalRequired.into()))
+    );
+    assert_eq!(
+        client.try_propose_action(&owner, &AdminAction::Clawback(token_id, 7)),
+        Err(Ok(errors::NonFungibleTokenError::NotAuthorized.into()))
+    );
+
+    let proposal_id = client.propose_action(&admin_2, &AdminAction::Clawback(token_id, 7));
+    let proposal = client.proposal(&proposal_id);
+    assert_eq!(proposal.approvals, vec![&e, admin_2.clone()]);
+    assert_eq!(proposal.deadline, e.ledger().sequence() + PROPOSAL_LIFETIME);
+    assert_eq!(client.owner_of(&token_id), owner);
+
+    assert_eq!(
+        client.try_approve_action(&admin_2, &proposal_id),
+        Err(Ok(errors::NonFungibleTokenError::AlreadyApproved.into()))
+    );
+    client.approve_action(&admin_3, &proposal_id);
+    assert_eq!(client.owner_of(&token_id), admin);
+    assert_eq!(
+        client.try_proposal(&proposal_id),
+        Err(Ok(errors::NonFungibleTokenError::NonExistentProposal.into()))
+    );
+
+    // The admin alone cannot undo the clawback nor hand over the role
+    assert_eq!(
+        client.try_clawback_release(&token_id, &owner),
+        Err(Ok(errors::NonFungibleTokenError::ProposalRequired.into()))
+    );
+    assert_eq!(
+        client.try_admin_transfer(&token_id, &owner, &true),
+        Err(Ok(errors::NonFungibleTokenError::ProposalRequired.into()))
+    );
+    assert_eq!(
+        client.try_transfer_admin(&admin_2),
+        Err(Ok(errors::NonFungibleTokenError::ProposalRequired.into()))
+    );
+    assert_eq!(
+        client.try_set_registry(&admin_2),
+        Err(Ok(errors::NonFungibleTokenError::ProposalRequired.into()))
+    );
+    let proposal_id = client.propose_action(
+        &admin,
+        &AdminAction::ClawbackRelease(token_id, owner.clone()),
+    );
+    assert_eq!(client.owner_of(&token_id), admin);
+    client.approve_action(&admin_2, &proposal_id);
+    assert_eq!(client.owner_of(&token_id), owner);
+    let proposal_id = client.propose_action(&admin, &AdminAction::TransferAdmin(admin_2.clone()));
+    client.approve_action(&admin_3, &proposal_id);
+    assert_eq!(client.get_pending_admin(), Some(admin_2.clone()));
+
+    let uri = String::from_str(&e, "https://example.com/v2/");
+    let proposal_id = client.propose_action(&admin, &AdminAction::SetBaseUri(uri.clone()));
+    e.ledger()
+        .with_mut(|ledger| ledger.sequence_number += PROPOSAL_LIFETIME + 1);
+    assert_eq!(
+        client.try_approve_action(&admin_3, &proposal_id),
+        Err(Ok(errors::NonFungibleTokenError::ProposalExpired.into()))
+    );
+    assert_ne!(client.base_uri(), uri);
+
+    // The admins replace a lost key together
+    let admin_4 = Address::generate(&e);
+    let new_admins = vec![&e, admin.clone(), admin_2.clone(), admin_4.clone()];
+    let proposal_id =
+        client.propose_action(&admin_2, &AdminAction::SetAdmins(new_admins.clone(), 3));
+    assert_eq!(client.admins(), admins);
+    client.approve_action(&admin_3, &proposal_id);
+    assert_eq!(client.admins(), new_admins);
+    assert_eq!(client.admin_threshold(), 3);
+    assert_eq!(
+        client.try_propose_action(&admin_3, &AdminAction::SetBaseUri(uri.clone())),
+        Err(Ok(errors::NonFungibleTokenError::NotAuthorized.into()))
+    );
+
+    // Invalid thresholds fail on execution
+    let proposal_id =
+        client.propose_action(&admin, &AdminAction::SetAdmins(vec![&e, admin.clone()], 2));
+    client.approve_action(&admin_2, &proposal_id);
+    assert_eq!(
+        client.try_approve_action(&admin_4, &proposal_id),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+    assert_eq!(client.admin_threshold(), 3);
+}
+
+#[test]
+fn test_resolve_chip() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let fixture_key = BytesN::from_array(&e, &CHIP1_PUBLIC_KEY);
+
+    // Unknown chip
+    assert_eq!(
+        client.resolve_chip(&fixture_key),
+        ChipResolution {
+            token_id: None,
+            owner: None,
+            claimed: false,
+            blocked: false,
+        }
+    );
+
+    // Minted, unclaimed
+    let token_id = client.admin_mint(&fixture_key);
+    client.block_chip(&fixture_key);
+    assert_eq!(
+        client.resolve_chip(&fixture_key),
+        ChipResolution {
+            token_id: Some(token_id),
+            owner: None,
+            claimed: false,
+            blocked: true,
+        }
+    );
+
+    // Claimed, the fixtures cannot sign contract flows so use a software chip
+    let chip = software_chip(1);
+    let token_id = client.admin_mint(&software_chip_public_key(&e, &chip));
+    software_chip_claim(&e, &client, &claimant, &chip, 1);
+    assert_eq!(
+        client.resolve_chip(&software_chip_public_key(&e, &chip)),
+        ChipResolution {
+            token_id: Some(token_id),
+            owner: Some(claimant),
+            claimed: true,
+            blocked: false,
+        }
+    );
+}
+
+#[test]
+fn test_get_nonces() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip_1 = BytesN::from_array(&e, &CHIP1_PUBLIC_KEY);
+    let chip_2 = BytesN::from_array(&e, &CHIP2_PUBLIC_KEY);
+    let chip = software_chip(1);
+    let public_keys = vec![
+        &e,
+        chip_1.clone(),
+        chip_2.clone(),
+        software_chip_public_key(&e, &chip),
+    ];
+
+    assert_eq!(client.get_nonces(&public_keys), vec![&e, 0, 0, 0]);
+    assert!(client.is_nonce_used(&chip_1.clone().into(), &0));
+    assert!(!client.is_nonce_used(&chip_1.clone().into(), &1));
+
+    // Minting without a chip signature leaves the nonce untouched
+    client.admin_mint(&chip_1);
+    software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &admin, &chip, 2);
+
+    assert_eq!(client.get_nonces(&public_keys), vec![&e, 0, 0, 2]);
+    assert!(!client.is_nonce_used(&chip_1.into(), &1));
+    assert!(!client.is_nonce_used(&chip_2.into(), &1));
+    let public_key: Bytes = software_chip_public_key(&e, &chip).into();
+    assert!(client.is_nonce_used(&public_key, &1));
+    assert!(client.is_nonce_used(&public_key, &2));
+    assert!(!client.is_nonce_used(&public_key, &3));
+}
+
+#[test]
+fn test_lock() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &owner, &chip, 2);
+
+    let until_ledger = e.ledger().sequence() + 100;
+    assert_eq!(
+        client.try_lock(&recipient, &token_id, &until_ledger),
+        Err(Ok(errors::NonFungibleTokenError::IncorrectOwner.into()))
+    );
+    assert_eq!(
+        client.try_lock(&owner, &token_id, &e.ledger().sequence()),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+    client.lock(&owner, &token_id, &until_ledger);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::Lock {
+            owner: owner.clone(),
+            token_id,
+            until_ledger,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert_eq!(client.lock_info(&token_id), Some(until_ledger));
+    assert_eq!(
+        client.try_lock(&owner, &token_id, &(until_ledger - 1)),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &owner, 3, ACTION_TRANSFER);
+    let result = client.try_transfer(
+        &owner,
+        &recipient,
+        &token_id,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &chip).into(),
+        &3,
+        &0,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::TokenLocked.into()))
+    );
+
+    // Before the deadline only the admin can release the token
+    assert_eq!(
+        client.try_unlock(&owner, &token_id),
+        Err(Ok(errors::NonFungibleTokenError::NotAuthorized.into()))
+    );
+    assert_eq!(client.lock_info(&token_id), Some(until_ledger));
+
+    // After the deadline the token moves again and the owner clears the lock
+    e.ledger()
+        .with_mut(|ledger| ledger.sequence_number = until_ledger);
+    assert_eq!(client.lock_info(&token_id), None);
+    assert_eq!(
+        client.try_unlock(&recipient, &token_id),
+        Err(Ok(errors::NonFungibleTokenError::NotAuthorized.into()))
+    );
+    client.unlock(&owner, &token_id);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::Unlock {
+            owner: owner.clone(),
+            token_id,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    software_chip_transfer(&e, &client, &owner, &recipient, &chip, 3);
+    assert_eq!(client.owner_of(&token_id), recipient);
+
+    // The admin releases early or late, and clawbacks ignore the lock
+    client.lock(&recipient, &token_id, &(until_ledger + 100));
+    client.unlock(&admin, &token_id);
+    assert_eq!(client.lock_info(&token_id), None);
+    client.lock(&recipient, &token_id, &(until_ledger + 101));
+    e.ledger()
+        .with_mut(|ledger| ledger.sequence_number = until_ledger + 101);
+    client.unlock(&admin, &token_id);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::Unlock {
+            owner: recipient.clone(),
+            token_id,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    client.lock(&recipient, &token_id, &(until_ledger + 200));
+    client.clawback(&token_id, &0);
+    assert_eq!(client.owner_of(&token_id), admin);
+    assert_eq!(client.lock_info(&token_id), None);
+}
+
+#[test]
+fn test_set_user() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let user = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &owner, &chip, 2);
+    assert_eq!(client.user_of(&token_id), None);
+
+    let expires_ledger = e.ledger().sequence() + 100;
+    assert_eq!(
+        client.try_set_user(&user, &token_id, &user, &expires_ledger),
+        Err(Ok(errors::NonFungibleTokenError::IncorrectOwner.into()))
+    );
+    assert_eq!(
+        client.try_set_user(&owner, &token_id, &user, &e.ledger().sequence()),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+
+    client.set_user(&owner, &token_id, &user, &expires_ledger);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::UserSet {
+            token_id,
+            user: user.clone(),
+            expires_ledger,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert_eq!(client.user_of(&token_id), Some(user.clone()));
+    assert_eq!(client.owner_of(&token_id), owner);
+
+    e.ledger()
+        .with_mut(|ledger| ledger.sequence_number = expires_ledger - 1);
+    assert_eq!(client.user_of(&token_id), Some(user.clone()));
+    e.ledger()
+        .with_mut(|ledger| ledger.sequence_number = expires_ledger);
+    assert_eq!(client.user_of(&token_id), None);
+
+    // Transfers clear the user
+    client.set_user(&owner, &token_id, &user, &(expires_ledger + 100));
+    assert_eq!(client.user_of(&token_id), Some(user));
+    software_chip_transfer(&e, &client, &owner, &recipient, &chip, 3);
+    assert_eq!(client.user_of(&token_id), None);
+}
+
+#[test]
+fn test_owners() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner_1 = Address::generate(&e);
+    let owner_2 = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip_1 = software_chip(1);
+    let chip_3 = software_chip(3);
+    let token_id_1 = software_chip_mint(&e, &client, &admin, &chip_1, 1);
+    software_chip_mint(&e, &client, &admin, &software_chip(2), 1);
+    let token_id_3 = software_chip_mint(&e, &client, &admin, &chip_3, 1);
+    software_chip_claim(&e, &client, &owner_1, &chip_1, 2);
+    software_chip_claim(&e, &client, &owner_2, &chip_3, 2);
+
+    assert_eq!(
+        client.owners(&0, &MAX_OWNERS_PAGE),
+        vec![
+            &e,
+            (token_id_1, owner_1.clone()),
+            (token_id_3, owner_2.clone())
+        ]
+    );
+    assert_eq!(client.owners(&0, &2), vec![&e, (token_id_1, owner_1)]);
+    assert_eq!(client.owners(&2, &2), vec![&e, (token_id_3, owner_2)]);
+    assert_eq!(client.owners(&3, &2), vec![&e]);
+    assert_eq!(
+        client.try_owners(&0, &(MAX_OWNERS_PAGE + 1)),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+}
+
+#[test]
+fn test_claim_and_transfer_auth() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let public_key: Bytes = software_chip_public_key(&e, &chip).into();
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+
+    // The chip signature alone does not claim, the claimant must consent
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 2, ACTION_CLAIM);
+    let claim_args = (
+        claimant.clone(),
+        message.clone(),
+        signature.clone(),
+        recovery_id,
+        public_key.clone(),
+        2u32,
+        0u32,
+    );
+    let claim_auth = |address: &Address| {
+        client
+            .mock_auths(&[MockAuth {
+                address,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "claim",
+                    args: claim_args.clone().into_val(&e),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_claim(
+                &claimant,
+                &message,
+                &signature,
+                &recovery_id,
+                &public_key,
+                &2,
+                &0,
+            )
+    };
+    assert!(claim_auth(&recipient).is_err());
+    assert_eq!(claim_auth(&claimant), Ok(Ok(token_id)));
+    assert_eq!(e.auths().len(), 1);
+    assert_eq!(e.auths()[0].0, claimant);
+
+    // Same for the sender of a transfer
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &chip, &claimant, 3, ACTION_TRANSFER);
+    let transfer_args = (
+        claimant.clone(),
+        recipient.clone(),
+        token_id,
+        message.clone(),
+        signature.clone(),
+        recovery_id,
+        public_key.clone(),
+        3u32,
+        0u32,
+    );
+    let transfer_auth = |address: &Address| {
+        client
+            .mock_auths(&[MockAuth {
+                address,
+                invoke: &MockAuthInvoke {
+                    contract: &client.address,
+                    fn_name: "transfer",
+                    args: transfer_args.clone().into_val(&e),
+                    sub_invokes: &[],
+                },
+            }])
+            .try_transfer(
+                &claimant,
+                &recipient,
+                &token_id,
+                &message,
+                &signature,
+                &recovery_id,
+                &public_key,
+                &3,
+                &0,
+            )
+    };
+    assert!(transfer_auth(&recipient).is_err());
+    assert_eq!(transfer_auth(&claimant), Ok(Ok(())));
+    assert_eq!(e.auths().len(), 1);
+    assert_eq!(e.auths()[0].0, claimant);
+    assert_eq!(client.owner_of(&token_id), recipient);
+}
+
+#[test]
+fn test_admin_transfer_lost_chip() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    let chip = software_chip(1);
+    let token_id = software_chip_mint(&e, &client, &admin, &chip, 1);
+    software_chip_claim(&e, &client, &owner, &chip, 2);
+
+    let forced_transfer = |signers: &[&Address]| {
+        let invoke = MockAuthInvoke {
+            contract: &client.address,
+            fn_name: "admin_transfer",
+            args: (token_id, recipient.clone(), true).into_val(&e),
+            sub_invokes: &[],
+        };
+        let auths: std::vec::Vec<MockAuth> = signers
+            .iter()
+            .map(|address| MockAuth {
+                address,
+                invoke: &invoke,
+            })
+            .collect();
+        client
+            .mock_auths(&auths)
+            .try_admin_transfer(&token_id, &recipient, &true)
+    };
+
+    // Neither party can move the token alone
+    assert!(forced_transfer(&[&admin]).is_err());
+    assert!(forced_transfer(&[&owner]).is_err());
+    assert_eq!(client.owner_of(&token_id), owner);
+    assert!(!client.is_chip_lost(&token_id));
+
+    assert_eq!(
+        client.try_admin_transfer(&token_id, &recipient, &false),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+
+    assert_eq!(forced_transfer(&[&admin, &owner]), Ok(Ok(())));
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::ForcedTransfer {
+            from: owner.clone(),
+            to: recipient.clone(),
+            token_id,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert_eq!(client.owner_of(&token_id), recipient);
+    assert_eq!(client.balance(&owner), 0);
+    assert!(client.is_chip_lost(&token_id));
+}
+
+#[test]
+fn test_chip_batch() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip_1 = BytesN::from_array(&e, &CHIP1_PUBLIC_KEY);
+    let chip_2 = BytesN::from_array(&e, &CHIP2_PUBLIC_KEY);
+    let batch_a = String::from_str(&e, "2025-A");
+    let batch_b = String::from_str(&e, "2025-B");
+
+    // Before the mint
+    client.set_chip_batch(&chip_1, &batch_a);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::BatchAssigned {
+            public_key: chip_1.clone(),
+            batch: batch_a.clone(),
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    let token_id_1 = client.admin_mint(&chip_1);
+    let token_id_2 = client.admin_mint(&chip_2);
+    assert_eq!(client.chip_batch(&token_id_1), Some(batch_a.clone()));
+    assert_eq!(client.chip_batch(&token_id_2), None);
+
+    // After the mint
+    client.set_chip_batch(&chip_2, &batch_b);
+    assert_eq!(client.chip_batch(&token_id_2), Some(batch_b.clone()));
+    assert_eq!(
+        client.tokens_in_batch(&batch_a, &0, &10),
+        vec![&e, token_id_1]
+    );
+    assert_eq!(
+        client.tokens_in_batch(&batch_b, &0, &10),
+        vec![&e, token_id_2]
+    );
+
+    // Reassigned chips leave the index of their previous batch
+    client.set_chip_batch(&chip_1, &batch_b);
+    assert_eq!(client.tokens_in_batch(&batch_a, &0, &10), vec![&e]);
+    assert_eq!(
+        client.tokens_in_batch(&batch_b, &0, &10),
+        vec![&e, token_id_2, token_id_1]
+    );
+    assert_eq!(
+        client.tokens_in_batch(&batch_b, &1, &10),
+        vec![&e, token_id_1]
+    );
+    assert_eq!(client.tokens_in_batch(&batch_b, &2, &10), vec![&e]);
+    assert_eq!(client.chip_batch(&99), None);
+
+    // The last token takes the place of a chip leaving the batch
+    let chip_3 = software_chip_public_key(&e, &software_chip(3));
+    client.set_chip_batch(&chip_3, &batch_b);
+    let token_id_3 = client.admin_mint(&chip_3);
+    client.set_chip_batch(&chip_2, &batch_a);
+    assert_eq!(
+        client.tokens_in_batch(&batch_b, &0, &10),
+        vec![&e, token_id_3, token_id_1]
+    );
+    assert_eq!(
+        client.tokens_in_batch(&batch_a, &0, &10),
+        vec![&e, token_id_2]
+    );
+    assert_eq!(
+        client.try_tokens_in_batch(&batch_a, &0, &(MAX_TOKENS_PAGE + 1)),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+
+    let too_long = String::from_bytes(&e, &[b'a'; MAX_BATCH_LEN as usize + 1]);
+    assert_eq!(
+        client.try_set_chip_batch(&chip_1, &too_long),
+        Err(Ok(errors::NonFungibleTokenError::InvalidInput.into()))
+    );
+}
+
+#[test]
+fn test_supports() {
+    let e = Env::default();
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    for interface in [
+        "approvals",
+        "chip_auth",
+        "enumerable",
+        "metadata",
+        "royalties",
+        "upgradeable",
+    ] {
+        assert!(client.supports(&Symbol::new(&e, interface)), "{interface}");
+    }
+    assert!(!client.supports(&Symbol::new(&e, "burnable")));
+}
+
+#[test]
+fn test_freeze_token_metadata() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let token_id_1 = client.admin_mint(&software_chip_public_key(&e, &software_chip(1)));
+    let token_id_2 = client.admin_mint(&software_chip_public_key(&e, &software_chip(2)));
+    let key = String::from_str(&e, "color");
+    let value = String::from_str(&e, "green");
+
+    assert_eq!(
+        client.try_freeze_token_metadata(&99),
+        Err(Ok(errors::NonFungibleTokenError::NonExistentToken.into()))
+    );
+
+    client.freeze_token_metadata(&token_id_1);
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::MetadataFrozen {
+            token_id: token_id_1,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert!(client.is_metadata_frozen(&token_id_1));
+    assert!(!client.is_metadata_frozen(&token_id_2));
+    assert_eq!(
+        client.try_set_token_attribute(&token_id_1, &key, &value),
+        Err(Ok(errors::NonFungibleTokenError::MetadataFrozen.into()))
+    );
+    client.set_token_attribute(&token_id_2, &key, &value);
+
+    client.freeze_all_metadata();
+    assert_eq!(
+        e.events().all().filter_by_contract(&client.address),
+        [events::MetadataFrozen {
+            token_id: ALL_TOKENS,
+        }
+        .to_xdr(&e, &client.address)]
+    );
+    assert!(client.is_metadata_frozen(&token_id_2));
+    assert_eq!(
+        client.try_set_token_attribute(&token_id_2, &key, &value),
+        Err(Ok(errors::NonFungibleTokenError::MetadataFrozen.into()))
+    );
+    assert_eq!(
+        client.try_set_base_uri(&String::from_str(&e, "ipfs://new/")),
+        Err(Ok(errors::NonFungibleTokenError::MetadataFrozen.into()))
+    );
+}
+
+#[test]
+fn test_claim_with_referrer() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let ambassador = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+
+    let claim = |chip: &SigningKey, referrer: Option<Address>| {
+        let token_id = software_chip_mint(&e, &client, &admin, chip, 1);
+        let (signature, recovery_id) =
+            software_chip_sign(&e, &client, chip, &claimant, 2, ACTION_CLAIM);
+        let claimed = client.claim_with_referrer(
+            &claimant,
+            &message,
+            &signature,
+            &recovery_id,
+            &software_chip_public_key(&e, chip).into(),
+            &2,
+            &0,
+            &referrer,
+        );
+        assert_eq!(claimed, token_id);
+        token_id
+    };
+
+    let token_id = claim(&software_chip(1), Some(ambassador.clone()));
+    let events = e.events().all().filter_by_contract(&client.address);
+    assert_eq!(
+        events.events().last().unwrap(),
+        &events::Referral {
+            referrer: ambassador.clone(),
+            claimant: claimant.clone(),
+            token_id,
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert_eq!(client.referrer_of(&token_id), Some(ambassador.clone()));
+    assert_eq!(client.referral_count(&ambassador), 1);
+
+    let token_id = claim(&software_chip(2), None);
+    assert_eq!(client.referrer_of(&token_id), None);
+    assert_eq!(client.owner_of(&token_id), claimant);
+
+    // Self-referrals are ignored
+    let token_id = claim(&software_chip(3), Some(claimant.clone()));
+    assert_eq!(client.referrer_of(&token_id), None);
+    assert_eq!(client.referral_count(&claimant), 0);
+
+    claim(&software_chip(4), Some(ambassador.clone()));
+    assert_eq!(client.referral_count(&ambassador), 2);
+}
+
+#[test]
+fn test_backup_key() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let message = Bytes::from_slice(&e, TEST_MESSAGE);
+
+    let chip = software_chip(1);
+    let backup = software_chip(2);
+    let backup_key = software_chip_public_key(&e, &backup);
+    software_chip_mint(&e, &client, &admin, &chip, 1);
+    let token_id = software_chip_claim(&e, &client, &owner, &chip, 2);
+    assert_eq!(client.backup_key(&token_id), None);
+
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &backup, &admin, 1, ACTION_BACKUP);
+    client.add_backup_key(
+        &token_id,
+        &backup_key,
+        &message,
+        &signature,
+        &recovery_id,
+        &1,
+    );
+    let events = e.events().all().filter_by_contract(&client.address);
+    assert_eq!(
+        events.events().last().unwrap(),
+        &events::BackupKeyAdded {
+            token_id,
+            backup_key: backup_key.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert_eq!(client.backup_key(&token_id), Some(backup_key.clone()));
+    assert_eq!(client.token_id(&backup_key.clone().into()), token_id);
+
+    // Either chip transfers the token
+    software_chip_transfer(&e, &client, &owner, &recipient, &backup, 2);
+    assert_eq!(client.owner_of(&token_id), recipient);
+    software_chip_transfer(&e, &client, &recipient, &owner, &chip, 3);
+    assert_eq!(client.owner_of(&token_id), owner);
+
+    // A second backup, or a key of another token, is rejected
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &backup, &admin, 3, ACTION_BACKUP);
+    let result = client.try_add_backup_key(
+        &token_id,
+        &backup_key,
+        &message,
+        &signature,
+        &recovery_id,
+        &3,
+    );
+    assert_eq!(
+        result.err().unwrap().unwrap(),
+        errors::NonFungibleTokenError::InvalidInput.into()
+    );
+    let other = software_chip(3);
+    let other_token_id = software_chip_mint(&e, &client, &admin, &other, 1);
+    let third = software_chip(4);
+    software_chip_mint(&e, &client, &admin, &third, 1);
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &third, &admin, 2, ACTION_BACKUP);
+    let result = client.try_add_backup_key(
+        &other_token_id,
+        &software_chip_public_key(&e, &third),
+        &message,
+        &signature,
+        &recovery_id,
+        &2,
+    );
+    assert_eq!(
+        result.err().unwrap().unwrap(),
+        errors::NonFungibleTokenError::TokenAlreadyMinted.into()
+    );
+
+    client.remove_backup_key(&token_id);
+    let events = e.events().all().filter_by_contract(&client.address);
+    assert_eq!(
+        events.events().last().unwrap(),
+        &events::BackupKeyRemoved {
+            token_id,
+            backup_key: backup_key.clone(),
+        }
+        .to_xdr(&e, &client.address)
+    );
+    assert_eq!(client.backup_key(&token_id), None);
+    let result = client.try_token_id(&backup_key.clone().into());
+    assert_eq!(
+        result.err().unwrap().unwrap(),
+        errors::NonFungibleTokenError::NonExistentToken.into()
+    );
+
+    // The removed backup chip is retired
+    assert_eq!(
+        client.try_admin_mint(&backup_key),
+        Err(Ok(errors::NonFungibleTokenError::ChipRetired.into()))
+    );
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &backup, &admin, 3, ACTION_BACKUP);
+    let result = client.try_add_backup_key(
+        &token_id,
+        &backup_key,
+        &message,
+        &signature,
+        &recovery_id,
+        &3,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::ChipRetired.into()))
+    );
+
+    // So is a chip rotated out
+    let replacement = software_chip(5);
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &replacement, &owner, 1, ACTION_ROTATE);
+    client.rotate_chip(
+        &token_id,
+        &software_chip_public_key(&e, &replacement),
+        &message,
+        &signature,
+        &recovery_id,
+        &1,
+    );
+    let (signature, recovery_id) = software_chip_sign(&e, &client, &chip, &admin, 4, ACTION_BACKUP);
+    let result = client.try_add_backup_key(
+        &token_id,
+        &software_chip_public_key(&e, &chip),
+        &message,
+        &signature,
+        &recovery_id,
+        &4,
+    );
+    assert_eq!(
+        result,
+        Err(Ok(errors::NonFungibleTokenError::ChipRetired.into()))
+    );
+}
+
+#[test]
+fn test_claimed_token_by_index() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let claimant = Address::generate(&e);
+    let client = create_client(&e, &admin);
+
+    // Minted but never claimed, skipped by the index
+    software_chip_mint(&e, &client, &admin, &software_chip(1), 1);
+    let second = software_chip_mint(&e, &client, &admin, &software_chip(2), 1);
+    let first = software_chip_mint(&e, &client, &admin, &software_chip(3), 1);
+    software_chip_claim(&e, &client, &claimant, &software_chip(3), 2);
+    software_chip_claim(&e, &client, &claimant, &software_chip(2), 2);
+
+    assert_eq!(client.claimed_count(), 2);
+    assert_eq!(client.claimed_token_by_index(&0), first);
+    assert_eq!(client.claimed_token_by_index(&1), second);
+    let result = client.try_claimed_token_by_index(&2);
+    assert_eq!(
+        result.err().unwrap().unwrap(),
+        errors::NonFungibleTokenError::IndexOutOfBounds.into()
+    );
+}
+
+#[test]
+fn test_first_token_id() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let client = deploy_client(&e, &admin, 2, false, 0, 1);
+    assert_eq!(client.first_token_id(), 1);
+
+    let token_id = software_chip_mint(&e, &client, &admin, &software_chip(1), 1);
+    assert_eq!(token_id, 1);
+    assert_eq!(
+        client.token_uri(&token_id),
+        String::from_str(&e, "ipfs://abcd/1")
+    );
+    assert_eq!(client.tokens(&0, &10), vec![&e, 1]);
+
+    // `max_tokens` is a count, so id 2 is the last one
+    assert_eq!(
+        software_chip_mint(&e, &client, &admin, &software_chip(2), 1),
+        2
+    );
+    assert_eq!(client.total_supply(), 2);
+    let (signature, recovery_id) =
+        software_chip_sign(&e, &client, &software_chip(3), &admin, 1, ACTION_MINT);
+    let result = client.try_mint(
+        &admin,
+        &Bytes::from_slice(&e, TEST_MESSAGE),
+        &signature,
+        &recovery_id,
+        &software_chip_public_key(&e, &software_chip(3)).into(),
+        &1,
+        &0,
+    );
+    assert_eq!(
+        result.err().unwrap().unwrap(),
+        errors::NonFungibleTokenError::TokenIDsAreDepleted.into()
+    );
+}
+
+#[test]
+fn test_budget() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let owner = Address::generate(&e);
+    let recipient = Address::generate(&e);
+    let client = create_client(&e, &admin);
+    let chip = software_chip(1);
+
+    let assert_within = |name: &str, cpu_limit: u64, mem_limit: u64| {
+        let mut budget = e.cost_estimate().budget();
+        let (cpu, mem) = (budget.cpu_instruction_cost(), budget.memory_bytes_cost());
+        assert!(cpu <= cpu_limit, "{name} used {cpu} CPU instructions");
+        assert!(mem <= mem_limit, "{name} used {mem} memory bytes");
+        budget.reset_default();
+    };
+
+    e.cost_estimate().budget().reset_default();
+    software_chip_mint(&e, &client, &admin, &chip, 1);
+    assert_within("mint", MINT_CPU_LIMIT, MINT_MEM_LIMIT);
+    software_chip_claim(&e, &client, &owner, &chip, 2);
+    assert_within("claim", CLAIM_CPU_LIMIT, CLAIM_MEM_LIMIT);
+    software_chip_transfer(&e, &client, &owner, &recipient, &chip, 3);
+    assert_within("transfer", TRANSFER_CPU_LIMIT, TRANSFER_MEM_LIMIT);
+}
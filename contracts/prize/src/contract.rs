@@ -4,11 +4,14 @@
 //! verification and NFT ownership via a bound NFC-NFT contract.
 
 use crate::{Prize, PrizeArgs, PrizeClient, PrizeTrait, errors, events, nfc_contract};
-use soroban_sdk::xdr::ToXdr;
 use soroban_sdk::{
-    Address, Bytes, BytesN, Env, contractimpl, contracttype, panic_with_error, token::TokenClient,
+    Address, Bytes, BytesN, Env, Symbol, contractimpl, contracttype, panic_with_error,
+    token::TokenClient,
 };
 
+/// Action discriminator of redeem signatures, see nfc-nft `consume_chip_signature`
+const ACTION_REDEEM: u32 = 3;
+
 #[contracttype]
 pub enum DataKey {
     Admin,
@@ -33,6 +36,12 @@ impl PrizeTrait for Prize {
         e.deployer().update_current_contract_wasm(wasm_hash);
     }
 
+    fn supports(e: &Env, interface: Symbol) -> bool {
+        ["chip_auth", "upgradeable"]
+            .iter()
+            .any(|name| Symbol::new(e, name) == interface)
+    }
+
     fn deposit(e: &Env, from: Address, amount: i128, nfc_contract: Address, token_id: u32) {
         from.require_auth();
 
@@ -70,17 +79,22 @@ impl PrizeTrait for Prize {
 
         let nfc_client = nfc_contract::Client::new(e, &nfc_contract);
 
-        let signer = redeemer.clone().to_xdr(e);
-        nfc_client.verify_chip_signature(
-            &signer,
+        // Consumes the chip nonce, a signature redeems a single time
+        let consumed = nfc_client.try_consume_chip_signature(
+            &redeemer,
             &message,
             &signature,
             &recovery_id,
-            &public_key,
+            &public_key.clone().into(),
             &nonce,
+            &ACTION_REDEEM,
+            &0,
         );
+        if !matches!(consumed, Ok(Ok(()))) {
+            panic_with_error!(&e, &errors::PrizeError::InvalidSignature);
+        }
 
-        let token_id = nfc_client.token_id(&public_key);
+        let token_id = nfc_client.token_id(&public_key.clone().into());
         let owner = nfc_client.owner_of(&token_id);
         if owner != redeemer {
             panic_with_error!(&e, &errors::PrizeError::NotChipOwner);
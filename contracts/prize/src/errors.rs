@@ -8,4 +8,7 @@ pub enum PrizeError {
     NoVaultForChip = 400,
     /// Redeemer is not the current owner of the NFT for this chip in the given NFC contract.
     NotChipOwner = 401,
+    /// Chip signature was rejected by the NFC contract (`consume_chip_signature` failed,
+    /// e.g. because its nonce was already used).
+    InvalidSignature = 402,
 }
@@ -7,10 +7,11 @@
 
 #![no_std]
 
-use soroban_sdk::{Address, Bytes, BytesN, Env, contract, contractmeta};
+use soroban_sdk::{Address, Bytes, BytesN, Env, Symbol, contract, contractmeta};
 
 contractmeta!(key = "Description", val = "ChimpDAO Prize");
 
+#[allow(clippy::too_many_arguments)]
 mod nfc_contract {
     soroban_sdk::contractimport!(file = "../nfc_nft.wasm");
 }
@@ -19,6 +20,7 @@ mod contract;
 mod errors;
 mod events;
 #[cfg(test)]
+#[allow(clippy::too_many_arguments)]
 mod test;
 
 #[contract]
@@ -37,6 +39,10 @@ pub trait PrizeTrait {
     /// Upgrade the contract to a new WASM build. Admin only.
     fn upgrade(e: &Env, wasm_hash: BytesN<32>);
 
+    /// Returns whether the contract exposes `interface`: `chip_auth` for
+    /// redemptions proven by chip signatures and `upgradeable`.
+    fn supports(e: &Env, interface: Symbol) -> bool;
+
     /// Deposit tokens for a specific prize campaign.
     ///
     /// Resolves `(nfc_contract, token_id)` to the chip public key via a cross-call to
@@ -64,7 +70,8 @@ pub trait PrizeTrait {
 
     /// Redeem locked token for a chip.
     ///
-    /// Verifies the chip signature via the given NFC contract, ensures the redeemer
+    /// Verifies the chip signature via the given NFC contract, consuming its nonce so
+    /// it cannot be replayed against later deposits, ensures the redeemer
     /// is the current owner of the NFT for that chip, then transfers the locked amount
     /// to the redeemer and sets the lock balance to zero.
     ///
@@ -77,12 +84,12 @@ pub trait PrizeTrait {
     /// * `signature` - 64-byte ECDSA signature from the chip.
     /// * `recovery_id` - Recovery ID (0–3) for signature recovery.
     /// * `public_key` - Chip public key (uncompressed SEC1, 65 bytes).
-    /// * `nonce` - Nonce used in the signed payload.
+    /// * `nonce` - Nonce used in the signed payload, signed with action `3` (redeem).
     ///
     /// # Panics
     ///
     /// * If the redeemer does not authorize.
-    /// * If the chip signature is invalid (via NFC contract).
+    /// * If the chip signature is invalid or was already used ([`errors::PrizeError::InvalidSignature`]).
     /// * If the redeemer is not the owner of the NFT for this chip ([`errors::PrizeError::NotChipOwner`]).
     /// * If there is no locked amount for this chip ([`errors::PrizeError::NoLockForChip`]).
     ///
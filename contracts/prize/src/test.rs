@@ -4,10 +4,11 @@ extern crate std;
 
 use soroban_sdk::token;
 use soroban_sdk::{
-    Address, Bytes, BytesN, Env, contract, contractimpl, contracttype, testutils::Address as _,
+    Address, Bytes, BytesN, Env, Symbol, contract, contractimpl, contracttype,
+    testutils::Address as _,
 };
 
-use crate::{Prize, PrizeClient};
+use crate::{Prize, PrizeClient, errors};
 
 // Fixed chip public key returned by MockNfc for token_id 0 (65 bytes, uncompressed SEC1)
 const MOCK_CHIP_PUBLIC_KEY: [u8; 65] = [
@@ -18,7 +19,7 @@ const MOCK_CHIP_PUBLIC_KEY: [u8; 65] = [
     0x00,
 ];
 
-// ---------- Mock NFC: no-op verify, fixed public_key/token_id/owner ----------
+// ---------- Mock NFC: accepts any signature with the next nonce, fixed public_key/token_id/owner ----------
 
 #[contract]
 pub struct MockNfc;
@@ -26,6 +27,7 @@ pub struct MockNfc;
 #[contracttype]
 enum MockNfcDataKey {
     Owner,
+    Nonce,
 }
 
 #[contractimpl]
@@ -38,18 +40,30 @@ impl MockNfc {
         BytesN::from_array(_e, &MOCK_CHIP_PUBLIC_KEY)
     }
 
-    pub fn verify_chip_signature(
-        _e: &Env,
-        _signer: Bytes,
+    pub fn consume_chip_signature(
+        e: &Env,
+        signer: Address,
         _message: Bytes,
         _signature: BytesN<64>,
         _recovery_id: u32,
-        _public_key: BytesN<65>,
-        _nonce: u32,
+        _public_key: Bytes,
+        nonce: u32,
+        _action: u32,
+        _expiration_ledger: u32,
     ) {
+        signer.require_auth();
+        let stored_nonce: u32 = e
+            .storage()
+            .instance()
+            .get(&MockNfcDataKey::Nonce)
+            .unwrap_or(0);
+        if nonce != stored_nonce + 1 {
+            panic!("nonce already used");
+        }
+        e.storage().instance().set(&MockNfcDataKey::Nonce, &nonce);
     }
 
-    pub fn token_id(_e: &Env, _public_key: BytesN<65>) -> u32 {
+    pub fn token_id(_e: &Env, _public_key: Bytes) -> u32 {
         0u32
     }
 
@@ -125,3 +139,60 @@ fn test_deposit_redeem() {
 
     prize.deposit(&depositor, &100_i128, &mock_nfc, &0u32);
 }
+
+#[test]
+fn test_supports() {
+    let e = Env::default();
+    let admin = Address::generate(&e);
+    let token = Address::generate(&e);
+    let prize_id = e.register(Prize, (admin, token));
+    let prize = PrizeClient::new(&e, &prize_id);
+
+    assert!(prize.supports(&Symbol::new(&e, "chip_auth")));
+    assert!(prize.supports(&Symbol::new(&e, "upgradeable")));
+    assert!(!prize.supports(&Symbol::new(&e, "burnable")));
+}
+
+#[test]
+fn test_redeem_replay() {
+    let e = Env::default();
+    e.mock_all_auths();
+
+    let admin = Address::generate(&e);
+    let redeemer = Address::generate(&e);
+    let depositor = Address::generate(&e);
+
+    let token = setup_stellar_asset_and_fund(&e, &depositor, 1000_i128);
+    let mock_nfc = e.register(MockNfc, ());
+    MockNfcClient::new(&e, &mock_nfc).set_owner(&redeemer);
+
+    let prize_id = e.register(Prize, (admin.clone(), token.clone()));
+    let prize = PrizeClient::new(&e, &prize_id);
+    let token_client = token::TokenClient::new(&e, &token);
+
+    let chip_pk = BytesN::from_array(&e, &MOCK_CHIP_PUBLIC_KEY);
+    let message = Bytes::from_slice(&e, b"dummy");
+    let signature = BytesN::from_array(&e, &[0u8; 64]);
+
+    prize.deposit(&depositor, &100_i128, &mock_nfc, &0u32);
+    prize.redeem(
+        &redeemer, &mock_nfc, &message, &signature, &0u32, &chip_pk, &1u32,
+    );
+    assert_eq!(token_client.balance(&redeemer), 100);
+
+    // The tap signature cannot redeem the next deposit
+    prize.deposit(&depositor, &50_i128, &mock_nfc, &0u32);
+    assert_eq!(
+        prize.try_redeem(
+            &redeemer, &mock_nfc, &message, &signature, &0u32, &chip_pk, &1u32,
+        ),
+        Err(Ok(errors::PrizeError::InvalidSignature.into()))
+    );
+    assert_eq!(prize.get_redeemable(&chip_pk), 50);
+
+    // A fresh signature can
+    prize.redeem(
+        &redeemer, &mock_nfc, &message, &signature, &0u32, &chip_pk, &2u32,
+    );
+    assert_eq!(token_client.balance(&redeemer), 150);
+}
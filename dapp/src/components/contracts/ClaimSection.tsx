@@ -137,7 +137,7 @@ export const ClaimSection = ({ keyId, contractId }: ClaimSectionProps) => {
         currentNonce = 0;
       }
 
-      // Use next nonce (must be greater than stored)
+      // Use next nonce (must be exactly stored + 1)
       const nonce = currentNonce + 1;
 
       // Create SEP-53 message for claim (signer = claimant = wallet address)
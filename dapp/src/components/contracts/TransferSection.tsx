@@ -161,7 +161,7 @@ export const TransferSection = ({
         currentNonce = 0;
       }
 
-      // Use next nonce (must be greater than stored)
+      // Use next nonce (must be exactly stored + 1)
       const nonce = currentNonce + 1;
 
       // Create SEP-53 message for transfer (signer = from = wallet address)
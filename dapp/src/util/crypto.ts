@@ -46,10 +46,21 @@ export async function sha256(data: string | Uint8Array): Promise<Uint8Array> {
   return new Uint8Array(hashBuffer);
 }
 
+/**
+ * Action discriminators hashed by nfc-nft check_chip_signature as a single byte.
+ */
+const CHIP_ACTIONS: Record<string, number> = {
+  mint: 0,
+  claim: 1,
+  transfer: 2,
+  rotate: 4,
+};
+
 /**
  * Create SEP-53 compliant auth message (without signer and nonce).
- * The contract reconstructs the signed payload as message || signer_xdr || nonce_xdr.
- * Returns the logical message (without signer and nonce) and the hash of (message || signer_xdr || nonce_xdr)
+ * The contract reconstructs the signed payload as
+ * message || signer_xdr || nonce_xdr || action || contract_xdr || network_id.
+ * Returns the logical message (without signer and nonce) and the hash of that payload
  * for the chip to sign (matches contract verification).
  *
  * @param signerAddress - Stellar account address that authorizes the call (admin for mint, claimant for claim, from for transfer)
@@ -111,13 +122,39 @@ export async function createSEP53Message(
   view.setUint32(0, 3, false);
   view.setUint32(4, nonce, false);
 
-  // Signed payload: message || signer_xdr || nonce_xdr (matches contract)
+  // Contract as Soroban ScVal::Address XDR bytes (same as contract's current_contract_address().to_xdr)
+  const contractXdrOutput = Address.contract(
+    Buffer.from(contractIdBytes),
+  )
+    .toScVal()
+    .toXDR("raw");
+  const contractXdrBytes =
+    typeof contractXdrOutput === "string"
+      ? new Uint8Array(Buffer.from(contractXdrOutput, "base64"))
+      : new Uint8Array(contractXdrOutput);
+
+  const action = CHIP_ACTIONS[functionName];
+  if (action === undefined) {
+    throw new Error(`No chip action for function "${functionName}".`);
+  }
+
+  // Signed payload: message || signer_xdr || nonce_xdr || action || contract_xdr || network_id (matches contract)
+  const signedParts = [
+    message,
+    signerXdrBytes,
+    nonceXdrBytes,
+    new Uint8Array([action]),
+    contractXdrBytes,
+    networkHash,
+  ];
   const signedPayload = new Uint8Array(
-    message.length + signerXdrBytes.length + nonceXdrBytes.length,
+    signedParts.reduce((sum, part) => sum + part.length, 0),
   );
-  signedPayload.set(message, 0);
-  signedPayload.set(signerXdrBytes, message.length);
-  signedPayload.set(nonceXdrBytes, message.length + signerXdrBytes.length);
+  let signedOffset = 0;
+  for (const part of signedParts) {
+    signedPayload.set(part, signedOffset);
+    signedOffset += part.length;
+  }
 
   const messageHash = await sha256(signedPayload);
 
@@ -128,8 +165,9 @@ export async function createSEP53Message(
 }
 
 /**
- * Build the exact signed payload used by nfc-nft verify_chip_signature:
+ * Build the chip signed payload without domain separation:
  * message_bytes || signer.to_xdr() || nonce.to_xdr(), then SHA-256.
+ * nfc-nft check_chip_signature additionally binds the contract and network.
  * Use this for arbitrary message signing (e.g. Sign tab) so the chip signs
  * the same hash the contract will verify.
  *